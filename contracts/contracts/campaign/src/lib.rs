@@ -0,0 +1,618 @@
+#![no_std]
+
+//! # Campaign Contract
+//!
+//! Points and achievement badges for the Stellars Finance testnet campaign. Records
+//! per-address activity milestones and exposes them as queryable badges.
+//!
+//! ## Key Features
+//! - **Protocol Hooks**: PositionManager reports opened positions and liquidations, the
+//!   LiquidityPool reports deposits; milestones are awarded as they are reached
+//! - **Keeper Attestation**: Admin-approved attesters can award any badge directly, for
+//!   activity the protocol hooks can't observe
+//! - **Badges and Points**: Each badge is earned once and is worth a fixed number of points
+//! - **Trading Competitions**: PositionManager reports realized PnL, accumulated per trader
+//!   per epoch with a top 10 leaderboard for each epoch
+//!
+//! ## Milestones
+//! - `FirstTrade`: first position opened
+//! - `TenTrades`: tenth position opened
+//! - `LiquidityProvider`: cumulative LP deposits reach the configured threshold
+//! - `LiquidationSurvivor`: opened a position again after being liquidated
+//!
+//! ## Integration
+//! Enabled by registering this contract with `ConfigManager::set_campaign()`. The hooks
+//! are best-effort: a failing hook never blocks the trade or deposit that triggered it.
+//!
+//! ## Usage
+//! - PositionManager calls `record_trade()`, `record_liquidation()` and `record_pnl()`
+//! - LiquidityPool calls `record_deposit()`
+//! - Attesters call `attest()`; frontends read `get_activity()` and `has_badge()`
+//! - The admin starts a competition with `set_competition_epoch()`; frontends read
+//!   `get_epoch_pnl()` and `get_epoch_leaderboard()`
+
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, panic_with_error, Address, Env, Vec,
+};
+
+pub use errors::CampaignError;
+
+mod config_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
+}
+
+/// Positions opened for the `TenTrades` badge
+const TEN_TRADES: u32 = 10;
+
+/// Traders ranked on each epoch leaderboard
+const LEADERBOARD_SIZE: u32 = 10;
+
+// Data Structures
+
+/// Campaign achievement
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Milestone {
+    FirstTrade,
+    TenTrades,
+    LiquidityProvider,
+    LiquidationSurvivor,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Badge {
+    pub milestone: Milestone,
+    pub earned_at: u64,
+}
+
+/// Campaign activity of an address
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Activity {
+    pub trades: u32,       // Positions opened
+    pub liquidations: u32, // Positions liquidated
+    pub deposited: i128,   // Cumulative LP deposits
+    pub points: u32,       // Sum of the points of every badge earned
+    pub badges: Vec<Badge>,
+}
+
+/// A trader's realized PnL on an epoch leaderboard
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub trader: Address,
+    pub pnl: i128, // Realized PnL in the epoch, net of fees
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    ConfigManager,
+    LpThreshold,            // Cumulative deposits for the LiquidityProvider badge
+    Attester(Address),      // bool: may award badges directly
+    Activity(Address),      // Account -> Activity
+    CompetitionEpochLength, // u64: seconds per competition epoch (0 = not tracked)
+    EpochPnl(Address, u64), // (Trader, epoch) -> realized PnL in the epoch
+    EpochLeaderboard(u64),  // Epoch -> Vec<LeaderboardEntry>, best first
+}
+
+// Events
+
+#[contractevent]
+pub struct BadgeEarnedEvent {
+    pub account: Address,
+    pub milestone: Milestone,
+    pub points: u32,
+    pub attester: Option<Address>, // None when awarded by a protocol hook
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct AttesterSetEvent {
+    pub attester: Address,
+    pub allowed: bool,
+    pub seq: u64,
+}
+
+// Helper Functions
+
+fn get_config_manager(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::ConfigManager)
+        .unwrap_or_else(|| panic_with_error!(env, CampaignError::NotInitialized))
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_manager(env))
+}
+
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+    let stored_admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| panic_with_error!(env, CampaignError::NotInitialized));
+    if admin != &stored_admin {
+        panic_with_error!(env, CampaignError::Unauthorized);
+    }
+}
+
+/// Only the registered PositionManager may report trading activity
+fn require_position_manager(env: &Env, reporter: &Address) {
+    reporter.require_auth();
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    if reporter != &config_client.position_manager() {
+        panic_with_error!(env, CampaignError::UnauthorizedReporter);
+    }
+}
+
+/// Only the registered LiquidityPool may report deposits
+fn require_liquidity_pool(env: &Env, reporter: &Address) {
+    reporter.require_auth();
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    if reporter != &config_client.liquidity_pool() {
+        panic_with_error!(env, CampaignError::UnauthorizedReporter);
+    }
+}
+
+/// Points a badge is worth
+fn milestone_points(milestone: Milestone) -> u32 {
+    match milestone {
+        Milestone::FirstTrade => 100,
+        Milestone::TenTrades => 500,
+        Milestone::LiquidityProvider => 500,
+        Milestone::LiquidationSurvivor => 250,
+    }
+}
+
+fn get_activity(env: &Env, account: &Address) -> Activity {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Activity(account.clone()))
+        .unwrap_or(Activity {
+            trades: 0,
+            liquidations: 0,
+            deposited: 0,
+            points: 0,
+            badges: Vec::new(env),
+        })
+}
+
+fn set_activity(env: &Env, account: &Address, activity: &Activity) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Activity(account.clone()), activity);
+}
+
+fn has_badge(activity: &Activity, milestone: Milestone) -> bool {
+    activity
+        .badges
+        .iter()
+        .any(|badge| badge.milestone == milestone)
+}
+
+/// Add a badge and its points unless the account already holds it.
+///
+/// # Returns
+/// True if the badge was newly earned
+fn award(
+    env: &Env,
+    account: &Address,
+    activity: &mut Activity,
+    milestone: Milestone,
+    attester: Option<Address>,
+) -> bool {
+    if has_badge(activity, milestone) {
+        return false;
+    }
+
+    let points = milestone_points(milestone);
+    activity.badges.push_back(Badge {
+        milestone,
+        earned_at: env.ledger().timestamp(),
+    });
+    activity.points += points;
+
+    BadgeEarnedEvent {
+        account: account.clone(),
+        milestone,
+        points,
+        attester,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+
+    true
+}
+
+/// Seconds per competition epoch (0 while no competition runs)
+fn get_competition_epoch_length(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::CompetitionEpochLength)
+        .unwrap_or(0)
+}
+
+/// Get a trader's realized PnL in an epoch
+fn get_epoch_pnl(env: &Env, trader: &Address, epoch: u64) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EpochPnl(trader.clone(), epoch))
+        .unwrap_or(0)
+}
+
+/// Get an epoch's leaderboard, best first
+fn get_epoch_leaderboard(env: &Env, epoch: u64) -> Vec<LeaderboardEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EpochLeaderboard(epoch))
+        .unwrap_or(Vec::new(env))
+}
+
+#[contract]
+pub struct Campaign;
+
+#[contractimpl]
+impl Campaign {
+    /// Initialize the campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address (must authorize)
+    /// * `config_manager` - Address of the ConfigManager contract
+    /// * `lp_threshold` - Cumulative LP deposits that earn the LiquidityProvider badge
+    ///
+    /// # Panics
+    ///
+    /// Panics if already initialized or the threshold is not positive
+    pub fn initialize(env: Env, admin: Address, config_manager: Address, lp_threshold: i128) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, CampaignError::AlreadyInitialized);
+        }
+        admin.require_auth();
+        if lp_threshold <= 0 {
+            panic_with_error!(&env, CampaignError::InvalidThreshold);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ConfigManager, &config_manager);
+        env.storage()
+            .instance()
+            .set(&DataKey::LpThreshold, &lp_threshold);
+    }
+
+    /// Update the cumulative LP deposits that earn the LiquidityProvider badge. Badges
+    /// already earned are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `lp_threshold` - The new threshold
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the threshold is not positive
+    pub fn set_lp_threshold(env: Env, admin: Address, lp_threshold: i128) {
+        require_admin(&env, &admin);
+        if lp_threshold <= 0 {
+            panic_with_error!(&env, CampaignError::InvalidThreshold);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::LpThreshold, &lp_threshold);
+    }
+
+    /// Get the cumulative LP deposits that earn the LiquidityProvider badge.
+    ///
+    /// # Returns
+    ///
+    /// The LP threshold
+    pub fn lp_threshold(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::LpThreshold)
+            .unwrap_or_else(|| panic_with_error!(&env, CampaignError::NotInitialized))
+    }
+
+    /// Allow or revoke an attester (e.g. a campaign keeper) awarding badges directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `attester` - The attester address
+    /// * `allowed` - True to allow, false to revoke
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_attester(env: Env, admin: Address, attester: Address, allowed: bool) {
+        require_admin(&env, &admin);
+        let key = DataKey::Attester(attester.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        AttesterSetEvent {
+            attester,
+            allowed,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check whether an address may award badges directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `attester` - The address to check
+    ///
+    /// # Returns
+    ///
+    /// True if the address is an allowed attester
+    pub fn is_attester(env: Env, attester: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Attester(attester))
+            .unwrap_or(false)
+    }
+
+    /// Record a position opened by a trader. Awards FirstTrade, TenTrades and, after a
+    /// liquidation, LiquidationSurvivor.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The PositionManager (must authorize)
+    /// * `trader` - The trader who opened the position
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reporter is not the PositionManager
+    pub fn record_trade(env: Env, reporter: Address, trader: Address) {
+        require_position_manager(&env, &reporter);
+
+        let mut activity = get_activity(&env, &trader);
+        activity.trades += 1;
+
+        award(&env, &trader, &mut activity, Milestone::FirstTrade, None);
+        if activity.trades >= TEN_TRADES {
+            award(&env, &trader, &mut activity, Milestone::TenTrades, None);
+        }
+        if activity.liquidations > 0 {
+            award(
+                &env,
+                &trader,
+                &mut activity,
+                Milestone::LiquidationSurvivor,
+                None,
+            );
+        }
+
+        set_activity(&env, &trader, &activity);
+    }
+
+    /// Record a liquidated position.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The PositionManager (must authorize)
+    /// * `trader` - The owner of the liquidated position
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reporter is not the PositionManager
+    pub fn record_liquidation(env: Env, reporter: Address, trader: Address) {
+        require_position_manager(&env, &reporter);
+
+        let mut activity = get_activity(&env, &trader);
+        activity.liquidations += 1;
+        set_activity(&env, &trader, &activity);
+    }
+
+    /// Record an LP deposit. Awards LiquidityProvider once cumulative deposits reach
+    /// the threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The LiquidityPool (must authorize)
+    /// * `user` - The depositor
+    /// * `amount` - Tokens deposited
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reporter is not the LiquidityPool
+    pub fn record_deposit(env: Env, reporter: Address, user: Address, amount: i128) {
+        require_liquidity_pool(&env, &reporter);
+
+        let mut activity = get_activity(&env, &user);
+        activity.deposited += amount;
+        if activity.deposited >= Self::lp_threshold(env.clone()) {
+            award(
+                &env,
+                &user,
+                &mut activity,
+                Milestone::LiquidityProvider,
+                None,
+            );
+        }
+        set_activity(&env, &user, &activity);
+    }
+
+    /// Record PnL realized by a trader in the current competition epoch and re-rank
+    /// them on its leaderboard. Ignored while no competition runs.
+    ///
+    /// The leaderboard is maintained on write: a trader is re-ranked only when they
+    /// realize PnL, so one who drops out of the top 10 returns once a later trade ranks
+    /// them again.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The PositionManager (must authorize)
+    /// * `trader` - The trader who realized the PnL
+    /// * `pnl` - Realized PnL net of fees
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reporter is not the PositionManager
+    pub fn record_pnl(env: Env, reporter: Address, trader: Address, pnl: i128) {
+        require_position_manager(&env, &reporter);
+
+        let epoch_length = get_competition_epoch_length(&env);
+        if epoch_length == 0 || pnl == 0 {
+            return;
+        }
+
+        let epoch = env.ledger().timestamp() / epoch_length;
+        let epoch_pnl = get_epoch_pnl(&env, &trader, epoch) + pnl;
+        env.storage()
+            .persistent()
+            .set(&DataKey::EpochPnl(trader.clone(), epoch), &epoch_pnl);
+
+        let mut leaderboard = get_epoch_leaderboard(&env, epoch);
+        if let Some(index) = leaderboard.iter().position(|entry| entry.trader == trader) {
+            leaderboard.remove(index as u32);
+        }
+        let rank = leaderboard
+            .iter()
+            .position(|entry| entry.pnl < epoch_pnl)
+            .unwrap_or(leaderboard.len() as usize) as u32;
+        if rank < LEADERBOARD_SIZE {
+            leaderboard.insert(
+                rank,
+                LeaderboardEntry {
+                    trader,
+                    pnl: epoch_pnl,
+                },
+            );
+            if leaderboard.len() > LEADERBOARD_SIZE {
+                leaderboard.pop_back();
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::EpochLeaderboard(epoch), &leaderboard);
+    }
+
+    /// Award a badge on an attester's word, for activity the protocol hooks can't see.
+    ///
+    /// # Arguments
+    ///
+    /// * `attester` - An allowed attester (must authorize)
+    /// * `account` - The account earning the badge
+    /// * `milestone` - The badge to award
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not an allowed attester or the account already holds
+    /// the badge
+    pub fn attest(env: Env, attester: Address, account: Address, milestone: Milestone) {
+        attester.require_auth();
+        if !Self::is_attester(env.clone(), attester.clone()) {
+            panic_with_error!(&env, CampaignError::UnauthorizedAttester);
+        }
+
+        let mut activity = get_activity(&env, &account);
+        if !award(&env, &account, &mut activity, milestone, Some(attester)) {
+            panic_with_error!(&env, CampaignError::BadgeAlreadyEarned);
+        }
+        set_activity(&env, &account, &activity);
+    }
+
+    /// Get an account's campaign activity, badges and points.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account address
+    ///
+    /// # Returns
+    ///
+    /// The account's Activity (all zero with no badges if it never took part)
+    pub fn get_activity(env: Env, account: Address) -> Activity {
+        get_activity(&env, &account)
+    }
+
+    /// Check whether an account holds a badge.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account address
+    /// * `milestone` - The badge
+    ///
+    /// # Returns
+    ///
+    /// True if the account has earned the badge
+    pub fn has_badge(env: Env, account: Address, milestone: Milestone) -> bool {
+        has_badge(&get_activity(&env, &account), milestone)
+    }
+
+    /// Start, re-time or stop a trading competition.
+    ///
+    /// Realized PnL is accumulated per trader per epoch, where epoch =
+    /// timestamp / `epoch_length`. Changing the length re-numbers the epochs, so it
+    /// should only change between competitions.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `epoch_length` - Seconds per epoch (0 stops tracking)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_competition_epoch(env: Env, admin: Address, epoch_length: u64) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::CompetitionEpochLength, &epoch_length);
+    }
+
+    /// Get the current competition epoch.
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (epoch, epoch_length); (0, 0) while no competition runs
+    pub fn current_epoch(env: Env) -> (u64, u64) {
+        let epoch_length = get_competition_epoch_length(&env);
+        if epoch_length == 0 {
+            return (0, 0);
+        }
+        (env.ledger().timestamp() / epoch_length, epoch_length)
+    }
+
+    /// Get a trader's realized PnL in a competition epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - The trader address
+    /// * `epoch` - The epoch number
+    ///
+    /// # Returns
+    ///
+    /// Realized PnL net of fees, in token base units (0 if none)
+    pub fn get_epoch_pnl(env: Env, trader: Address, epoch: u64) -> i128 {
+        get_epoch_pnl(&env, &trader, epoch)
+    }
+
+    /// Get the top traders of a competition epoch by realized PnL.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch number
+    ///
+    /// # Returns
+    ///
+    /// Up to 10 entries, best first
+    pub fn get_epoch_leaderboard(env: Env, epoch: u64) -> Vec<LeaderboardEntry> {
+        get_epoch_leaderboard(&env, epoch)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,222 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+struct TestEnv<'a> {
+    campaign_client: CampaignClient<'a>,
+    admin: Address,
+    position_manager: Address,
+    liquidity_pool: Address,
+    trader: Address,
+}
+
+/// Campaign with a 1,000 token LP threshold, reported to by stand-in protocol contracts
+fn setup_test_environment(env: &Env) -> TestEnv<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let position_manager = Address::generate(env);
+    let liquidity_pool = Address::generate(env);
+    let trader = Address::generate(env);
+
+    let config_id = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(env, &config_id);
+    config_client.set_position_manager(&admin, &position_manager);
+    config_client.set_liquidity_pool(&admin, &liquidity_pool);
+
+    let campaign_id = env.register(Campaign, ());
+    let campaign_client = CampaignClient::new(env, &campaign_id);
+    campaign_client.initialize(&admin, &config_id, &10_000_000_000i128);
+
+    TestEnv {
+        campaign_client,
+        admin,
+        position_manager,
+        liquidity_pool,
+        trader,
+    }
+}
+
+#[test]
+fn test_trade_milestones() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    t.campaign_client
+        .record_trade(&t.position_manager, &t.trader);
+    assert!(t
+        .campaign_client
+        .has_badge(&t.trader, &Milestone::FirstTrade));
+    assert!(!t
+        .campaign_client
+        .has_badge(&t.trader, &Milestone::TenTrades));
+
+    for _ in 1..10 {
+        t.campaign_client
+            .record_trade(&t.position_manager, &t.trader);
+    }
+    let activity = t.campaign_client.get_activity(&t.trader);
+    assert_eq!(activity.trades, 10);
+    assert_eq!(activity.badges.len(), 2);
+    assert_eq!(activity.points, 600);
+
+    // Trading on after a liquidation earns the survivor badge
+    t.campaign_client
+        .record_liquidation(&t.position_manager, &t.trader);
+    assert!(!t
+        .campaign_client
+        .has_badge(&t.trader, &Milestone::LiquidationSurvivor));
+    t.campaign_client
+        .record_trade(&t.position_manager, &t.trader);
+    let activity = t.campaign_client.get_activity(&t.trader);
+    assert_eq!(activity.liquidations, 1);
+    assert_eq!(activity.points, 850);
+}
+
+#[test]
+fn test_lp_milestone_on_cumulative_deposits() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    t.campaign_client
+        .record_deposit(&t.liquidity_pool, &t.trader, &6_000_000_000i128);
+    assert!(!t
+        .campaign_client
+        .has_badge(&t.trader, &Milestone::LiquidityProvider));
+
+    t.campaign_client
+        .record_deposit(&t.liquidity_pool, &t.trader, &4_000_000_000i128);
+    assert!(t
+        .campaign_client
+        .has_badge(&t.trader, &Milestone::LiquidityProvider));
+    assert_eq!(t.campaign_client.get_activity(&t.trader).points, 500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #904)")]
+fn test_record_trade_unauthorized_reporter() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    // Deposits are reported by the pool, trades only by the PositionManager
+    t.campaign_client.record_trade(&t.liquidity_pool, &t.trader);
+}
+
+#[test]
+fn test_attester_awards_badge() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+    let attester = Address::generate(&env);
+
+    t.campaign_client.set_attester(&t.admin, &attester, &true);
+    assert!(t.campaign_client.is_attester(&attester));
+
+    t.campaign_client
+        .attest(&attester, &t.trader, &Milestone::LiquidationSurvivor);
+    assert!(t
+        .campaign_client
+        .has_badge(&t.trader, &Milestone::LiquidationSurvivor));
+
+    // Already earned
+    assert!(t
+        .campaign_client
+        .try_attest(&attester, &t.trader, &Milestone::LiquidationSurvivor)
+        .is_err());
+
+    t.campaign_client.set_attester(&t.admin, &attester, &false);
+    assert!(t
+        .campaign_client
+        .try_attest(&attester, &t.trader, &Milestone::FirstTrade)
+        .is_err());
+}
+
+#[test]
+fn test_epoch_pnl_and_leaderboard() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+    let trader2 = Address::generate(&env);
+
+    // Ignored until a competition runs
+    t.campaign_client
+        .record_pnl(&t.position_manager, &t.trader, &1_000);
+    assert_eq!(t.campaign_client.current_epoch(), (0, 0));
+    assert_eq!(t.campaign_client.get_epoch_pnl(&t.trader, &0), 0);
+
+    // Daily epochs
+    t.campaign_client
+        .set_competition_epoch(&t.admin, &86_400u64);
+    let (epoch, epoch_length) = t.campaign_client.current_epoch();
+    assert_eq!(epoch_length, 86_400);
+
+    t.campaign_client
+        .record_pnl(&t.position_manager, &t.trader, &500);
+    t.campaign_client
+        .record_pnl(&t.position_manager, &trader2, &-200);
+    assert_eq!(t.campaign_client.get_epoch_pnl(&t.trader, &epoch), 500);
+    assert_eq!(t.campaign_client.get_epoch_pnl(&trader2, &epoch), -200);
+    let leaderboard = t.campaign_client.get_epoch_leaderboard(&epoch);
+    assert_eq!(leaderboard.len(), 2);
+    assert_eq!(leaderboard.get(0).unwrap().trader, t.trader);
+    assert_eq!(leaderboard.get(1).unwrap().trader, trader2);
+
+    // A bigger win in the same epoch moves trader2 to the top
+    t.campaign_client
+        .record_pnl(&t.position_manager, &trader2, &1_000);
+    let leaderboard = t.campaign_client.get_epoch_leaderboard(&epoch);
+    assert_eq!(leaderboard.len(), 2);
+    assert_eq!(leaderboard.get(0).unwrap().trader, trader2);
+    assert_eq!(leaderboard.get(0).unwrap().pnl, 800);
+
+    // The next epoch starts empty
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(t.campaign_client.current_epoch().0, epoch + 1);
+    assert_eq!(t.campaign_client.get_epoch_pnl(&t.trader, &(epoch + 1)), 0);
+    assert_eq!(
+        t.campaign_client.get_epoch_leaderboard(&(epoch + 1)).len(),
+        0
+    );
+}
+
+#[test]
+fn test_leaderboard_keeps_top_ten() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+    t.campaign_client
+        .set_competition_epoch(&t.admin, &86_400u64);
+    let (epoch, _) = t.campaign_client.current_epoch();
+
+    let mut traders = Vec::new(&env);
+    for i in 1..=11i128 {
+        let trader = Address::generate(&env);
+        t.campaign_client
+            .record_pnl(&t.position_manager, &trader, &(i * 100));
+        traders.push_back(trader);
+    }
+
+    // The smallest win drops off; the biggest leads
+    let leaderboard = t.campaign_client.get_epoch_leaderboard(&epoch);
+    assert_eq!(leaderboard.len(), 10);
+    assert_eq!(leaderboard.get(0).unwrap().trader, traders.get(10).unwrap());
+    assert!(leaderboard
+        .iter()
+        .all(|entry| entry.trader != traders.get(0).unwrap()));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #904)")]
+fn test_record_pnl_unauthorized_reporter() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+    t.campaign_client
+        .set_competition_epoch(&t.admin, &86_400u64);
+
+    t.campaign_client
+        .record_pnl(&t.liquidity_pool, &t.trader, &1_000);
+}
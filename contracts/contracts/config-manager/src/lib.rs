@@ -7,12 +7,41 @@
 //!
 //! ## Key Features
 //! - **Contract Registry**: Stores addresses of all protocol contracts (LiquidityPool,
-//!   PositionManager, MarketManager, OracleIntegrator, Token, DIA/Reflector oracles)
-//! - **Trading Parameters**: Min/max leverage (default 5-20x), minimum position size
+//!   PositionManager, OrderManager, MarketManager, OracleIntegrator, Token, optional
+//!   execution fee token, DIA/Reflector oracles, KeeperRegistry, KeeperRewards, insurance
+//!   fund, Campaign);
+//!   `is_protocol_contract()` reports which core role an address is registered for
+//! - **Trading Parameters**: Min/max leverage (default 5-20x) with optional per-market
+//!   overrides, minimum position size (in collateral token units, plus an optional USD
+//!   floor priced through the oracle), optional per-market risk tiers raising the
+//!   initial margin of larger positions
 //! - **Fee Parameters**: Maker fee, taker fee, liquidation fee (all in basis points)
 //! - **Risk Parameters**: Liquidation threshold, maintenance margin, max price deviation
-//! - **Time Parameters**: Funding interval (60s), price staleness threshold
+//! - **Time Parameters**: Funding interval (60s), price staleness threshold with optional
+//!   per-market overrides, max market pause duration before stale positions can be
+//!   force-settled (7 days)
 //! - **Liquidity Parameters**: Max utilization ratio (80%), min reserve ratio (20%)
+//! - **Integrator Allow-List**: External contracts (vaults, copy-trading) permitted to
+//!   open positions on behalf of users
+//! - **Frontend Fee Share**: Registered third-party frontends earn a configurable share
+//!   of the fees charged on positions opened through them
+//! - **Permissioned Keepers**: Setting a KeeperRegistry restricts keeper actions to bonded
+//!   keepers; leaving it unset keeps keeping permissionless
+//! - **Compliance Hook**: Optional (disabled by default) admin-managed blocklist and/or
+//!   external allowlist contract, checked on position opens and LP deposits/withdrawals
+//! - **Global Pause**: Halts new exposure protocol-wide; replacing the token or oracle
+//!   requires the pause and zero open interest, unless explicitly forced
+//! - **Introspection**: `list_config_keys()` describes every global parameter (getter,
+//!   value type, bounds and setter) so tooling doesn't hard-code the catalog
+//! - **Batch Updates**: `set_config_batch()` validates a set of global parameter changes
+//!   as a whole and applies them all or none
+//! - **Named Parameters**: Admin-set u32, bool and Address values under arbitrary
+//!   symbols, each type in its own namespace, for parameters without a dedicated key
+//! - **Network Profile**: Testnet, futurenet or mainnet, chosen at initialization;
+//!   on mainnet the oracle test mode, faucet minting and testnet campaigns refuse to
+//!   enable so test hooks can't leak into production
+//! - **Storage Usage**: `get_storage_usage()` counts global parameters, per-market
+//!   overrides and named parameters, for monitoring rent exposure
 //!
 //! ## Access Control
 //! All configuration changes require admin authorization. The admin can be transferred
@@ -23,40 +52,347 @@
 //! and resolve contract addresses. This creates a single source of truth for all
 //! protocol settings.
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, panic_with_error, vec, Address, Env,
+    IntoVal, Symbol, Vec,
+};
+
+pub use errors::ConfigError;
 
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Admin,
-    // Contract Registry
-    LiquidityPoolContract,
-    PositionManagerContract,
-    MarketManagerContract,
-    OracleIntegratorContract,
-    DiaOracleContract,
-    ReflectorOracleContract,
-    TokenContract,
+    NetworkProfile, // Fixed at initialization (unset on older deployments = Testnet)
     // Trading parameters
     MinLeverage,
     MaxLeverage,
     MinPositionSize,
+    MinPositionSizeUsd, // USD floor on new position sizes (1e7 scaled, 0 = disabled)
+    CollateralPriceMarket, // Oracle market pricing the collateral token in USD
+    // Per-market leverage overrides (fall back to global limits when unset)
+    MarketMinLeverage(u32),
+    MarketMaxLeverage(u32),
+    MarketLotSize(u32), // Size increment positions and orders must be multiples of (unset = none)
+    MarketRiskTiers(u32), // Vec<RiskTier> by ascending notional (unset = no tiers)
+    MarketLedgerOpenCap(u32), // Max notional of opens accepted per ledger (unset = no cap)
+    MarketLeverageDecay(u32), // LeverageDecay as the market's OI nears its cap (unset = none)
+    // Protocol-wide leverage caps on new positions (0 = off)
+    LeverageStepDown,
+    CircuitBreakerMaxLeverage, // Applies while any market is paused by the circuit breaker
     // Fee parameters
     MakerFeeBps,
     TakerFeeBps,
     LiquidationFeeBps,
+    EarlyCloseFeeBps,
+    KeeperMinReward,
+    KeeperMaxReward,
+    LiquidationRebateBufferBps, // Equity kept by the pool above liquidation fees (0 = no rebate)
+    LiquidationPriorityWindow,  // Seconds bonded keepers have a liquidatable position to themselves
     // Risk parameters
     LiquidationThreshold,
     MaintenanceMargin,
     MaxPriceDeviationBps,
+    MaxFundingPerIntervalBps,
     // Time parameters
     FundingInterval,
     PriceStalenessThreshold,
+    MarketPriceStalenessThreshold(u32), // Per-market override (falls back to global when unset)
+    MaxPauseDuration,
+    MinHoldDuration,
+    FundingMinHoldDuration, // Funding received by positions closed sooner is forfeited
     // Liquidity parameters
     MaxUtilizationRatio,
     MinLiquidityReserveRatio,
+    PausedWithdrawalLimitBps,
+    PausedWithdrawalInterval,
     // Borrowing parameters
     BorrowRatePerSecond,
+    CumulativeBorrowIndex, // Sum of rate * seconds up to BorrowIndexUpdatedAt
+    BorrowIndexUpdatedAt,
+    // Integrator allow-list
+    AllowedIntegrator(Address),
+    // Frontend fee share
+    Frontend(Address), // Persistent: registered frontend earning a share of trading fees
+    FrontendFeeShareBps, // Share of fees on attributed positions paid to the frontend
+    // Compliance hook
+    ComplianceEnabled,
+    Blocked(Address), // Persistent: the blocklist is unbounded
+    // Global pause
+    GlobalPaused,
+    // Per-operation pause switches
+    OperationPaused(PauseOp), // bool: operation paused protocol-wide
+    MarketOperationPaused(u32, PauseOp), // bool: operation paused in one market
+    // Storage usage counters
+    MarketOverrideCount, // Per-market staleness, lot size, risk tier, open cap and decay entries
+    NamedValueCount,     // Named parameter entries across the three namespaces
+}
+
+/// Advance the protocol-wide event sequence and return the new value. The other
+/// contracts draw theirs through `next_event_seq()` with `events::next_seq()`.
+fn next_event_seq(env: &Env) -> u64 {
+    let seq = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&EventKey::Seq)
+        .unwrap_or(0)
+        + 1;
+    env.storage().instance().set(&EventKey::Seq, &seq);
+    seq
+}
+
+/// Registered protocol contracts and external addresses, kept apart from `DataKey`
+/// because a contract type enum is limited to 50 variants.
+#[derive(Clone)]
+#[contracttype]
+pub enum ContractKey {
+    LiquidityPool,
+    PositionManager,
+    MarketManager,
+    OracleIntegrator,
+    DiaOracle,
+    ReflectorOracle,
+    Token,
+    FeeToken, // Execution fee token (falls back to Token when unset)
+    KeeperRegistry,
+    InsuranceFund,
+    Campaign,
+    Compliance,
+    OrderManager,
+    KeeperRewards,
+}
+
+/// Event bookkeeping, kept apart from `DataKey` for the same reason
+#[derive(Clone)]
+#[contracttype]
+pub enum EventKey {
+    Seq, // u64: protocol-wide events stamped so far; the last one carries this seq
+}
+
+/// Named parameters (persistent), one namespace per value type
+#[derive(Clone)]
+#[contracttype]
+pub enum NamedKey {
+    U32(Symbol),
+    Bool(Symbol),
+    Address(Symbol),
+}
+
+/// Operation that can be paused on its own. Liquidations are deliberately absent:
+/// they keep running through every pause so bad debt cannot build up.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseOp {
+    Opens,         // Opening and increasing positions (including limit and TWAP fills)
+    Orders,        // Creating and executing limit and TWAP orders
+    LpDeposits,    // LiquidityPool deposits (protocol-wide only)
+    LpWithdrawals, // LiquidityPool withdrawals (protocol-wide only)
+}
+
+/// Role a registered core contract plays in the protocol
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProtocolRole {
+    LiquidityPool,
+    PositionManager,
+    MarketManager,
+    OracleIntegrator,
+    OrderManager,
+}
+
+/// Network a deployment targets, fixed at initialization. Test hooks (oracle test
+/// mode, faucet minting, testnet campaigns) refuse to enable on `Mainnet`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NetworkProfile {
+    Testnet,
+    Futurenet,
+    Mainnet,
+}
+
+/// Type returned by a parameter's getter
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigValueType {
+    I128,
+    U64,
+}
+
+/// Stored entry counts, from `get_storage_usage()`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageUsage {
+    pub parameters: u32,       // Global parameters listed by list_config_keys()
+    pub market_overrides: u32, // Per-market staleness thresholds, lot sizes, risk tiers, open caps, leverage decays
+    pub named_values: u32,     // Named u32, bool and Address parameters
+}
+
+/// Describes a global configuration parameter for admin tooling
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigKeyDescriptor {
+    pub key: Symbol, // Name of the getter returning the value
+    pub value_type: ConfigValueType,
+    pub min: i128,      // Smallest value the setter accepts
+    pub max: i128,      // Largest value the setter accepts
+    pub setter: Symbol, // Admin function that updates it (may set several parameters)
+}
+
+const U64_MAX: i128 = u64::MAX as i128;
+
+/// Minimum size of a new position. The USD floor applies while it is set and the
+/// oracle prices the collateral token; the token-unit floor applies otherwise.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinPositionSize {
+    pub token_units: i128,    // Floor in collateral token base units
+    pub usd: i128,            // Floor in USD (1e7 scaled), 0 = token-unit floor only
+    pub price_market_id: u32, // Oracle market whose price is the collateral token's USD price
+}
+
+/// Maximum number of risk tiers per market
+pub const MAX_RISK_TIERS: u32 = 10;
+
+/// Initial margin required of positions up to a notional size
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskTier {
+    pub max_notional: u128,      // Largest position size in the tier (inclusive)
+    pub initial_margin_bps: u32, // Collateral required as a share of size
+}
+
+/// Linear decay of a market's max leverage for new positions as a side's open interest
+/// nears the market's OI cap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeverageDecay {
+    pub start_utilization_bps: u32, // OI cap usage below which the full max leverage applies
+    pub end_utilization_bps: u32,   // Usage at and above which only floor_leverage applies
+    pub floor_leverage: i128,       // Max leverage once end_utilization_bps is reached
+}
+
+/// Global parameters as (getter, type, min, max, setter). Bounds are the setter's
+/// per-value limits; cross-parameter rules (max leverage above min leverage, liquidation
+/// threshold above maintenance margin, keeper max reward at least the min) are
+/// enforced by the setter and by `set_config_batch()` on top of them.
+#[rustfmt::skip]
+const CONFIG_KEYS: [(&str, ConfigValueType, i128, i128, &str); 29] = [
+    ("min_leverage", ConfigValueType::I128, 1, 99, "set_leverage_limits"),
+    ("max_leverage", ConfigValueType::I128, 2, 100, "set_leverage_limits"),
+    ("leverage_step_down", ConfigValueType::I128, 0, 100, "set_leverage_step_down"),
+    ("circuit_breaker_max_leverage", ConfigValueType::I128, 0, 100, "set_circuit_breaker_leverage"),
+    ("min_position_size", ConfigValueType::I128, 1, i128::MAX, "set_min_position_size"),
+    ("min_position_size_usd", ConfigValueType::I128, 0, i128::MAX, "set_min_position_size_usd"),
+    ("maker_fee_bps", ConfigValueType::I128, 0, 1000, "set_fees"),
+    ("taker_fee_bps", ConfigValueType::I128, 0, 1000, "set_fees"),
+    ("liquidation_fee_bps", ConfigValueType::I128, 0, 1000, "set_fees"),
+    ("keeper_min_reward", ConfigValueType::I128, 0, i128::MAX, "set_keeper_reward_limits"),
+    ("keeper_max_reward", ConfigValueType::I128, 0, i128::MAX, "set_keeper_reward_limits"),
+    ("liquidation_rebate_buffer_bps", ConfigValueType::I128, 0, 10000, "set_liquidation_rebate_buffer"),
+    ("liquidation_priority_window", ConfigValueType::U64, 0, 3600, "set_liquidation_priority_window"),
+    ("early_close_fee_bps", ConfigValueType::I128, 0, 1000, "set_early_close_fee"),
+    ("min_hold_duration", ConfigValueType::U64, 0, U64_MAX, "set_early_close_fee"),
+    ("funding_min_hold_duration", ConfigValueType::U64, 0, U64_MAX, "set_funding_min_hold_duration"),
+    ("liquidation_threshold", ConfigValueType::I128, 2, 10000, "set_risk_params"),
+    ("maintenance_margin", ConfigValueType::I128, 1, 9999, "set_risk_params"),
+    ("max_price_deviation_bps", ConfigValueType::I128, 1, 5000, "set_max_price_deviation"),
+    ("max_funding_per_interval_bps", ConfigValueType::I128, 1, 10000, "set_max_funding_per_interval"),
+    ("funding_interval", ConfigValueType::U64, 1, U64_MAX, "set_time_params"),
+    ("price_staleness_threshold", ConfigValueType::U64, 1, U64_MAX, "set_time_params"),
+    ("max_pause_duration", ConfigValueType::U64, 0, U64_MAX, "set_max_pause_duration"),
+    ("max_utilization_ratio", ConfigValueType::I128, 0, 10000, "set_max_utilization_ratio"),
+    ("min_liquidity_reserve_ratio", ConfigValueType::I128, 0, 10000, "set_min_liquidity_reserve_ratio"),
+    ("paused_withdrawal_limit_bps", ConfigValueType::I128, 0, 10000, "set_paused_withdrawal_limit"),
+    ("paused_withdrawal_interval", ConfigValueType::U64, 1, U64_MAX, "set_paused_withdrawal_limit"),
+    ("borrow_rate_per_second", ConfigValueType::I128, 0, i128::MAX, "set_borrow_rate_per_second"),
+    ("frontend_fee_share_bps", ConfigValueType::I128, 0, 5000, "set_frontend_fee_share"),
+];
+
+// Events
+#[contractevent]
+pub struct IntegratorUpdatedEvent {
+    pub integrator: Address,
+    pub allowed: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FrontendUpdatedEvent {
+    pub frontend: Address,
+    pub registered: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct ComplianceUpdatedEvent {
+    pub enabled: bool,
+    pub compliance_contract: Option<Address>,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct BlocklistUpdatedEvent {
+    pub account: Address,
+    pub blocked: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct LeverageStepDownUpdatedEvent {
+    pub max_leverage: i128, // 0 = step-down lifted
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct GlobalPauseUpdatedEvent {
+    pub paused: bool,
+    pub seq: u64,
+}
+
+/// Emitted when a protocol contract's circuit breaker pauses the protocol
+#[contractevent]
+pub struct GlobalPauseTrippedEvent {
+    pub contract: Address,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct OperationPauseUpdatedEvent {
+    pub operation: PauseOp,
+    pub market_id: Option<u32>, // None for the protocol-wide switch
+    pub paused: bool,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct ConfigBatchAppliedEvent {
+    pub keys: Vec<Symbol>, // Getter names of the parameters updated, in batch order
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct NetworkProfileSetEvent {
+    pub profile: NetworkProfile,
+    pub seq: u64,
+}
+
+/// Emitted when the token or oracle is replaced without the migration interlocks
+#[contractevent]
+pub struct RegistryChangeForcedEvent {
+    pub registry: Symbol,
+    pub previous: Address,
+    pub contract: Address,
+    pub open_interest: u128,
+    pub seq: u64,
+}
+
+/// Emitted when the LiquidityPool repoints the token at the end of its token migration
+#[contractevent]
+pub struct PoolTokenMigratedEvent {
+    pub previous: Address,
+    pub token: Address,
+    pub open_interest: u128,
+    pub seq: u64,
 }
 
 #[contract]
@@ -71,6 +407,48 @@ fn put_admin(e: &Env, admin: &Address) {
     e.storage().instance().set(&DataKey::Admin, admin);
 }
 
+fn get_storage_count(e: &Env, key: &DataKey) -> u32 {
+    e.storage().instance().get(key).unwrap_or(0)
+}
+
+fn adjust_storage_count(e: &Env, key: &DataKey, added: bool) {
+    let count = get_storage_count(e, key);
+    let count = if added {
+        count + 1
+    } else {
+        count.saturating_sub(1)
+    };
+    e.storage().instance().set(key, &count);
+}
+
+/// Count a per-market override about to be stored, unless it replaces an existing one
+fn count_market_override(e: &Env, key: &DataKey) {
+    if !e.storage().instance().has(key) {
+        adjust_storage_count(e, &DataKey::MarketOverrideCount, true);
+    }
+}
+
+fn remove_market_override(e: &Env, key: &DataKey) {
+    if e.storage().instance().has(key) {
+        e.storage().instance().remove(key);
+        adjust_storage_count(e, &DataKey::MarketOverrideCount, false);
+    }
+}
+
+/// Count a named parameter about to be stored, unless it replaces an existing one
+fn count_named_value(e: &Env, key: &NamedKey) {
+    if !e.storage().persistent().has(key) {
+        adjust_storage_count(e, &DataKey::NamedValueCount, true);
+    }
+}
+
+fn remove_named_value(e: &Env, key: &NamedKey) {
+    if e.storage().persistent().has(key) {
+        e.storage().persistent().remove(key);
+        adjust_storage_count(e, &DataKey::NamedValueCount, false);
+    }
+}
+
 fn get_config_value(e: &Env, key: &DataKey) -> i128 {
     e.storage().instance().get(key).unwrap_or(0)
 }
@@ -87,72 +465,212 @@ fn put_time_config_value(e: &Env, key: &DataKey, value: u64) {
     e.storage().instance().set(key, &value);
 }
 
+/// Cumulative borrow index (rate per second * seconds, scaled by 1e7) accrued up to now
+fn current_borrow_index(e: &Env) -> i128 {
+    let index: i128 = get_config_value(e, &DataKey::CumulativeBorrowIndex);
+    let updated_at = get_time_config_value(e, &DataKey::BorrowIndexUpdatedAt);
+    let elapsed = e.ledger().timestamp().saturating_sub(updated_at) as i128;
+    index + get_config_value(e, &DataKey::BorrowRatePerSecond) * elapsed
+}
+
 fn require_admin(e: &Env, admin: &Address) {
     admin.require_auth();
     let stored_admin = get_admin(e);
     if admin != &stored_admin {
-        panic!("unauthorized");
+        panic_with_error!(e, ConfigError::Unauthorized);
+    }
+}
+
+/// Require `contract` to be the registered LiquidityPool, authorizing the call
+fn require_liquidity_pool(e: &Env, contract: &Address) {
+    contract.require_auth();
+    let pool: Option<Address> = e.storage().instance().get(&ContractKey::LiquidityPool);
+    if pool.as_ref() != Some(contract) {
+        panic_with_error!(e, ConfigError::Unauthorized);
+    }
+}
+
+fn validate_leverage_limits(e: &Env, min_leverage: i128, max_leverage: i128) {
+    if min_leverage < 1 {
+        panic_with_error!(e, ConfigError::InvalidLeverage);
+    }
+    if max_leverage <= min_leverage {
+        panic_with_error!(e, ConfigError::InvalidLeverage);
+    }
+    if max_leverage > 100 {
+        panic_with_error!(e, ConfigError::InvalidLeverage);
+    }
+}
+
+/// Storage key of a global parameter in CONFIG_KEYS, by getter name
+fn config_param_key(e: &Env, key: &str) -> DataKey {
+    match key {
+        "min_leverage" => DataKey::MinLeverage,
+        "max_leverage" => DataKey::MaxLeverage,
+        "leverage_step_down" => DataKey::LeverageStepDown,
+        "circuit_breaker_max_leverage" => DataKey::CircuitBreakerMaxLeverage,
+        "min_position_size" => DataKey::MinPositionSize,
+        "min_position_size_usd" => DataKey::MinPositionSizeUsd,
+        "maker_fee_bps" => DataKey::MakerFeeBps,
+        "taker_fee_bps" => DataKey::TakerFeeBps,
+        "liquidation_fee_bps" => DataKey::LiquidationFeeBps,
+        "keeper_min_reward" => DataKey::KeeperMinReward,
+        "keeper_max_reward" => DataKey::KeeperMaxReward,
+        "liquidation_rebate_buffer_bps" => DataKey::LiquidationRebateBufferBps,
+        "liquidation_priority_window" => DataKey::LiquidationPriorityWindow,
+        "early_close_fee_bps" => DataKey::EarlyCloseFeeBps,
+        "min_hold_duration" => DataKey::MinHoldDuration,
+        "funding_min_hold_duration" => DataKey::FundingMinHoldDuration,
+        "liquidation_threshold" => DataKey::LiquidationThreshold,
+        "maintenance_margin" => DataKey::MaintenanceMargin,
+        "max_price_deviation_bps" => DataKey::MaxPriceDeviationBps,
+        "max_funding_per_interval_bps" => DataKey::MaxFundingPerIntervalBps,
+        "funding_interval" => DataKey::FundingInterval,
+        "price_staleness_threshold" => DataKey::PriceStalenessThreshold,
+        "max_pause_duration" => DataKey::MaxPauseDuration,
+        "max_utilization_ratio" => DataKey::MaxUtilizationRatio,
+        "min_liquidity_reserve_ratio" => DataKey::MinLiquidityReserveRatio,
+        "paused_withdrawal_limit_bps" => DataKey::PausedWithdrawalLimitBps,
+        "paused_withdrawal_interval" => DataKey::PausedWithdrawalInterval,
+        "borrow_rate_per_second" => DataKey::BorrowRatePerSecond,
+        "frontend_fee_share_bps" => DataKey::FrontendFeeShareBps,
+        _ => panic_with_error!(e, ConfigError::UnknownConfigKey),
     }
 }
 
-fn get_contract_address(e: &Env, key: &DataKey) -> Address {
+fn get_contract_address(e: &Env, key: &ContractKey) -> Address {
     e.storage().instance().get(key).unwrap()
 }
 
-fn put_contract_address(e: &Env, key: &DataKey, address: &Address) {
+fn put_contract_address(e: &Env, key: &ContractKey, address: &Address) {
     e.storage().instance().set(key, address);
 }
 
+/// Total open interest across all markets, or zero before a MarketManager is registered
+fn total_open_interest(e: &Env) -> u128 {
+    match e
+        .storage()
+        .instance()
+        .get::<ContractKey, Address>(&ContractKey::MarketManager)
+    {
+        Some(market_manager) => e.invoke_contract::<u128>(
+            &market_manager,
+            &Symbol::new(e, "get_total_open_interest"),
+            vec![e],
+        ),
+        None => 0,
+    }
+}
+
+/// Replace the token or oracle address, guarding positions opened against the old one.
+///
+/// The first assignment is always allowed. Replacing an existing address requires the
+/// global pause and zero open interest unless `force` is set, in which case the change
+/// is recorded in a `RegistryChangeForcedEvent`.
+fn put_migrated_contract_address(
+    e: &Env,
+    key: &ContractKey,
+    registry: &str,
+    contract: &Address,
+    force: bool,
+) {
+    let previous = match e.storage().instance().get::<ContractKey, Address>(key) {
+        Some(previous) if previous != *contract => previous,
+        _ => return put_contract_address(e, key, contract),
+    };
+
+    let open_interest = total_open_interest(e);
+    if force {
+        RegistryChangeForcedEvent {
+            registry: Symbol::new(e, registry),
+            previous,
+            contract: contract.clone(),
+            open_interest,
+            seq: next_event_seq(e),
+        }
+        .publish(e);
+    } else {
+        if !ConfigManager::is_globally_paused(e.clone()) {
+            panic_with_error!(e, ConfigError::GlobalPauseRequired);
+        }
+        if open_interest > 0 {
+            panic_with_error!(e, ConfigError::OpenInterestNotZero);
+        }
+    }
+    put_contract_address(e, key, contract);
+}
+
 #[contractimpl]
 impl ConfigManager {
-    /// Initialize the configuration contract with admin.
+    /// Set up the configuration contract as it is deployed, with its admin and network
+    /// profile. Running in the deploy transaction, it cannot be front-run into taking
+    /// another admin.
+    ///
+    /// The profile cannot be changed afterwards. On `Mainnet`, test hooks refuse
+    /// to enable: the oracle's test mode, faucet minting and testnet campaigns.
     ///
     /// # Arguments
     ///
     /// * `admin` - The administrator address
-    ///
-    /// # Panics
-    ///
-    /// Panics if already initialized
-    pub fn initialize(env: Env, admin: Address) {
-        // Verify not already initialized
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic!("already initialized");
-        }
-
-        // Require the admin to authorize this initialization
-        admin.require_auth();
-
+    /// * `profile` - The network this deployment targets
+    pub fn __constructor(env: Env, admin: Address, profile: NetworkProfile) {
         // Set admin
         put_admin(&env, &admin);
 
+        env.storage()
+            .instance()
+            .set(&DataKey::NetworkProfile, &profile);
+        NetworkProfileSetEvent {
+            profile,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
         // Set default configuration values
         // Trading parameters
         put_config_value(&env, &DataKey::MinLeverage, 5);
         put_config_value(&env, &DataKey::MaxLeverage, 20);
         put_config_value(&env, &DataKey::MinPositionSize, 10_000_000);
+        put_config_value(&env, &DataKey::MinPositionSizeUsd, 0); // Token-unit floor only
 
         // Fee parameters (in basis points)
         put_config_value(&env, &DataKey::MakerFeeBps, 2);
         put_config_value(&env, &DataKey::TakerFeeBps, 5);
         put_config_value(&env, &DataKey::LiquidationFeeBps, 50);
+        put_config_value(&env, &DataKey::EarlyCloseFeeBps, 0);
+        put_config_value(&env, &DataKey::FrontendFeeShareBps, 0); // No frontend share
+
+        // Keeper liquidation reward bounds (in token units)
+        put_config_value(&env, &DataKey::KeeperMinReward, 5_000_000); // 0.5 tokens
+        put_config_value(&env, &DataKey::KeeperMaxReward, 100_000_000_000); // 10,000 tokens
 
         // Risk parameters
         put_config_value(&env, &DataKey::LiquidationThreshold, 9000);
         put_config_value(&env, &DataKey::MaintenanceMargin, 5000);
         put_config_value(&env, &DataKey::MaxPriceDeviationBps, 500);
+        put_config_value(&env, &DataKey::MaxFundingPerIntervalBps, 1000); // 10% of collateral
 
         // Time parameters
         put_time_config_value(&env, &DataKey::FundingInterval, 60);
         put_time_config_value(&env, &DataKey::PriceStalenessThreshold, 60);
+        put_time_config_value(&env, &DataKey::MaxPauseDuration, 604_800); // 7 days
+        put_time_config_value(&env, &DataKey::MinHoldDuration, 0); // No early close fee
+        put_time_config_value(&env, &DataKey::FundingMinHoldDuration, 0); // Funding always paid out
 
         // Liquidity parameters (in basis points)
         put_config_value(&env, &DataKey::MaxUtilizationRatio, 8000); // 80%
         put_config_value(&env, &DataKey::MinLiquidityReserveRatio, 2000); // 20%
+        put_config_value(&env, &DataKey::PausedWithdrawalLimitBps, 1000); // 10% of TVL
+        put_time_config_value(&env, &DataKey::PausedWithdrawalInterval, 86_400); // per day
 
         // Borrowing parameters (rate per second scaled by 1e7)
         // Default: 1 = 0.0000001% per second (~3.15% APR)
         put_config_value(&env, &DataKey::BorrowRatePerSecond, 1);
+        put_time_config_value(
+            &env,
+            &DataKey::BorrowIndexUpdatedAt,
+            env.ledger().timestamp(),
+        );
     }
 
     /// Update the admin address.
@@ -173,6 +691,27 @@ impl ConfigManager {
         put_admin(&env, &new_admin);
     }
 
+    /// Get the network profile chosen at initialization.
+    ///
+    /// # Returns
+    ///
+    /// The deployment's network profile (Testnet for deployments predating profiles)
+    pub fn network_profile(env: Env) -> NetworkProfile {
+        env.storage()
+            .instance()
+            .get(&DataKey::NetworkProfile)
+            .unwrap_or(NetworkProfile::Testnet)
+    }
+
+    /// Check whether test hooks may be enabled on this deployment.
+    ///
+    /// # Returns
+    ///
+    /// False on the mainnet profile, true on testnet and futurenet
+    pub fn test_hooks_allowed(env: Env) -> bool {
+        Self::network_profile(env) != NetworkProfile::Mainnet
+    }
+
     /// Get the current admin address.
     ///
     /// # Returns
@@ -209,6 +748,33 @@ impl ConfigManager {
         get_config_value(&env, &DataKey::MinPositionSize)
     }
 
+    /// Get the USD floor on new position sizes.
+    ///
+    /// # Returns
+    ///
+    /// Minimum position size in USD, scaled by 1e7 (default: 0 = disabled)
+    pub fn min_position_size_usd(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::MinPositionSizeUsd)
+    }
+
+    /// Get both minimum position size bounds in one call.
+    ///
+    /// # Returns
+    ///
+    /// MinPositionSize with the token-unit floor, the USD floor and the oracle market
+    /// pricing the collateral token
+    pub fn min_position_size_bounds(env: Env) -> MinPositionSize {
+        MinPositionSize {
+            token_units: get_config_value(&env, &DataKey::MinPositionSize),
+            usd: get_config_value(&env, &DataKey::MinPositionSizeUsd),
+            price_market_id: env
+                .storage()
+                .instance()
+                .get(&DataKey::CollateralPriceMarket)
+                .unwrap_or(0),
+        }
+    }
+
     /// Get maker fee in basis points.
     ///
     /// # Returns
@@ -236,6 +802,46 @@ impl ConfigManager {
         get_config_value(&env, &DataKey::LiquidationFeeBps)
     }
 
+    /// Get the minimum keeper reward for a liquidation, so small positions stay
+    /// worth liquidating.
+    ///
+    /// # Returns
+    ///
+    /// Minimum reward in token units (default: 5_000_000 = 0.5 tokens)
+    pub fn keeper_min_reward(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::KeeperMinReward)
+    }
+
+    /// Get the maximum keeper reward for a liquidation.
+    ///
+    /// # Returns
+    ///
+    /// Maximum reward in token units (default: 100_000_000_000 = 10,000 tokens)
+    pub fn keeper_max_reward(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::KeeperMaxReward)
+    }
+
+    /// Get the liquidation rebate buffer: equity (as bps of position size) the pool
+    /// keeps on top of the liquidation fees before returning the rest to the trader.
+    ///
+    /// # Returns
+    ///
+    /// Buffer in basis points of size (default: 0 = liquidated equity is never rebated)
+    pub fn liquidation_rebate_buffer_bps(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::LiquidationRebateBufferBps)
+    }
+
+    /// Get the keeper priority window for liquidations: seconds after a position is
+    /// flagged liquidatable during which only bonded keepers may liquidate it.
+    ///
+    /// # Returns
+    ///
+    /// Window in seconds (default: 0 = in permissioned keeper mode, only bonded keepers
+    /// ever liquidate)
+    pub fn liquidation_priority_window(env: Env) -> u64 {
+        get_time_config_value(&env, &DataKey::LiquidationPriorityWindow)
+    }
+
     /// Get liquidation threshold in basis points.
     ///
     /// # Returns
@@ -263,6 +869,24 @@ impl ConfigManager {
         get_config_value(&env, &DataKey::MaxPriceDeviationBps)
     }
 
+    /// Get the most funding a position can be charged per funding interval.
+    ///
+    /// # Returns
+    ///
+    /// Cap in basis points of the position's collateral (default: 1000)
+    pub fn max_funding_per_interval_bps(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::MaxFundingPerIntervalBps)
+    }
+
+    /// Get the extra fee charged on positions closed within the minimum hold duration.
+    ///
+    /// # Returns
+    ///
+    /// Early close fee in basis points of the closed size (default: 0)
+    pub fn early_close_fee_bps(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::EarlyCloseFeeBps)
+    }
+
     /// Get funding interval in seconds.
     ///
     /// # Returns
@@ -281,6 +905,34 @@ impl ConfigManager {
         get_time_config_value(&env, &DataKey::PriceStalenessThreshold)
     }
 
+    /// Get the maximum time a market may stay paused before its positions
+    /// can be force-settled at the last valid TWAP.
+    ///
+    /// # Returns
+    ///
+    /// Max pause duration in seconds (default: 604800 = 7 days, 0 = never settle)
+    pub fn max_pause_duration(env: Env) -> u64 {
+        get_time_config_value(&env, &DataKey::MaxPauseDuration)
+    }
+
+    /// Get how long a position must stay open to avoid the early close fee.
+    ///
+    /// # Returns
+    ///
+    /// Minimum hold duration in seconds (default: 0 = no early close fee)
+    pub fn min_hold_duration(env: Env) -> u64 {
+        get_time_config_value(&env, &DataKey::MinHoldDuration)
+    }
+
+    /// Get how long a position must stay open before the funding it receives counts.
+    ///
+    /// # Returns
+    ///
+    /// Funding minimum hold duration in seconds (default: 0 = funding always credited)
+    pub fn funding_min_hold_duration(env: Env) -> u64 {
+        get_time_config_value(&env, &DataKey::FundingMinHoldDuration)
+    }
+
     // Contract Registry Functions
 
     /// Set the Liquidity Pool contract address.
@@ -295,7 +947,7 @@ impl ConfigManager {
     /// Panics if caller is not the admin
     pub fn set_liquidity_pool(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::LiquidityPoolContract, &contract);
+        put_contract_address(&env, &ContractKey::LiquidityPool, &contract);
     }
 
     /// Get the Liquidity Pool contract address.
@@ -304,7 +956,7 @@ impl ConfigManager {
     ///
     /// The Liquidity Pool contract address
     pub fn liquidity_pool(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::LiquidityPoolContract)
+        get_contract_address(&env, &ContractKey::LiquidityPool)
     }
 
     /// Set the Position Manager contract address.
@@ -319,7 +971,7 @@ impl ConfigManager {
     /// Panics if caller is not the admin
     pub fn set_position_manager(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::PositionManagerContract, &contract);
+        put_contract_address(&env, &ContractKey::PositionManager, &contract);
     }
 
     /// Get the Position Manager contract address.
@@ -328,7 +980,31 @@ impl ConfigManager {
     ///
     /// The Position Manager contract address
     pub fn position_manager(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::PositionManagerContract)
+        get_contract_address(&env, &ContractKey::PositionManager)
+    }
+
+    /// Set the Order Manager contract address.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The Order Manager contract address
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_order_manager(env: Env, admin: Address, contract: Address) {
+        require_admin(&env, &admin);
+        put_contract_address(&env, &ContractKey::OrderManager, &contract);
+    }
+
+    /// Get the Order Manager contract address.
+    ///
+    /// # Returns
+    ///
+    /// The Order Manager contract address
+    pub fn order_manager(env: Env) -> Address {
+        get_contract_address(&env, &ContractKey::OrderManager)
     }
 
     /// Set the Market Manager contract address.
@@ -343,7 +1019,7 @@ impl ConfigManager {
     /// Panics if caller is not the admin
     pub fn set_market_manager(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::MarketManagerContract, &contract);
+        put_contract_address(&env, &ContractKey::MarketManager, &contract);
     }
 
     /// Get the Market Manager contract address.
@@ -352,7 +1028,7 @@ impl ConfigManager {
     ///
     /// The Market Manager contract address
     pub fn market_manager(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::MarketManagerContract)
+        get_contract_address(&env, &ContractKey::MarketManager)
     }
 
     /// Set the Oracle Integrator contract address.
@@ -364,10 +1040,39 @@ impl ConfigManager {
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the admin
+    /// Panics if caller is not the admin, or if replacing an existing oracle while
+    /// the protocol is not globally paused or open interest is not zero
     pub fn set_oracle_integrator(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::OracleIntegratorContract, &contract);
+        put_migrated_contract_address(
+            &env,
+            &ContractKey::OracleIntegrator,
+            "oracle",
+            &contract,
+            false,
+        );
+    }
+
+    /// Replace the Oracle Integrator contract address without the pause and open
+    /// interest interlocks, emitting a `RegistryChangeForcedEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The Oracle Integrator contract address
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn force_set_oracle_integrator(env: Env, admin: Address, contract: Address) {
+        require_admin(&env, &admin);
+        put_migrated_contract_address(
+            &env,
+            &ContractKey::OracleIntegrator,
+            "oracle",
+            &contract,
+            true,
+        );
     }
 
     /// Get the Oracle Integrator contract address.
@@ -376,7 +1081,7 @@ impl ConfigManager {
     ///
     /// The Oracle Integrator contract address
     pub fn oracle_integrator(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::OracleIntegratorContract)
+        get_contract_address(&env, &ContractKey::OracleIntegrator)
     }
 
     /// Set the Token contract address.
@@ -388,10 +1093,27 @@ impl ConfigManager {
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the admin
+    /// Panics if caller is not the admin, or if replacing an existing token while
+    /// the protocol is not globally paused or open interest is not zero
     pub fn set_token(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::TokenContract, &contract);
+        put_migrated_contract_address(&env, &ContractKey::Token, "token", &contract, false);
+    }
+
+    /// Replace the Token contract address without the pause and open interest
+    /// interlocks, emitting a `RegistryChangeForcedEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The Token contract address
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn force_set_token(env: Env, admin: Address, contract: Address) {
+        require_admin(&env, &admin);
+        put_migrated_contract_address(&env, &ContractKey::Token, "token", &contract, true);
     }
 
     /// Get the Token contract address.
@@ -400,7 +1122,40 @@ impl ConfigManager {
     ///
     /// The Token contract address
     pub fn token(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::TokenContract)
+        get_contract_address(&env, &ContractKey::Token)
+    }
+
+    /// Set or clear a separate token for order execution fees (keeper payments).
+    ///
+    /// Collateral always stays in the pool token; orders escrow their execution fees
+    /// in the fee token current when they are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The fee token contract address, or None to charge fees in the pool token
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_fee_token(env: Env, admin: Address, contract: Option<Address>) {
+        require_admin(&env, &admin);
+        match contract {
+            Some(address) => put_contract_address(&env, &ContractKey::FeeToken, &address),
+            None => env.storage().instance().remove(&ContractKey::FeeToken),
+        }
+    }
+
+    /// Get the token execution fees are charged in.
+    ///
+    /// # Returns
+    ///
+    /// The fee token contract address, or the pool token if no fee token is set
+    pub fn fee_token(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&ContractKey::FeeToken)
+            .unwrap_or_else(|| get_contract_address(&env, &ContractKey::Token))
     }
 
     /// Set the DIA Oracle contract address.
@@ -415,7 +1170,7 @@ impl ConfigManager {
     /// Panics if caller is not the admin
     pub fn set_dia_oracle(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::DiaOracleContract, &contract);
+        put_contract_address(&env, &ContractKey::DiaOracle, &contract);
     }
 
     /// Get the DIA Oracle contract address.
@@ -424,7 +1179,7 @@ impl ConfigManager {
     ///
     /// The DIA Oracle contract address
     pub fn dia_oracle(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::DiaOracleContract)
+        get_contract_address(&env, &ContractKey::DiaOracle)
     }
 
     /// Set the Reflector Oracle contract address.
@@ -439,7 +1194,7 @@ impl ConfigManager {
     /// Panics if caller is not the admin
     pub fn set_reflector_oracle(env: Env, admin: Address, contract: Address) {
         require_admin(&env, &admin);
-        put_contract_address(&env, &DataKey::ReflectorOracleContract, &contract);
+        put_contract_address(&env, &ContractKey::ReflectorOracle, &contract);
     }
 
     /// Get the Reflector Oracle contract address.
@@ -448,114 +1203,1236 @@ impl ConfigManager {
     ///
     /// The Reflector Oracle contract address
     pub fn reflector_oracle(env: Env) -> Address {
-        get_contract_address(&env, &DataKey::ReflectorOracleContract)
+        get_contract_address(&env, &ContractKey::ReflectorOracle)
     }
 
-    /// Get maximum pool utilization ratio in basis points.
+    /// Set or clear the KeeperRegistry contract address.
     ///
-    /// # Returns
-    ///
-    /// Maximum utilization ratio in basis points (default: 8000 = 80%)
-    pub fn max_utilization_ratio(env: Env) -> i128 {
-        get_config_value(&env, &DataKey::MaxUtilizationRatio)
-    }
-
-    /// Set maximum pool utilization ratio in basis points.
+    /// While a registry is set, keeper actions are permissioned: only keepers with an
+    /// active bond may perform them, and invalid submissions count against their bond.
     ///
     /// # Arguments
     ///
     /// * `admin` - The administrator address
-    /// * `ratio` - The maximum utilization ratio in basis points (e.g., 8000 = 80%)
+    /// * `contract` - The KeeperRegistry contract address, or None for permissionless keeping
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the admin or ratio is invalid
-    pub fn set_max_utilization_ratio(env: Env, admin: Address, ratio: i128) {
+    /// Panics if caller is not the admin
+    pub fn set_keeper_registry(env: Env, admin: Address, contract: Option<Address>) {
         require_admin(&env, &admin);
-        if ratio < 0 || ratio > 10000 {
-            panic!("invalid utilization ratio");
+        match contract {
+            Some(address) => put_contract_address(&env, &ContractKey::KeeperRegistry, &address),
+            None => env
+                .storage()
+                .instance()
+                .remove(&ContractKey::KeeperRegistry),
+        }
+    }
+
+    /// Get the KeeperRegistry contract address.
+    ///
+    /// # Returns
+    ///
+    /// The KeeperRegistry contract address, or None while keeping is permissionless
+    pub fn keeper_registry(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&ContractKey::KeeperRegistry)
+    }
+
+    /// Set or clear the KeeperRewards contract address.
+    ///
+    /// While it is set, liquidation rewards and execution fees are paid through it, so
+    /// keepers can route or accrue them; otherwise they go to the keeper directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The KeeperRewards contract address, or None to pay keepers directly
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_keeper_rewards(env: Env, admin: Address, contract: Option<Address>) {
+        require_admin(&env, &admin);
+        match contract {
+            Some(address) => put_contract_address(&env, &ContractKey::KeeperRewards, &address),
+            None => env.storage().instance().remove(&ContractKey::KeeperRewards),
+        }
+    }
+
+    /// Get the KeeperRewards contract address.
+    ///
+    /// # Returns
+    ///
+    /// The KeeperRewards contract address, or None while keepers are paid directly
+    pub fn keeper_rewards(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ContractKey::KeeperRewards)
+    }
+
+    /// Set or clear the Campaign contract address.
+    ///
+    /// While a campaign is set, PositionManager and LiquidityPool report trades,
+    /// liquidations and deposits to it so it can award testnet badges.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The Campaign contract address, or None to stop reporting
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, or if setting a campaign on the mainnet profile
+    pub fn set_campaign(env: Env, admin: Address, contract: Option<Address>) {
+        require_admin(&env, &admin);
+        if contract.is_some() && !Self::test_hooks_allowed(env.clone()) {
+            panic_with_error!(&env, ConfigError::TestHooksDisabled);
+        }
+        match contract {
+            Some(address) => put_contract_address(&env, &ContractKey::Campaign, &address),
+            None => env.storage().instance().remove(&ContractKey::Campaign),
+        }
+    }
+
+    /// Get the Campaign contract address.
+    ///
+    /// # Returns
+    ///
+    /// The Campaign contract address, or None while no campaign is running
+    pub fn campaign(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ContractKey::Campaign)
+    }
+
+    /// Set the insurance fund address.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The address receiving slashed keeper bonds
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_insurance_fund(env: Env, admin: Address, contract: Address) {
+        require_admin(&env, &admin);
+        put_contract_address(&env, &ContractKey::InsuranceFund, &contract);
+    }
+
+    /// Get the insurance fund address.
+    ///
+    /// # Returns
+    ///
+    /// The address receiving slashed keeper bonds
+    pub fn insurance_fund(env: Env) -> Address {
+        get_contract_address(&env, &ContractKey::InsuranceFund)
+    }
+
+    /// Look up the role an address is registered for, to validate cross-contract wiring.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to check
+    ///
+    /// # Returns
+    ///
+    /// The role the address is registered as, or None if it is not a registered
+    /// liquidity pool, position manager, market manager, oracle integrator or order
+    /// manager
+    pub fn is_protocol_contract(env: Env, address: Address) -> Option<ProtocolRole> {
+        let roles = [
+            (ContractKey::LiquidityPool, ProtocolRole::LiquidityPool),
+            (
+                ContractKey::PositionManager,
+                ProtocolRole::PositionManager,
+            ),
+            (ContractKey::MarketManager, ProtocolRole::MarketManager),
+            (
+                ContractKey::OracleIntegrator,
+                ProtocolRole::OracleIntegrator,
+            ),
+            (ContractKey::OrderManager, ProtocolRole::OrderManager),
+        ];
+        for (key, role) in roles {
+            let registered: Option<Address> = env.storage().instance().get(&key);
+            if registered == Some(address.clone()) {
+                return Some(role);
+            }
+        }
+        None
+    }
+
+    /// Allow or revoke an external contract permitted to open positions on
+    /// behalf of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `integrator` - The integrator contract address
+    /// * `allowed` - True to allow-list, false to revoke
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_integrator_allowed(env: Env, admin: Address, integrator: Address, allowed: bool) {
+        require_admin(&env, &admin);
+        let key = DataKey::AllowedIntegrator(integrator.clone());
+        if allowed {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+
+        IntegratorUpdatedEvent {
+            integrator,
+            allowed,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check if an integrator is allow-listed.
+    ///
+    /// # Arguments
+    ///
+    /// * `integrator` - The integrator contract address
+    ///
+    /// # Returns
+    ///
+    /// True if the integrator may open positions on behalf of users
+    pub fn is_integrator_allowed(env: Env, integrator: Address) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowedIntegrator(integrator))
+            .unwrap_or(false)
+    }
+
+    /// Register or deregister a frontend earning a share of the fees on positions opened
+    /// through it. Deregistering stops the share for positions already attributed too.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `frontend` - The frontend's fee recipient address
+    /// * `registered` - True to register, false to deregister
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_frontend(env: Env, admin: Address, frontend: Address, registered: bool) {
+        require_admin(&env, &admin);
+        let key = DataKey::Frontend(frontend.clone());
+        if registered {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        FrontendUpdatedEvent {
+            frontend,
+            registered,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check if a frontend is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `frontend` - The frontend's fee recipient address
+    ///
+    /// # Returns
+    ///
+    /// True if positions may be attributed to the frontend
+    pub fn is_frontend(env: Env, frontend: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Frontend(frontend))
+            .unwrap_or(false)
+    }
+
+    /// Get the share of fees a frontend earns on the positions attributed to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `frontend` - The frontend's fee recipient address
+    ///
+    /// # Returns
+    ///
+    /// The fee share in basis points, 0 if the frontend is not registered
+    pub fn frontend_fee_share(env: Env, frontend: Address) -> i128 {
+        if !Self::is_frontend(env.clone(), frontend) {
+            return 0;
+        }
+        get_config_value(&env, &DataKey::FrontendFeeShareBps)
+    }
+
+    /// Get the frontend fee share.
+    ///
+    /// # Returns
+    ///
+    /// Share of fees paid to registered frontends in basis points (default: 0)
+    pub fn frontend_fee_share_bps(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::FrontendFeeShareBps)
+    }
+
+    /// Set the share of the fees charged on attributed positions that is paid to the
+    /// frontend they were opened through instead of the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `share_bps` - Fee share in basis points (0-5000)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the share is above 5000 bps
+    pub fn set_frontend_fee_share(env: Env, admin: Address, share_bps: i128) {
+        require_admin(&env, &admin);
+        if !(0..=5000).contains(&share_bps) {
+            panic_with_error!(&env, ConfigError::InvalidFee);
+        }
+        put_config_value(&env, &DataKey::FrontendFeeShareBps, share_bps);
+    }
+
+    /// Enable or disable the compliance hook.
+    ///
+    /// While disabled (the default) every account is allowed and the blocklist and
+    /// compliance contract are ignored, so deployments opt in to a policy explicitly.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `enabled` - True to enforce the blocklist and compliance contract
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_compliance_enabled(env: Env, admin: Address, enabled: bool) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ComplianceEnabled, &enabled);
+
+        ComplianceUpdatedEvent {
+            enabled,
+            compliance_contract: Self::compliance_contract(env.clone()),
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check if the compliance hook is enforced.
+    ///
+    /// # Returns
+    ///
+    /// True if the blocklist and compliance contract are checked (default: false)
+    pub fn compliance_enabled(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::ComplianceEnabled)
+            .unwrap_or(false)
+    }
+
+    /// Set or clear the external compliance contract.
+    ///
+    /// The contract must expose `is_allowed(account: Address) -> bool`; it is consulted
+    /// after the blocklist, letting deployments plug in their own allowlist policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `contract` - The compliance contract address, or None to rely on the blocklist only
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_compliance_contract(env: Env, admin: Address, contract: Option<Address>) {
+        require_admin(&env, &admin);
+        match &contract {
+            Some(address) => put_contract_address(&env, &ContractKey::Compliance, address),
+            None => env
+                .storage()
+                .instance()
+                .remove(&ContractKey::Compliance),
+        }
+
+        ComplianceUpdatedEvent {
+            enabled: Self::compliance_enabled(env.clone()),
+            compliance_contract: contract,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Get the external compliance contract address.
+    ///
+    /// # Returns
+    ///
+    /// The compliance contract address, or None if only the blocklist applies
+    pub fn compliance_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ContractKey::Compliance)
+    }
+
+    /// Add or remove an account from the compliance blocklist.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `account` - The account to block or unblock
+    /// * `blocked` - True to block, false to unblock
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_blocked(env: Env, admin: Address, account: Address, blocked: bool) {
+        require_admin(&env, &admin);
+        let key = DataKey::Blocked(account.clone());
+        if blocked {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        BlocklistUpdatedEvent {
+            account,
+            blocked,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check if an account is on the compliance blocklist.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account to check
+    ///
+    /// # Returns
+    ///
+    /// True if the account is blocklisted (regardless of whether compliance is enabled)
+    pub fn is_blocked(env: Env, account: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Blocked(account))
+            .unwrap_or(false)
+    }
+
+    /// Check if an account may open positions or move LP funds.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account to check
+    ///
+    /// # Returns
+    ///
+    /// True if compliance is disabled, or the account is not blocklisted and the
+    /// compliance contract (if set) allows it
+    pub fn is_account_allowed(env: Env, account: Address) -> bool {
+        if !Self::compliance_enabled(env.clone()) {
+            return true;
+        }
+        if Self::is_blocked(env.clone(), account.clone()) {
+            return false;
+        }
+        match Self::compliance_contract(env.clone()) {
+            Some(contract) => env.invoke_contract::<bool>(
+                &contract,
+                &Symbol::new(&env, "is_allowed"),
+                vec![&env, account.into_val(&env)],
+            ),
+            None => true,
+        }
+    }
+
+    /// Pause or resume new exposure across every market.
+    ///
+    /// While paused, positions cannot be opened or increased and orders cannot be
+    /// created or executed; closes, decreases and liquidations continue. Operations
+    /// can also be paused individually with `set_operation_paused()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `paused` - True to pause, false to resume
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_global_pause(env: Env, admin: Address, paused: bool) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::GlobalPaused, &paused);
+
+        GlobalPauseUpdatedEvent {
+            paused,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Pause new exposure across every market on behalf of the registered
+    /// LiquidityPool, whose drawdown circuit breaker tripped. Only the admin can lift
+    /// the pause again, with `set_global_pause()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract` - The LiquidityPool contract address (must authorize)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `contract` is not the registered LiquidityPool
+    pub fn trip_global_pause(env: Env, contract: Address) {
+        require_liquidity_pool(&env, &contract);
+        env.storage().instance().set(&DataKey::GlobalPaused, &true);
+
+        GlobalPauseUpdatedEvent {
+            paused: true,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        GlobalPauseTrippedEvent {
+            contract,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Point the Token registry at the registered LiquidityPool's new backing token,
+    /// in the same call that swaps the pool's balance into it. Unlike `set_token()`
+    /// positions may stay open: the pool only migrates at a rate other than 1:1 once
+    /// no token amounts are owed, so every amount recorded in the old token still holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract` - The LiquidityPool contract address (must authorize)
+    /// * `token` - The pool's new backing token
+    ///
+    /// # Panics
+    ///
+    /// Panics if `contract` is not the registered LiquidityPool or the protocol is not
+    /// globally paused
+    pub fn migrate_token(env: Env, contract: Address, token: Address) {
+        require_liquidity_pool(&env, &contract);
+        if !Self::is_globally_paused(env.clone()) {
+            panic_with_error!(&env, ConfigError::GlobalPauseRequired);
+        }
+        let previous = get_contract_address(&env, &ContractKey::Token);
+        put_contract_address(&env, &ContractKey::Token, &token);
+
+        PoolTokenMigratedEvent {
+            previous,
+            token,
+            open_interest: total_open_interest(&env),
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check if the protocol is globally paused.
+    ///
+    /// # Returns
+    ///
+    /// True if new exposure is halted in every market (default: false)
+    pub fn is_globally_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::GlobalPaused)
+            .unwrap_or(false)
+    }
+
+    /// Pause or resume a single operation protocol-wide, so an incident can be
+    /// contained without halting everything (e.g. block opens but keep LP flow).
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `operation` - The operation to switch
+    /// * `paused` - True to pause, false to resume
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_operation_paused(env: Env, admin: Address, operation: PauseOp, paused: bool) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::OperationPaused(operation), &paused);
+
+        OperationPauseUpdatedEvent {
+            operation,
+            market_id: None,
+            paused,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Pause or resume opens or orders in a single market.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market to switch the operation in
+    /// * `operation` - `Opens` or `Orders`
+    /// * `paused` - True to pause, false to resume
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the operation is an LP operation, which
+    /// is not market specific
+    pub fn set_market_operation_paused(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        operation: PauseOp,
+        paused: bool,
+    ) {
+        require_admin(&env, &admin);
+        if matches!(operation, PauseOp::LpDeposits | PauseOp::LpWithdrawals) {
+            panic_with_error!(&env, ConfigError::NotPausablePerMarket);
+        }
+        env.storage().instance().set(
+            &DataKey::MarketOperationPaused(market_id, operation),
+            &paused,
+        );
+
+        OperationPauseUpdatedEvent {
+            operation,
+            market_id: Some(market_id),
+            paused,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Check if an operation is paused, by the global pause (opens and orders), its
+    /// protocol-wide switch or, when a market is given, that market's switch.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation to check
+    /// * `market_id` - The market it runs in (None for LP operations)
+    ///
+    /// # Returns
+    ///
+    /// True if the operation must be refused
+    pub fn is_operation_paused(env: Env, operation: PauseOp, market_id: Option<u32>) -> bool {
+        let storage = env.storage().instance();
+        if matches!(operation, PauseOp::Opens | PauseOp::Orders)
+            && storage.get(&DataKey::GlobalPaused).unwrap_or(false)
+        {
+            return true;
+        }
+        if storage
+            .get(&DataKey::OperationPaused(operation))
+            .unwrap_or(false)
+        {
+            return true;
+        }
+        match market_id {
+            Some(market_id) => storage
+                .get(&DataKey::MarketOperationPaused(market_id, operation))
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Get maximum pool utilization ratio in basis points.
+    ///
+    /// # Returns
+    ///
+    /// Maximum utilization ratio in basis points (default: 8000 = 80%)
+    pub fn max_utilization_ratio(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::MaxUtilizationRatio)
+    }
+
+    /// Set maximum pool utilization ratio in basis points.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `ratio` - The maximum utilization ratio in basis points (e.g., 8000 = 80%)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or ratio is invalid
+    pub fn set_max_utilization_ratio(env: Env, admin: Address, ratio: i128) {
+        require_admin(&env, &admin);
+        if !(0..=10000).contains(&ratio) {
+            panic_with_error!(&env, ConfigError::InvalidBps);
         }
         put_config_value(&env, &DataKey::MaxUtilizationRatio, ratio);
     }
 
-    /// Get minimum liquidity reserve ratio in basis points.
+    /// Get minimum liquidity reserve ratio in basis points.
+    ///
+    /// # Returns
+    ///
+    /// Minimum reserve ratio in basis points (default: 2000 = 20%)
+    pub fn min_liquidity_reserve_ratio(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::MinLiquidityReserveRatio)
+    }
+
+    /// Set minimum liquidity reserve ratio in basis points.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `ratio` - The minimum reserve ratio in basis points (e.g., 2000 = 20%)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or ratio is invalid
+    pub fn set_min_liquidity_reserve_ratio(env: Env, admin: Address, ratio: i128) {
+        require_admin(&env, &admin);
+        if !(0..=10000).contains(&ratio) {
+            panic_with_error!(&env, ConfigError::InvalidBps);
+        }
+        put_config_value(&env, &DataKey::MinLiquidityReserveRatio, ratio);
+    }
+
+    /// Get the share of pool value LPs may withdraw per interval while the protocol is paused.
+    ///
+    /// # Returns
+    ///
+    /// Withdrawal limit in basis points of pool value (default: 1000 = 10%)
+    pub fn paused_withdrawal_limit_bps(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::PausedWithdrawalLimitBps)
+    }
+
+    /// Get the length of a paused withdrawal window in seconds.
+    ///
+    /// # Returns
+    ///
+    /// Window length in seconds (default: 86400 = 1 day)
+    pub fn paused_withdrawal_interval(env: Env) -> u64 {
+        get_time_config_value(&env, &DataKey::PausedWithdrawalInterval)
+    }
+
+    /// Set the LP withdrawal throttle applied while the protocol is globally paused.
+    ///
+    /// Deposits are unaffected. A limit of 10000 lets the whole pool leave within one
+    /// window; a limit of 0 freezes withdrawals for the duration of the pause.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `limit_bps` - Withdrawal limit per window in basis points of pool value
+    /// * `interval` - Window length in seconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the limit is outside 0..=10000 or the interval is zero
+    pub fn set_paused_withdrawal_limit(env: Env, admin: Address, limit_bps: i128, interval: u64) {
+        require_admin(&env, &admin);
+        if !(0..=10000).contains(&limit_bps) {
+            panic_with_error!(&env, ConfigError::InvalidBps);
+        }
+        if interval == 0 {
+            panic_with_error!(&env, ConfigError::InvalidDuration);
+        }
+        put_config_value(&env, &DataKey::PausedWithdrawalLimitBps, limit_bps);
+        put_time_config_value(&env, &DataKey::PausedWithdrawalInterval, interval);
+    }
+
+    /// Get borrow rate per second (scaled by 1e7).
+    ///
+    /// # Returns
+    ///
+    /// Borrow rate per second for calculating borrowing fees
+    pub fn borrow_rate_per_second(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::BorrowRatePerSecond)
+    }
+
+    /// Set borrow rate per second.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `rate` - Borrow rate per second (scaled by 1e7, must be >= 0)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or rate is negative
+    pub fn set_borrow_rate_per_second(env: Env, admin: Address, rate: i128) {
+        require_admin(&env, &admin);
+        if rate < 0 {
+            panic_with_error!(&env, ConfigError::InvalidAmount);
+        }
+        // Checkpoint the index so time before the change accrues at the old rate
+        let index = current_borrow_index(&env);
+        put_config_value(&env, &DataKey::CumulativeBorrowIndex, index);
+        put_time_config_value(
+            &env,
+            &DataKey::BorrowIndexUpdatedAt,
+            env.ledger().timestamp(),
+        );
+        put_config_value(&env, &DataKey::BorrowRatePerSecond, rate);
+    }
+
+    /// Get the cumulative borrow index.
+    ///
+    /// # Returns
+    ///
+    /// Sum of `borrow_rate_per_second * seconds` since initialization (scaled by 1e7),
+    /// checkpointed on every rate change, so the borrowing fee owed on `size` between
+    /// two readings is `size * (later - earlier) / 1e7`
+    pub fn cumulative_borrow_index(env: Env) -> i128 {
+        current_borrow_index(&env)
+    }
+
+    /// Set leverage limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `min_leverage` - Minimum leverage (must be >= 1)
+    /// * `max_leverage` - Maximum leverage (must be > min_leverage and <= 100)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or limits are invalid
+    pub fn set_leverage_limits(env: Env, admin: Address, min_leverage: i128, max_leverage: i128) {
+        require_admin(&env, &admin);
+        validate_leverage_limits(&env, min_leverage, max_leverage);
+        put_config_value(&env, &DataKey::MinLeverage, min_leverage);
+        put_config_value(&env, &DataKey::MaxLeverage, max_leverage);
+    }
+
+    /// Set leverage limits overriding the global defaults for a single market.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    /// * `min_leverage` - Minimum leverage (must be >= 1)
+    /// * `max_leverage` - Maximum leverage (must be > min_leverage and <= 100)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or limits are invalid
+    pub fn set_market_leverage_limits(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        min_leverage: i128,
+        max_leverage: i128,
+    ) {
+        require_admin(&env, &admin);
+        validate_leverage_limits(&env, min_leverage, max_leverage);
+        put_config_value(&env, &DataKey::MarketMinLeverage(market_id), min_leverage);
+        put_config_value(&env, &DataKey::MarketMaxLeverage(market_id), max_leverage);
+    }
+
+    /// Remove a market's leverage override so it falls back to the global limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn clear_market_leverage_limits(env: Env, admin: Address, market_id: u32) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .remove(&DataKey::MarketMinLeverage(market_id));
+        env.storage()
+            .instance()
+            .remove(&DataKey::MarketMaxLeverage(market_id));
+    }
+
+    /// Get the effective leverage limits for a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (min_leverage, max_leverage): the market override if set,
+    /// otherwise the global limits
+    pub fn market_leverage_limits(env: Env, market_id: u32) -> (i128, i128) {
+        let min_leverage = env
+            .storage()
+            .instance()
+            .get(&DataKey::MarketMinLeverage(market_id))
+            .unwrap_or_else(|| get_config_value(&env, &DataKey::MinLeverage));
+        let max_leverage = env
+            .storage()
+            .instance()
+            .get(&DataKey::MarketMaxLeverage(market_id))
+            .unwrap_or_else(|| get_config_value(&env, &DataKey::MaxLeverage));
+        (min_leverage, max_leverage)
+    }
+
+    /// Lower the max leverage for new positions across all markets at once, e.g. from
+    /// 20x to 5x during extreme volatility. It caps every market's own limit without
+    /// changing it, and open positions are left as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `max_leverage` - Protocol-wide cap (1-100), or 0 to lift the step-down
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the cap is invalid
+    pub fn set_leverage_step_down(env: Env, admin: Address, max_leverage: i128) {
+        require_admin(&env, &admin);
+        if !(0..=100).contains(&max_leverage) {
+            panic_with_error!(&env, ConfigError::InvalidLeverage);
+        }
+        put_config_value(&env, &DataKey::LeverageStepDown, max_leverage);
+
+        LeverageStepDownUpdatedEvent {
+            max_leverage,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Get the protocol-wide max leverage step-down.
+    ///
+    /// # Returns
+    ///
+    /// The cap on new positions' leverage in every market (0 = no step-down)
+    pub fn leverage_step_down(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::LeverageStepDown)
+    }
+
+    /// Set the max leverage applied automatically to new positions in every market
+    /// while any market is paused by the circuit breaker.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `max_leverage` - Protocol-wide cap while tripped (1-100), or 0 to disable
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the cap is invalid
+    pub fn set_circuit_breaker_leverage(env: Env, admin: Address, max_leverage: i128) {
+        require_admin(&env, &admin);
+        if !(0..=100).contains(&max_leverage) {
+            panic_with_error!(&env, ConfigError::InvalidLeverage);
+        }
+        put_config_value(&env, &DataKey::CircuitBreakerMaxLeverage, max_leverage);
+    }
+
+    /// Get the max leverage applied while the circuit breaker is tripped.
+    ///
+    /// # Returns
+    ///
+    /// The cap on new positions' leverage while tripped (0 = disabled)
+    pub fn circuit_breaker_max_leverage(env: Env) -> i128 {
+        get_config_value(&env, &DataKey::CircuitBreakerMaxLeverage)
+    }
+
+    /// Set a price staleness threshold overriding the global one for a single market,
+    /// e.g. a tighter limit for volatile small-cap markets.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    /// * `threshold` - Maximum oracle price age in seconds (must be >= 1)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the threshold is 0
+    pub fn set_market_staleness_threshold(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        threshold: u64,
+    ) {
+        require_admin(&env, &admin);
+        if threshold < 1 {
+            panic_with_error!(&env, ConfigError::InvalidDuration);
+        }
+        let key = DataKey::MarketPriceStalenessThreshold(market_id);
+        count_market_override(&env, &key);
+        put_time_config_value(&env, &key, threshold);
+    }
+
+    /// Remove a market's staleness override so it falls back to the global threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn clear_market_staleness_threshold(env: Env, admin: Address, market_id: u32) {
+        require_admin(&env, &admin);
+        remove_market_override(&env, &DataKey::MarketPriceStalenessThreshold(market_id));
+    }
+
+    /// Get the effective price staleness threshold for a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// Maximum oracle price age in seconds: the market override if set, otherwise the
+    /// global threshold
+    pub fn market_staleness_threshold(env: Env, market_id: u32) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MarketPriceStalenessThreshold(market_id))
+            .unwrap_or_else(|| get_time_config_value(&env, &DataKey::PriceStalenessThreshold))
+    }
+
+    /// Set a lot size for a market: position, order and size change amounts must be
+    /// whole multiples of it, keeping sizes aligned with external hedging venues.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    /// * `lot_size` - Minimum size increment (must be > 0)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the lot size is not positive
+    pub fn set_market_lot_size(env: Env, admin: Address, market_id: u32, lot_size: i128) {
+        require_admin(&env, &admin);
+        if lot_size <= 0 {
+            panic_with_error!(&env, ConfigError::InvalidAmount);
+        }
+        let key = DataKey::MarketLotSize(market_id);
+        count_market_override(&env, &key);
+        put_config_value(&env, &key, lot_size);
+    }
+
+    /// Remove a market's lot size so any size is accepted again.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn clear_market_lot_size(env: Env, admin: Address, market_id: u32) {
+        require_admin(&env, &admin);
+        remove_market_override(&env, &DataKey::MarketLotSize(market_id));
+    }
+
+    /// Get the lot size of a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// The minimum size increment, or 0 if the market has no lot size
+    pub fn market_lot_size(env: Env, market_id: u32) -> i128 {
+        get_config_value(&env, &DataKey::MarketLotSize(market_id))
+    }
+
+    /// Cap the total notional of positions a market accepts within one ledger, making
+    /// it uneconomical to race a known pending oracle update with a burst of opens.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    /// * `max_notional` - Notional accepted per ledger across all traders (must be > 0)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the cap is not positive
+    pub fn set_market_ledger_open_cap(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        max_notional: i128,
+    ) {
+        require_admin(&env, &admin);
+        if max_notional <= 0 {
+            panic_with_error!(&env, ConfigError::InvalidAmount);
+        }
+        let key = DataKey::MarketLedgerOpenCap(market_id);
+        count_market_override(&env, &key);
+        put_config_value(&env, &key, max_notional);
+    }
+
+    /// Remove a market's per-ledger open cap.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn clear_market_ledger_open_cap(env: Env, admin: Address, market_id: u32) {
+        require_admin(&env, &admin);
+        remove_market_override(&env, &DataKey::MarketLedgerOpenCap(market_id));
+    }
+
+    /// Get the per-ledger open cap of a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
     ///
     /// # Returns
     ///
-    /// Minimum reserve ratio in basis points (default: 2000 = 20%)
-    pub fn min_liquidity_reserve_ratio(env: Env) -> i128 {
-        get_config_value(&env, &DataKey::MinLiquidityReserveRatio)
+    /// Notional of new positions accepted per ledger, or 0 if the market has no cap
+    pub fn market_ledger_open_cap(env: Env, market_id: u32) -> i128 {
+        get_config_value(&env, &DataKey::MarketLedgerOpenCap(market_id))
     }
 
-    /// Set minimum liquidity reserve ratio in basis points.
+    /// Set a market's risk tiers: positions up to each tier's notional must post at
+    /// least its initial margin, so larger positions get less leverage. Positions above
+    /// the last tier's notional cannot be opened.
     ///
     /// # Arguments
     ///
     /// * `admin` - The administrator address
-    /// * `ratio` - The minimum reserve ratio in basis points (e.g., 2000 = 20%)
+    /// * `market_id` - The market identifier
+    /// * `tiers` - 1 to 10 tiers with strictly increasing `max_notional` and
+    ///   non-decreasing `initial_margin_bps` (1-10000)
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the admin or ratio is invalid
-    pub fn set_min_liquidity_reserve_ratio(env: Env, admin: Address, ratio: i128) {
+    /// Panics if caller is not the admin or the tier table is invalid
+    pub fn set_market_risk_tiers(env: Env, admin: Address, market_id: u32, tiers: Vec<RiskTier>) {
         require_admin(&env, &admin);
-        if ratio < 0 || ratio > 10000 {
-            panic!("invalid reserve ratio");
+        if tiers.is_empty() || tiers.len() > MAX_RISK_TIERS {
+            panic_with_error!(&env, ConfigError::InvalidRiskTiers);
         }
-        put_config_value(&env, &DataKey::MinLiquidityReserveRatio, ratio);
+        let mut previous: Option<RiskTier> = None;
+        for tier in tiers.iter() {
+            if tier.max_notional == 0 {
+                panic_with_error!(&env, ConfigError::InvalidRiskTiers);
+            }
+            if tier.initial_margin_bps == 0 || tier.initial_margin_bps > 10000 {
+                panic_with_error!(&env, ConfigError::InvalidRiskTiers);
+            }
+            if let Some(previous) = previous {
+                if tier.max_notional <= previous.max_notional {
+                    panic_with_error!(&env, ConfigError::InvalidRiskTiers);
+                }
+                if tier.initial_margin_bps < previous.initial_margin_bps {
+                    panic_with_error!(&env, ConfigError::InvalidRiskTiers);
+                }
+            }
+            previous = Some(tier);
+        }
+        let key = DataKey::MarketRiskTiers(market_id);
+        count_market_override(&env, &key);
+        env.storage().instance().set(&key, &tiers);
     }
 
-    /// Get borrow rate per second (scaled by 1e7).
+    /// Remove a market's risk tiers so only its leverage limits apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `market_id` - The market identifier
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn clear_market_risk_tiers(env: Env, admin: Address, market_id: u32) {
+        require_admin(&env, &admin);
+        remove_market_override(&env, &DataKey::MarketRiskTiers(market_id));
+    }
+
+    /// Get a market's risk tier schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
     ///
     /// # Returns
     ///
-    /// Borrow rate per second for calculating borrowing fees
-    pub fn borrow_rate_per_second(env: Env) -> i128 {
-        get_config_value(&env, &DataKey::BorrowRatePerSecond)
+    /// The tiers by ascending notional, or an empty list if the market has none
+    pub fn market_risk_tiers(env: Env, market_id: u32) -> Vec<RiskTier> {
+        env.storage()
+            .instance()
+            .get(&DataKey::MarketRiskTiers(market_id))
+            .unwrap_or_else(|| Vec::new(&env))
     }
 
-    /// Set borrow rate per second.
+    /// Set a market's leverage decay: the max leverage for new positions falls linearly
+    /// from the market's limit at `start_utilization_bps` of its OI cap to
+    /// `floor_leverage` at `end_utilization_bps`, so the book gets harder to crowd the
+    /// closer it is to full. Open positions keep their leverage.
     ///
     /// # Arguments
     ///
     /// * `admin` - The administrator address
-    /// * `rate` - Borrow rate per second (scaled by 1e7, must be >= 0)
+    /// * `market_id` - The market identifier
+    /// * `decay` - Start below end (at most 10000 bps) and a floor of 1-100
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the admin or rate is negative
-    pub fn set_borrow_rate_per_second(env: Env, admin: Address, rate: i128) {
+    /// Panics if caller is not the admin or the decay is invalid
+    pub fn set_market_leverage_decay(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        decay: LeverageDecay,
+    ) {
         require_admin(&env, &admin);
-        if rate < 0 {
-            panic!("borrow rate must be >= 0");
+        if decay.start_utilization_bps >= decay.end_utilization_bps
+            || decay.end_utilization_bps > 10000
+        {
+            panic_with_error!(&env, ConfigError::InvalidBps);
         }
-        put_config_value(&env, &DataKey::BorrowRatePerSecond, rate);
+        if !(1..=100).contains(&decay.floor_leverage) {
+            panic_with_error!(&env, ConfigError::InvalidLeverage);
+        }
+        let key = DataKey::MarketLeverageDecay(market_id);
+        count_market_override(&env, &key);
+        env.storage().instance().set(&key, &decay);
     }
 
-    /// Set leverage limits.
+    /// Remove a market's leverage decay so its max leverage no longer depends on OI.
     ///
     /// # Arguments
     ///
     /// * `admin` - The administrator address
-    /// * `min_leverage` - Minimum leverage (must be >= 1)
-    /// * `max_leverage` - Maximum leverage (must be > min_leverage and <= 100)
+    /// * `market_id` - The market identifier
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the admin or limits are invalid
-    pub fn set_leverage_limits(env: Env, admin: Address, min_leverage: i128, max_leverage: i128) {
+    /// Panics if caller is not the admin
+    pub fn clear_market_leverage_decay(env: Env, admin: Address, market_id: u32) {
         require_admin(&env, &admin);
-        if min_leverage < 1 {
-            panic!("min leverage must be >= 1");
-        }
-        if max_leverage <= min_leverage {
-            panic!("max leverage must be > min leverage");
-        }
-        if max_leverage > 100 {
-            panic!("max leverage must be <= 100");
-        }
-        put_config_value(&env, &DataKey::MinLeverage, min_leverage);
-        put_config_value(&env, &DataKey::MaxLeverage, max_leverage);
+        remove_market_override(&env, &DataKey::MarketLeverageDecay(market_id));
+    }
+
+    /// Get a market's leverage decay.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// The decay, or None if the market's max leverage does not depend on OI
+    pub fn market_leverage_decay(env: Env, market_id: u32) -> Option<LeverageDecay> {
+        env.storage()
+            .instance()
+            .get(&DataKey::MarketLeverageDecay(market_id))
     }
 
     /// Set minimum position size.
@@ -571,11 +2448,41 @@ impl ConfigManager {
     pub fn set_min_position_size(env: Env, admin: Address, size: i128) {
         require_admin(&env, &admin);
         if size <= 0 {
-            panic!("min position size must be > 0");
+            panic_with_error!(&env, ConfigError::InvalidAmount);
         }
         put_config_value(&env, &DataKey::MinPositionSize, size);
     }
 
+    /// Set the USD floor on new position sizes, so the minimum does not drift with the
+    /// collateral token's price. Sizes are valued at the oracle price of
+    /// `price_market_id`; while that price is unavailable the token-unit floor from
+    /// `set_min_position_size()` applies instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `min_usd` - Minimum position size in USD, scaled by 1e7 (0 disables)
+    /// * `price_market_id` - Oracle market quoting the collateral token in USD
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or `min_usd` is negative
+    pub fn set_min_position_size_usd(
+        env: Env,
+        admin: Address,
+        min_usd: i128,
+        price_market_id: u32,
+    ) {
+        require_admin(&env, &admin);
+        if min_usd < 0 {
+            panic_with_error!(&env, ConfigError::InvalidAmount);
+        }
+        put_config_value(&env, &DataKey::MinPositionSizeUsd, min_usd);
+        env.storage()
+            .instance()
+            .set(&DataKey::CollateralPriceMarket, &price_market_id);
+    }
+
     /// Set fee parameters in basis points.
     ///
     /// # Arguments
@@ -590,20 +2497,121 @@ impl ConfigManager {
     /// Panics if caller is not the admin or fees are invalid
     pub fn set_fees(env: Env, admin: Address, maker_fee: i128, taker_fee: i128, liquidation_fee: i128) {
         require_admin(&env, &admin);
-        if maker_fee < 0 || maker_fee > 1000 {
-            panic!("maker fee must be 0-1000 bps");
+        if !(0..=1000).contains(&maker_fee) {
+            panic_with_error!(&env, ConfigError::InvalidFee);
         }
-        if taker_fee < 0 || taker_fee > 1000 {
-            panic!("taker fee must be 0-1000 bps");
+        if !(0..=1000).contains(&taker_fee) {
+            panic_with_error!(&env, ConfigError::InvalidFee);
         }
-        if liquidation_fee < 0 || liquidation_fee > 1000 {
-            panic!("liquidation fee must be 0-1000 bps");
+        if !(0..=1000).contains(&liquidation_fee) {
+            panic_with_error!(&env, ConfigError::InvalidFee);
         }
         put_config_value(&env, &DataKey::MakerFeeBps, maker_fee);
         put_config_value(&env, &DataKey::TakerFeeBps, taker_fee);
         put_config_value(&env, &DataKey::LiquidationFeeBps, liquidation_fee);
     }
 
+    /// Set the floor and cap applied to the keeper's share of the liquidation fee.
+    ///
+    /// The reward is still limited to the position's collateral.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `min_reward` - Minimum keeper reward in token units
+    /// * `max_reward` - Maximum keeper reward in token units (at least `min_reward`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the bounds are invalid
+    pub fn set_keeper_reward_limits(env: Env, admin: Address, min_reward: i128, max_reward: i128) {
+        require_admin(&env, &admin);
+        if min_reward < 0 || max_reward < min_reward {
+            panic_with_error!(&env, ConfigError::InvalidKeeperRewardLimits);
+        }
+        put_config_value(&env, &DataKey::KeeperMinReward, min_reward);
+        put_config_value(&env, &DataKey::KeeperMaxReward, max_reward);
+    }
+
+    /// Set the liquidation rebate buffer. When a liquidated position's equity exceeds the
+    /// liquidation fees plus `buffer_bps` of its size, the excess is returned to the
+    /// trader instead of staying in the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `buffer_bps` - Buffer in basis points of position size (0 disables rebates)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the buffer exceeds 10000 bps
+    pub fn set_liquidation_rebate_buffer(env: Env, admin: Address, buffer_bps: i128) {
+        require_admin(&env, &admin);
+        if !(0..=10000).contains(&buffer_bps) {
+            panic_with_error!(&env, ConfigError::InvalidBps);
+        }
+        put_config_value(&env, &DataKey::LiquidationRebateBufferBps, buffer_bps);
+    }
+
+    /// Set the keeper priority window for liquidations. With a KeeperRegistry
+    /// registered, bonded keepers get this many seconds of exclusive rights to a
+    /// position once it is flagged liquidatable; after that anyone may liquidate it.
+    /// Without a registry liquidation is permissionless and the window has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `window` - Exclusive seconds for bonded keepers (0 keeps liquidation bonded-only)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the window exceeds one hour
+    pub fn set_liquidation_priority_window(env: Env, admin: Address, window: u64) {
+        require_admin(&env, &admin);
+        if window > 3600 {
+            panic_with_error!(&env, ConfigError::InvalidDuration);
+        }
+        put_time_config_value(&env, &DataKey::LiquidationPriorityWindow, window);
+    }
+
+    /// Set the anti-scalping fee for positions closed shortly after opening.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `min_hold_duration` - Seconds a position must stay open to close at the standard rate (0 disables)
+    /// * `fee_bps` - Extra fee on size closed earlier, in basis points (max 1000 = 10%)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the fee is invalid
+    pub fn set_early_close_fee(env: Env, admin: Address, min_hold_duration: u64, fee_bps: i128) {
+        require_admin(&env, &admin);
+        if !(0..=1000).contains(&fee_bps) {
+            panic_with_error!(&env, ConfigError::InvalidFee);
+        }
+        put_time_config_value(&env, &DataKey::MinHoldDuration, min_hold_duration);
+        put_config_value(&env, &DataKey::EarlyCloseFeeBps, fee_bps);
+    }
+
+    /// Set the funding-arbitrage guard: funding received by a position closed within
+    /// `duration` seconds of opening is forfeited to the pool, so opening just to collect
+    /// funding and closing again cannot turn a profit. One funding interval is a typical
+    /// setting.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `duration` - Seconds a position must stay open for received funding to count (0 disables)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_funding_min_hold_duration(env: Env, admin: Address, duration: u64) {
+        require_admin(&env, &admin);
+        put_time_config_value(&env, &DataKey::FundingMinHoldDuration, duration);
+    }
+
     /// Set risk parameters.
     ///
     /// # Arguments
@@ -618,13 +2626,13 @@ impl ConfigManager {
     pub fn set_risk_params(env: Env, admin: Address, liquidation_threshold: i128, maintenance_margin: i128) {
         require_admin(&env, &admin);
         if maintenance_margin <= 0 || maintenance_margin > 10000 {
-            panic!("maintenance margin must be 1-10000 bps");
+            panic_with_error!(&env, ConfigError::InvalidMargin);
         }
         if liquidation_threshold <= maintenance_margin {
-            panic!("liquidation threshold must be > maintenance margin");
+            panic_with_error!(&env, ConfigError::InvalidMargin);
         }
         if liquidation_threshold > 10000 {
-            panic!("liquidation threshold must be <= 10000 bps");
+            panic_with_error!(&env, ConfigError::InvalidMargin);
         }
         put_config_value(&env, &DataKey::LiquidationThreshold, liquidation_threshold);
         put_config_value(&env, &DataKey::MaintenanceMargin, maintenance_margin);
@@ -642,12 +2650,31 @@ impl ConfigManager {
     /// Panics if caller is not the admin or deviation is invalid
     pub fn set_max_price_deviation(env: Env, admin: Address, deviation: i128) {
         require_admin(&env, &admin);
-        if deviation < 1 || deviation > 5000 {
-            panic!("price deviation must be 1-5000 bps");
+        if !(1..=5000).contains(&deviation) {
+            panic_with_error!(&env, ConfigError::InvalidBps);
         }
         put_config_value(&env, &DataKey::MaxPriceDeviationBps, deviation);
     }
 
+    /// Set the most funding a position can be charged per funding interval. Funding
+    /// owed above the cap is carried forward rather than forgiven.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `max_bps` - Cap in bps of the position's collateral (must be 1-10000)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the cap is invalid
+    pub fn set_max_funding_per_interval(env: Env, admin: Address, max_bps: i128) {
+        require_admin(&env, &admin);
+        if !(1..=10000).contains(&max_bps) {
+            panic_with_error!(&env, ConfigError::InvalidBps);
+        }
+        put_config_value(&env, &DataKey::MaxFundingPerIntervalBps, max_bps);
+    }
+
     /// Set time parameters.
     ///
     /// # Arguments
@@ -662,14 +2689,285 @@ impl ConfigManager {
     pub fn set_time_params(env: Env, admin: Address, funding_interval: u64, staleness_threshold: u64) {
         require_admin(&env, &admin);
         if funding_interval < 1 {
-            panic!("funding interval must be >= 1");
+            panic_with_error!(&env, ConfigError::InvalidDuration);
         }
         if staleness_threshold < 1 {
-            panic!("staleness threshold must be >= 1");
+            panic_with_error!(&env, ConfigError::InvalidDuration);
         }
         put_time_config_value(&env, &DataKey::FundingInterval, funding_interval);
         put_time_config_value(&env, &DataKey::PriceStalenessThreshold, staleness_threshold);
     }
+
+    /// List the global configuration parameters with their value types and bounds.
+    ///
+    /// Each `key` names the getter returning the parameter, so tooling can read current
+    /// values and validate updates without hard-coding the catalog. Per-market overrides,
+    /// contract addresses and flags are not included.
+    ///
+    /// # Returns
+    ///
+    /// A ConfigKeyDescriptor per parameter, grouped by setter
+    pub fn list_config_keys(env: Env) -> Vec<ConfigKeyDescriptor> {
+        let mut keys = Vec::new(&env);
+        for (key, value_type, min, max, setter) in CONFIG_KEYS {
+            keys.push_back(ConfigKeyDescriptor {
+                key: Symbol::new(&env, key),
+                value_type,
+                min,
+                max,
+                setter: Symbol::new(&env, setter),
+            });
+        }
+        keys
+    }
+
+    /// Update several global parameters at once, all or nothing.
+    ///
+    /// Each update names a parameter by its `list_config_keys()` getter. The whole set is
+    /// validated before anything is written: every value must be within its parameter's
+    /// bounds, and the values the batch would leave in place must satisfy the
+    /// cross-parameter rules (max leverage above min leverage, liquidation threshold
+    /// above maintenance margin, keeper max reward at least the min). Coordinated
+    /// changes such as a fee restructure therefore never pass through an inconsistent
+    /// intermediate state. A borrow rate change checkpoints the borrow index first, as
+    /// `set_borrow_rate_per_second()` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `updates` - (getter name, new value) pairs; U64 parameters take the value as i128
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the batch is empty, a key is unknown or
+    /// repeated, a value is out of bounds, or the resulting parameters are inconsistent
+    pub fn set_config_batch(env: Env, admin: Address, updates: Vec<(Symbol, i128)>) {
+        require_admin(&env, &admin);
+        if updates.is_empty() {
+            panic_with_error!(&env, ConfigError::InvalidConfigBatch);
+        }
+
+        // Resolve every update to its catalog entry and check its bounds
+        let mut values: [Option<i128>; CONFIG_KEYS.len()] = [None; CONFIG_KEYS.len()];
+        let mut order = Vec::new(&env);
+        for (key, value) in updates.iter() {
+            let index = CONFIG_KEYS
+                .iter()
+                .position(|(name, ..)| Symbol::new(&env, name) == key)
+                .unwrap_or_else(|| panic_with_error!(&env, ConfigError::UnknownConfigKey));
+            if values[index].is_some() {
+                panic_with_error!(&env, ConfigError::InvalidConfigBatch);
+            }
+            let (_, _, min, max, _) = CONFIG_KEYS[index];
+            if value < min || value > max {
+                panic_with_error!(&env, ConfigError::ConfigValueOutOfBounds);
+            }
+            values[index] = Some(value);
+            order.push_back(index as u32);
+        }
+
+        // Check the cross-parameter rules against the values the batch leaves in place
+        let resulting = |name: &str| -> i128 {
+            let index = CONFIG_KEYS
+                .iter()
+                .position(|(key, ..)| *key == name)
+                .unwrap();
+            values[index].unwrap_or_else(|| get_config_value(&env, &config_param_key(&env, name)))
+        };
+        validate_leverage_limits(&env, resulting("min_leverage"), resulting("max_leverage"));
+        if resulting("liquidation_threshold") <= resulting("maintenance_margin") {
+            panic_with_error!(&env, ConfigError::InvalidMargin);
+        }
+        if resulting("keeper_max_reward") < resulting("keeper_min_reward") {
+            panic_with_error!(&env, ConfigError::InvalidKeeperRewardLimits);
+        }
+
+        // Apply
+        let mut keys = Vec::new(&env);
+        for index in order.iter() {
+            let (name, value_type, _, _, _) = CONFIG_KEYS[index as usize];
+            let value = values[index as usize].unwrap();
+            let key = config_param_key(&env, name);
+            match key {
+                DataKey::BorrowRatePerSecond => {
+                    let borrow_index = current_borrow_index(&env);
+                    put_config_value(&env, &DataKey::CumulativeBorrowIndex, borrow_index);
+                    put_time_config_value(
+                        &env,
+                        &DataKey::BorrowIndexUpdatedAt,
+                        env.ledger().timestamp(),
+                    );
+                }
+                DataKey::LeverageStepDown => {
+                    LeverageStepDownUpdatedEvent {
+                        max_leverage: value,
+                        seq: next_event_seq(&env),
+                    }
+                    .publish(&env);
+                }
+                _ => {}
+            }
+            match value_type {
+                ConfigValueType::I128 => put_config_value(&env, &key, value),
+                ConfigValueType::U64 => put_time_config_value(&env, &key, value as u64),
+            }
+            keys.push_back(Symbol::new(&env, name));
+        }
+
+        ConfigBatchAppliedEvent {
+            keys,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Get the number of global parameters, per-market overrides and named parameters
+    /// in storage, to monitor ledger rent exposure.
+    ///
+    /// # Returns
+    ///
+    /// The StorageUsage counters. Entries written before the counters were introduced
+    /// are not included.
+    pub fn get_storage_usage(env: Env) -> StorageUsage {
+        StorageUsage {
+            parameters: CONFIG_KEYS.len() as u32,
+            market_overrides: get_storage_count(&env, &DataKey::MarketOverrideCount),
+            named_values: get_storage_count(&env, &DataKey::NamedValueCount),
+        }
+    }
+
+    /// Set a named u32 parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `key` - Parameter name
+    /// * `value` - New value
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_config_u32(env: Env, admin: Address, key: Symbol, value: u32) {
+        require_admin(&env, &admin);
+        let key = NamedKey::U32(key);
+        count_named_value(&env, &key);
+        env.storage().persistent().set(&key, &value);
+    }
+
+    /// Get a named u32 parameter.
+    ///
+    /// # Returns
+    ///
+    /// The value, or None if it was never set
+    pub fn get_config_u32(env: Env, key: Symbol) -> Option<u32> {
+        env.storage().persistent().get(&NamedKey::U32(key))
+    }
+
+    /// Set a named bool parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `key` - Parameter name
+    /// * `value` - New value
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_config_bool(env: Env, admin: Address, key: Symbol, value: bool) {
+        require_admin(&env, &admin);
+        let key = NamedKey::Bool(key);
+        count_named_value(&env, &key);
+        env.storage().persistent().set(&key, &value);
+    }
+
+    /// Get a named bool parameter.
+    ///
+    /// # Returns
+    ///
+    /// The value, or None if it was never set
+    pub fn get_config_bool(env: Env, key: Symbol) -> Option<bool> {
+        env.storage().persistent().get(&NamedKey::Bool(key))
+    }
+
+    /// Set a named Address parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `key` - Parameter name
+    /// * `value` - New address
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_config_address(env: Env, admin: Address, key: Symbol, value: Address) {
+        require_admin(&env, &admin);
+        let key = NamedKey::Address(key);
+        count_named_value(&env, &key);
+        env.storage().persistent().set(&key, &value);
+    }
+
+    /// Get a named Address parameter.
+    ///
+    /// # Returns
+    ///
+    /// The address, or None if it was never set
+    pub fn get_config_address(env: Env, key: Symbol) -> Option<Address> {
+        env.storage().persistent().get(&NamedKey::Address(key))
+    }
+
+    /// Remove a named parameter from every type namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `key` - Parameter name
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn clear_config(env: Env, admin: Address, key: Symbol) {
+        require_admin(&env, &admin);
+        remove_named_value(&env, &NamedKey::U32(key.clone()));
+        remove_named_value(&env, &NamedKey::Bool(key.clone()));
+        remove_named_value(&env, &NamedKey::Address(key));
+    }
+
+    /// Set the maximum market pause duration before force settlement is allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `duration` - Max pause duration in seconds (0 disables force settlement)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin
+    pub fn set_max_pause_duration(env: Env, admin: Address, duration: u64) {
+        require_admin(&env, &admin);
+        put_time_config_value(&env, &DataKey::MaxPauseDuration, duration);
+    }
+
+    /// Draw the next protocol-wide event sequence number. Every protocol contract
+    /// stamps its events with one, so indexers can order events across contracts and
+    /// spot any they missed.
+    ///
+    /// # Returns
+    ///
+    /// The sequence number for the caller's next event
+    pub fn next_event_seq(env: Env) -> u64 {
+        next_event_seq(&env)
+    }
+
+    /// Get the sequence number of the last event any protocol contract emitted.
+    ///
+    /// # Returns
+    ///
+    /// The number of events stamped so far (0 before the first)
+    pub fn get_event_seq(env: Env) -> u64 {
+        env.storage().instance().get(&EventKey::Seq).unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger as _},
+    Address, Env, Event as _,
+};
 
 #[test]
 fn test_initialize_and_get_config() {
@@ -11,12 +14,9 @@ fn test_initialize_and_get_config() {
     let admin = Address::generate(&env);
 
     // Deploy config manager contract
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    // Initialize contract
-    client.initialize(&admin);
-
     // Test getting default values
     assert_eq!(client.min_leverage(), 5);
     assert_eq!(client.max_leverage(), 20);
@@ -35,11 +35,9 @@ fn test_set_leverage_limits() {
 
     let admin = Address::generate(&env);
 
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    client.initialize(&admin);
-
     // Verify defaults
     assert_eq!(client.min_leverage(), 5);
     assert_eq!(client.max_leverage(), 20);
@@ -57,11 +55,9 @@ fn test_set_fees() {
 
     let admin = Address::generate(&env);
 
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    client.initialize(&admin);
-
     // Verify defaults
     assert_eq!(client.maker_fee_bps(), 2);
     assert_eq!(client.taker_fee_bps(), 5);
@@ -81,11 +77,9 @@ fn test_set_risk_params() {
 
     let admin = Address::generate(&env);
 
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    client.initialize(&admin);
-
     // Verify defaults
     assert_eq!(client.liquidation_threshold(), 9000);
     assert_eq!(client.maintenance_margin(), 5000);
@@ -105,11 +99,9 @@ fn test_admin_transfer() {
     let admin2 = Address::generate(&env);
 
     // Deploy config manager contract
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin1, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    // Initialize contract with admin1
-    client.initialize(&admin1);
     assert_eq!(client.admin(), admin1);
 
     // Transfer admin to admin2
@@ -129,12 +121,9 @@ fn test_contract_registry() {
     let oi_contract = Address::generate(&env);
 
     // Deploy config manager contract
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    // Initialize contract
-    client.initialize(&admin);
-
     // Register all contracts
     client.set_liquidity_pool(&admin, &lp_contract);
     client.set_position_manager(&admin, &pm_contract);
@@ -148,6 +137,54 @@ fn test_contract_registry() {
     assert_eq!(client.oracle_integrator(), oi_contract);
 }
 
+#[test]
+fn test_is_protocol_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp_contract = Address::generate(&env);
+    let pm_contract = Address::generate(&env);
+    let mm_contract = Address::generate(&env);
+    let oi_contract = Address::generate(&env);
+    let om_contract = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Nothing is registered yet
+    assert_eq!(client.is_protocol_contract(&lp_contract), None);
+
+    client.set_liquidity_pool(&admin, &lp_contract);
+    client.set_position_manager(&admin, &pm_contract);
+    client.set_market_manager(&admin, &mm_contract);
+    client.set_oracle_integrator(&admin, &oi_contract);
+    client.set_order_manager(&admin, &om_contract);
+
+    assert_eq!(
+        client.is_protocol_contract(&lp_contract),
+        Some(ProtocolRole::LiquidityPool)
+    );
+    assert_eq!(
+        client.is_protocol_contract(&pm_contract),
+        Some(ProtocolRole::PositionManager)
+    );
+    assert_eq!(
+        client.is_protocol_contract(&mm_contract),
+        Some(ProtocolRole::MarketManager)
+    );
+    assert_eq!(
+        client.is_protocol_contract(&oi_contract),
+        Some(ProtocolRole::OracleIntegrator)
+    );
+    assert_eq!(
+        client.is_protocol_contract(&om_contract),
+        Some(ProtocolRole::OrderManager)
+    );
+    assert_eq!(client.order_manager(), om_contract);
+    assert_eq!(client.is_protocol_contract(&admin), None);
+}
+
 #[test]
 fn test_registry_update_addresses() {
     let env = Env::default();
@@ -158,12 +195,9 @@ fn test_registry_update_addresses() {
     let lp_contract_v2 = Address::generate(&env);
 
     // Deploy config manager contract
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    // Initialize contract
-    client.initialize(&admin);
-
     // Admin can set contract addresses
     client.set_liquidity_pool(&admin, &lp_contract_v1);
     assert_eq!(client.liquidity_pool(), lp_contract_v1);
@@ -174,16 +208,104 @@ fn test_registry_update_addresses() {
 }
 
 #[test]
-fn test_borrow_rate_per_second() {
+fn test_keeper_registry_toggles_permissioned_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Keeping is permissionless until a registry is set
+    assert_eq!(client.keeper_registry(), None);
+
+    client.set_keeper_registry(&admin, &Some(registry.clone()));
+    assert_eq!(client.keeper_registry(), Some(registry));
+
+    client.set_keeper_registry(&admin, &None);
+    assert_eq!(client.keeper_registry(), None);
+}
+
+#[test]
+fn test_keeper_rewards_set_and_clear() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let keeper_rewards = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Keepers are paid directly until a KeeperRewards contract is set
+    assert_eq!(client.keeper_rewards(), None);
+
+    client.set_keeper_rewards(&admin, &Some(keeper_rewards.clone()));
+    assert_eq!(client.keeper_rewards(), Some(keeper_rewards));
+
+    client.set_keeper_rewards(&admin, &None);
+    assert_eq!(client.keeper_rewards(), None);
+}
+
+#[test]
+fn test_campaign_set_and_clear() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
+    let campaign = Address::generate(&env);
 
-    let contract_id = env.register(ConfigManager, ());
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
 
-    client.initialize(&admin);
+    assert_eq!(client.campaign(), None);
+
+    client.set_campaign(&admin, &Some(campaign.clone()));
+    assert_eq!(client.campaign(), Some(campaign));
+
+    client.set_campaign(&admin, &None);
+    assert_eq!(client.campaign(), None);
+}
+
+#[test]
+fn test_network_profile() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let campaign = Address::generate(&env);
+
+    // The testnet profile keeps its hooks
+    let testnet_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let testnet = ConfigManagerClient::new(&env, &testnet_id);
+    assert_eq!(testnet.network_profile(), NetworkProfile::Testnet);
+    assert!(testnet.test_hooks_allowed());
+
+    let mainnet_id = env.register(ConfigManager, (&admin, NetworkProfile::Mainnet));
+    let mainnet = ConfigManagerClient::new(&env, &mainnet_id);
+    assert_eq!(env.events().all().len(), 1);
+    assert_eq!(mainnet.network_profile(), NetworkProfile::Mainnet);
+    assert!(!mainnet.test_hooks_allowed());
+
+    // Campaigns can't be started on mainnet, but clearing one is harmless
+    assert!(mainnet
+        .try_set_campaign(&admin, &Some(campaign.clone()))
+        .is_err());
+    mainnet.set_campaign(&admin, &None);
+    assert_eq!(mainnet.campaign(), None);
+}
+
+#[test]
+fn test_borrow_rate_per_second() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
 
     // Check default value
     assert_eq!(client.borrow_rate_per_second(), 1);
@@ -198,17 +320,969 @@ fn test_borrow_rate_per_second() {
 }
 
 #[test]
-#[should_panic(expected = "borrow rate must be >= 0")]
-fn test_borrow_rate_negative_fails() {
+fn test_cumulative_borrow_index_checkpoints_rate_changes() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
 
-    let contract_id = env.register(ConfigManager, ());
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
     let client = ConfigManagerClient::new(&env, &contract_id);
+    assert_eq!(client.cumulative_borrow_index(), 0);
+
+    // 100 seconds at the default rate of 1
+    env.ledger().with_mut(|li| li.timestamp = 1_100);
+    assert_eq!(client.cumulative_borrow_index(), 100);
+
+    // Raising the rate doesn't reprice the elapsed time
+    client.set_borrow_rate_per_second(&admin, &10);
+    assert_eq!(client.cumulative_borrow_index(), 100);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_150);
+    assert_eq!(client.cumulative_borrow_index(), 600);
+
+    client.set_borrow_rate_per_second(&admin, &0);
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    assert_eq!(client.cumulative_borrow_index(), 600);
+}
 
-    client.initialize(&admin);
+#[test]
+#[should_panic(expected = "Error(Contract, #114)")]
+fn test_borrow_rate_negative_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
 
     client.set_borrow_rate_per_second(&admin, &-1);
 }
+
+#[test]
+fn test_market_leverage_override() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Without an override, markets use the global limits
+    assert_eq!(client.market_leverage_limits(&1), (5, 20));
+
+    // BTC keeps 20x, long-tail market 3 is capped at 10x
+    client.set_market_leverage_limits(&admin, &3, &2, &10);
+    assert_eq!(client.market_leverage_limits(&3), (2, 10));
+    assert_eq!(client.market_leverage_limits(&1), (5, 20));
+
+    // Global changes don't affect the override
+    client.set_leverage_limits(&admin, &5, &50);
+    assert_eq!(client.market_leverage_limits(&3), (2, 10));
+    assert_eq!(client.market_leverage_limits(&1), (5, 50));
+
+    // Clearing falls back to the global limits
+    client.clear_market_leverage_limits(&admin, &3);
+    assert_eq!(client.market_leverage_limits(&3), (5, 50));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #107)")]
+fn test_market_leverage_override_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_market_leverage_limits(&admin, &0, &10, &10);
+}
+
+#[test]
+fn test_max_pause_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Default: 7 days
+    assert_eq!(client.max_pause_duration(), 604_800);
+
+    client.set_max_pause_duration(&admin, &86_400);
+    assert_eq!(client.max_pause_duration(), 86_400);
+}
+
+#[test]
+fn test_market_staleness_override() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Markets fall back to the global threshold
+    assert_eq!(client.market_staleness_threshold(&3), 60);
+
+    client.set_market_staleness_threshold(&admin, &3, &15);
+    assert_eq!(client.market_staleness_threshold(&3), 15);
+    assert_eq!(client.market_staleness_threshold(&1), 60);
+
+    // Global changes don't affect the override
+    client.set_time_params(&admin, &60, &120);
+    assert_eq!(client.market_staleness_threshold(&3), 15);
+    assert_eq!(client.market_staleness_threshold(&1), 120);
+
+    client.clear_market_staleness_threshold(&admin, &3);
+    assert_eq!(client.market_staleness_threshold(&3), 120);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #112)")]
+fn test_market_staleness_override_zero() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_market_staleness_threshold(&admin, &3, &0);
+}
+
+#[test]
+fn test_market_lot_size() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Markets have no lot size until one is set
+    assert_eq!(client.market_lot_size(&0), 0);
+
+    client.set_market_lot_size(&admin, &0, &1_000_000);
+    assert_eq!(client.market_lot_size(&0), 1_000_000);
+    assert_eq!(client.market_lot_size(&1), 0);
+
+    client.clear_market_lot_size(&admin, &0);
+    assert_eq!(client.market_lot_size(&0), 0);
+}
+
+#[test]
+fn test_market_ledger_open_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert_eq!(client.market_ledger_open_cap(&0), 0);
+
+    client.set_market_ledger_open_cap(&admin, &0, &50_000_000_000);
+    assert_eq!(client.market_ledger_open_cap(&0), 50_000_000_000);
+    assert_eq!(client.market_ledger_open_cap(&1), 0);
+    assert_eq!(client.get_storage_usage().market_overrides, 1);
+    assert!(client
+        .try_set_market_ledger_open_cap(&admin, &1, &0)
+        .is_err());
+
+    client.clear_market_ledger_open_cap(&admin, &0);
+    assert_eq!(client.market_ledger_open_cap(&0), 0);
+    assert_eq!(client.get_storage_usage().market_overrides, 0);
+}
+
+#[test]
+fn test_market_risk_tiers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert!(client.market_risk_tiers(&0).is_empty());
+
+    let tier = |max_notional: u128, initial_margin_bps: u32| RiskTier {
+        max_notional,
+        initial_margin_bps,
+    };
+    let tiers = vec![&env, tier(10_000, 500), tier(100_000, 1000)];
+    client.set_market_risk_tiers(&admin, &0, &tiers);
+    assert_eq!(client.market_risk_tiers(&0), tiers);
+    assert!(client.market_risk_tiers(&1).is_empty());
+
+    // Notionals must increase and margins must not fall
+    let unordered = vec![&env, tier(100_000, 500), tier(10_000, 1000)];
+    assert!(client
+        .try_set_market_risk_tiers(&admin, &0, &unordered)
+        .is_err());
+    let looser = vec![&env, tier(10_000, 1000), tier(100_000, 500)];
+    assert!(client
+        .try_set_market_risk_tiers(&admin, &0, &looser)
+        .is_err());
+    let no_margin = vec![&env, tier(10_000, 0)];
+    assert!(client
+        .try_set_market_risk_tiers(&admin, &0, &no_margin)
+        .is_err());
+    assert!(client
+        .try_set_market_risk_tiers(&admin, &0, &Vec::new(&env))
+        .is_err());
+
+    client.clear_market_risk_tiers(&admin, &0);
+    assert!(client.market_risk_tiers(&0).is_empty());
+}
+
+#[test]
+fn test_market_leverage_decay() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert_eq!(client.market_leverage_decay(&0), None);
+
+    let decay = |start_utilization_bps: u32, end_utilization_bps: u32, floor_leverage: i128| {
+        LeverageDecay {
+            start_utilization_bps,
+            end_utilization_bps,
+            floor_leverage,
+        }
+    };
+    client.set_market_leverage_decay(&admin, &0, &decay(5000, 9500, 5));
+    assert_eq!(client.market_leverage_decay(&0), Some(decay(5000, 9500, 5)));
+    assert_eq!(client.market_leverage_decay(&1), None);
+    assert_eq!(client.get_storage_usage().market_overrides, 1);
+
+    // The decay must start below its end, within the cap, down to a valid leverage
+    assert!(client
+        .try_set_market_leverage_decay(&admin, &0, &decay(9500, 5000, 5))
+        .is_err());
+    assert!(client
+        .try_set_market_leverage_decay(&admin, &0, &decay(5000, 10001, 5))
+        .is_err());
+    assert!(client
+        .try_set_market_leverage_decay(&admin, &0, &decay(5000, 9500, 0))
+        .is_err());
+
+    client.clear_market_leverage_decay(&admin, &0);
+    assert_eq!(client.market_leverage_decay(&0), None);
+    assert_eq!(client.get_storage_usage().market_overrides, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #114)")]
+fn test_market_lot_size_zero() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_market_lot_size(&admin, &0, &0);
+}
+
+#[test]
+fn test_early_close_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Disabled by default
+    assert_eq!(client.min_hold_duration(), 0);
+    assert_eq!(client.early_close_fee_bps(), 0);
+
+    client.set_early_close_fee(&admin, &60, &10);
+    assert_eq!(client.min_hold_duration(), 60);
+    assert_eq!(client.early_close_fee_bps(), 10);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #108)")]
+fn test_early_close_fee_too_high() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_early_close_fee(&admin, &60, &1001);
+}
+
+#[test]
+fn test_keeper_reward_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert_eq!(client.keeper_min_reward(), 5_000_000);
+    assert_eq!(client.keeper_max_reward(), 100_000_000_000);
+
+    client.set_keeper_reward_limits(&admin, &10_000_000, &50_000_000);
+    assert_eq!(client.keeper_min_reward(), 10_000_000);
+    assert_eq!(client.keeper_max_reward(), 50_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #111)")]
+fn test_keeper_reward_limits_inverted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_keeper_reward_limits(&admin, &50_000_000, &10_000_000);
+}
+
+#[test]
+fn test_liquidation_rebate_buffer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Rebates are off until a buffer is set
+    assert_eq!(client.liquidation_rebate_buffer_bps(), 0);
+
+    client.set_liquidation_rebate_buffer(&admin, &20);
+    assert_eq!(client.liquidation_rebate_buffer_bps(), 20);
+
+    assert!(client
+        .try_set_liquidation_rebate_buffer(&admin, &10_001)
+        .is_err());
+}
+
+#[test]
+fn test_liquidation_priority_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Bonded-only until a window is set
+    assert_eq!(client.liquidation_priority_window(), 0);
+
+    client.set_liquidation_priority_window(&admin, &30);
+    assert_eq!(client.liquidation_priority_window(), 30);
+
+    assert!(client
+        .try_set_liquidation_priority_window(&admin, &3601)
+        .is_err());
+}
+
+#[test]
+fn test_paused_withdrawal_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // 10% of pool value per day by default
+    assert_eq!(client.paused_withdrawal_limit_bps(), 1000);
+    assert_eq!(client.paused_withdrawal_interval(), 86_400);
+
+    client.set_paused_withdrawal_limit(&admin, &500, &3600);
+    assert_eq!(client.paused_withdrawal_limit_bps(), 500);
+    assert_eq!(client.paused_withdrawal_interval(), 3600);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #113)")]
+fn test_paused_withdrawal_limit_too_high() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_paused_withdrawal_limit(&admin, &10_001, &3600);
+}
+
+#[test]
+fn test_max_funding_per_interval() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert_eq!(client.max_funding_per_interval_bps(), 1000);
+
+    client.set_max_funding_per_interval(&admin, &250);
+    assert_eq!(client.max_funding_per_interval_bps(), 250);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #113)")]
+fn test_max_funding_per_interval_zero_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_max_funding_per_interval(&admin, &0);
+}
+
+#[test]
+fn test_leverage_step_down() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert_eq!(client.leverage_step_down(), 0);
+    assert_eq!(client.circuit_breaker_max_leverage(), 0);
+
+    client.set_leverage_step_down(&admin, &5);
+    client.set_circuit_breaker_leverage(&admin, &3);
+    assert_eq!(client.leverage_step_down(), 5);
+    assert_eq!(client.circuit_breaker_max_leverage(), 3);
+
+    // Market limits are left alone
+    assert_eq!(client.market_leverage_limits(&0), (5, 20));
+
+    client.set_leverage_step_down(&admin, &0);
+    assert_eq!(client.leverage_step_down(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #107)")]
+fn test_leverage_step_down_above_max_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_leverage_step_down(&admin, &101);
+}
+
+#[test]
+fn test_integrator_allow_list() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let vault = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Integrators are denied by default
+    assert!(!client.is_integrator_allowed(&vault));
+
+    client.set_integrator_allowed(&admin, &vault, &true);
+    assert!(client.is_integrator_allowed(&vault));
+
+    client.set_integrator_allowed(&admin, &vault, &false);
+    assert!(!client.is_integrator_allowed(&vault));
+}
+
+/// External compliance policy allowing only accounts explicitly approved
+#[soroban_sdk::contract]
+struct MockAllowlist;
+
+#[soroban_sdk::contractimpl]
+impl MockAllowlist {
+    pub fn approve(env: Env, account: Address) {
+        env.storage().persistent().set(&account, &true);
+    }
+
+    pub fn is_allowed(env: Env, account: Address) -> bool {
+        env.storage().persistent().has(&account)
+    }
+}
+
+#[test]
+fn test_compliance_hook() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Disabled by default: the blocklist is recorded but not enforced
+    assert!(!client.compliance_enabled());
+    client.set_blocked(&admin, &trader, &true);
+    assert!(client.is_blocked(&trader));
+    assert!(client.is_account_allowed(&trader));
+
+    client.set_compliance_enabled(&admin, &true);
+    assert!(!client.is_account_allowed(&trader));
+    assert!(client.is_account_allowed(&other));
+
+    // An external allowlist is consulted after the blocklist
+    let allowlist_id = env.register(MockAllowlist, ());
+    let allowlist = MockAllowlistClient::new(&env, &allowlist_id);
+    client.set_compliance_contract(&admin, &Some(allowlist_id.clone()));
+    assert!(!client.is_account_allowed(&other));
+    allowlist.approve(&other);
+    assert!(client.is_account_allowed(&other));
+    allowlist.approve(&trader);
+    assert!(!client.is_account_allowed(&trader));
+
+    // Removing the hook restores open access
+    client.set_compliance_contract(&admin, &None);
+    client.set_blocked(&admin, &trader, &false);
+    assert!(client.is_account_allowed(&trader));
+    client.set_compliance_enabled(&admin, &false);
+    assert_eq!(client.compliance_contract(), None);
+}
+
+#[test]
+fn test_token_and_oracle_changes_require_global_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // The first assignment and re-setting the same address are not migrations
+    client.set_token(&admin, &token);
+    client.set_oracle_integrator(&admin, &oracle);
+    client.set_token(&admin, &token);
+
+    let new_token = Address::generate(&env);
+    let new_oracle = Address::generate(&env);
+    assert!(client.try_set_token(&admin, &new_token).is_err());
+    assert!(client
+        .try_set_oracle_integrator(&admin, &new_oracle)
+        .is_err());
+
+    // With no MarketManager registered there is no open interest
+    assert!(!client.is_globally_paused());
+    client.set_global_pause(&admin, &true);
+    assert!(client.is_globally_paused());
+    client.set_token(&admin, &new_token);
+    client.set_oracle_integrator(&admin, &new_oracle);
+    assert_eq!(client.token(), new_token);
+    assert_eq!(client.oracle_integrator(), new_oracle);
+    client.set_global_pause(&admin, &false);
+
+    // Forcing skips the interlocks and leaves an audit event
+    client.force_set_token(&admin, &token);
+    assert_eq!(env.events().all().len(), 1);
+    assert_eq!(client.token(), token);
+}
+
+#[test]
+fn test_trip_global_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let pool = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    // Only the registered LiquidityPool may trip the pause
+    assert!(client.try_trip_global_pause(&pool).is_err());
+    client.set_liquidity_pool(&admin, &pool);
+    client.trip_global_pause(&pool);
+    assert_eq!(env.events().all().len(), 2);
+    assert!(client.is_globally_paused());
+
+    // Lifting it is left to the admin
+    client.set_global_pause(&admin, &false);
+    assert!(!client.is_globally_paused());
+}
+
+#[test]
+fn test_migrate_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let pool = Address::generate(&env);
+    let old_token = Address::generate(&env);
+    let new_token = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+    client.set_token(&admin, &old_token);
+
+    // Only the registered LiquidityPool may repoint the token, and only while paused
+    assert!(client.try_migrate_token(&pool, &new_token).is_err());
+    client.set_liquidity_pool(&admin, &pool);
+    assert!(client.try_migrate_token(&pool, &new_token).is_err());
+
+    client.set_global_pause(&admin, &true);
+    client.migrate_token(&pool, &new_token);
+    assert_eq!(env.events().all().len(), 1);
+    assert_eq!(client.token(), new_token);
+}
+
+#[test]
+fn test_operation_pause_switches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert!(!client.is_operation_paused(&PauseOp::Opens, &Some(0)));
+
+    // A market switch only affects its market
+    client.set_market_operation_paused(&admin, &1, &PauseOp::Opens, &true);
+    assert!(client.is_operation_paused(&PauseOp::Opens, &Some(1)));
+    assert!(!client.is_operation_paused(&PauseOp::Opens, &Some(0)));
+    assert!(!client.is_operation_paused(&PauseOp::Orders, &Some(1)));
+
+    // A protocol switch affects every market, and nothing else
+    client.set_operation_paused(&admin, &PauseOp::LpDeposits, &true);
+    assert!(client.is_operation_paused(&PauseOp::LpDeposits, &None));
+    assert!(!client.is_operation_paused(&PauseOp::LpWithdrawals, &None));
+    client.set_operation_paused(&admin, &PauseOp::LpDeposits, &false);
+    assert!(!client.is_operation_paused(&PauseOp::LpDeposits, &None));
+
+    // The global pause halts opens and orders but not LP flow
+    client.set_global_pause(&admin, &true);
+    assert!(client.is_operation_paused(&PauseOp::Orders, &Some(0)));
+    assert!(!client.is_operation_paused(&PauseOp::LpWithdrawals, &None));
+
+    assert!(client
+        .try_set_market_operation_paused(&admin, &0, &PauseOp::LpWithdrawals, &true)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #103)")]
+fn test_token_change_without_pause_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_token(&admin, &Address::generate(&env));
+    client.set_token(&admin, &Address::generate(&env));
+}
+
+#[test]
+fn test_list_config_keys() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    let keys = client.list_config_keys();
+    assert_eq!(keys.len(), 29);
+
+    let max_leverage = keys
+        .iter()
+        .find(|d| d.key == Symbol::new(&env, "max_leverage"))
+        .unwrap();
+    assert_eq!(max_leverage.value_type, ConfigValueType::I128);
+    assert_eq!((max_leverage.min, max_leverage.max), (2, 100));
+    assert_eq!(
+        max_leverage.setter,
+        Symbol::new(&env, "set_leverage_limits")
+    );
+
+    // Every key names a getter whose default value lies within the advertised bounds
+    for descriptor in keys.iter() {
+        let value = match descriptor.value_type {
+            ConfigValueType::I128 => {
+                env.invoke_contract::<i128>(&contract_id, &descriptor.key, Vec::new(&env))
+            }
+            ConfigValueType::U64 => {
+                env.invoke_contract::<u64>(&contract_id, &descriptor.key, Vec::new(&env)) as i128
+            }
+        };
+        assert!(value >= descriptor.min && value <= descriptor.max);
+    }
+}
+
+#[test]
+fn test_set_config_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    let key = |name: &str| Symbol::new(&env, name);
+
+    // Raising maintenance margin past the current liquidation threshold only works
+    // together with the threshold
+    let alone = vec![&env, (key("maintenance_margin"), 9_000i128)];
+    assert!(client.try_set_config_batch(&admin, &alone).is_err());
+
+    let updates = vec![
+        &env,
+        (key("maintenance_margin"), 9_000i128),
+        (key("liquidation_threshold"), 9_500i128),
+        (key("taker_fee_bps"), 20i128),
+        (key("min_hold_duration"), 300i128),
+    ];
+    client.set_config_batch(&admin, &updates);
+    assert_eq!(client.maintenance_margin(), 9_000);
+    assert_eq!(client.liquidation_threshold(), 9_500);
+    assert_eq!(client.taker_fee_bps(), 20);
+    assert_eq!(client.min_hold_duration(), 300);
+
+    // A single bad entry rejects the whole batch
+    let fees_before = client.maker_fee_bps();
+    let rejected = [
+        vec![
+            &env,
+            (key("maker_fee_bps"), 7i128),
+            (key("max_leverage"), 101i128),
+        ],
+        vec![
+            &env,
+            (key("maker_fee_bps"), 7i128),
+            (key("maker_fee_bps"), 2i128),
+        ],
+        vec![&env, (key("maker_fee_bps"), 7i128), (key("admin"), 1i128)],
+        vec![
+            &env,
+            (key("maker_fee_bps"), 7i128),
+            (key("keeper_max_reward"), -1i128),
+        ],
+    ];
+    for updates in rejected.iter() {
+        assert!(client.try_set_config_batch(&admin, updates).is_err());
+    }
+    assert_eq!(client.maker_fee_bps(), fees_before);
+}
+
+#[test]
+fn test_min_position_size_bounds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.min_position_size_bounds(),
+        MinPositionSize {
+            token_units: 10_000_000,
+            usd: 0,
+            price_market_id: 0,
+        }
+    );
+
+    client.set_min_position_size_usd(&admin, &100_000_000, &3);
+    assert_eq!(client.min_position_size_usd(), 100_000_000);
+    let bounds = client.min_position_size_bounds();
+    assert_eq!((bounds.usd, bounds.price_market_id), (100_000_000, 3));
+    assert_eq!(bounds.token_units, client.min_position_size());
+
+    assert!(client
+        .try_set_min_position_size_usd(&admin, &-1, &3)
+        .is_err());
+}
+
+#[test]
+fn test_frontend_fee_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let frontend = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    client.set_frontend_fee_share(&admin, &2000);
+    assert_eq!(client.frontend_fee_share_bps(), 2000);
+
+    // Unregistered frontends earn nothing
+    assert!(!client.is_frontend(&frontend));
+    assert_eq!(client.frontend_fee_share(&frontend), 0);
+
+    client.set_frontend(&admin, &frontend, &true);
+    assert!(client.is_frontend(&frontend));
+    assert_eq!(client.frontend_fee_share(&frontend), 2000);
+
+    client.set_frontend(&admin, &frontend, &false);
+    assert_eq!(client.frontend_fee_share(&frontend), 0);
+
+    assert!(client.try_set_frontend_fee_share(&admin, &5001).is_err());
+}
+
+#[test]
+fn test_storage_usage_counts_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    let key = Symbol::new(&env, "router");
+    client.set_config_u32(&admin, &key, &1);
+    client.set_config_u32(&admin, &key, &2);
+    client.set_config_bool(&admin, &key, &true);
+    client.set_market_lot_size(&admin, &0, &100);
+    client.set_market_lot_size(&admin, &0, &200);
+    client.set_market_staleness_threshold(&admin, &1, &120);
+    assert_eq!(
+        client.get_storage_usage(),
+        StorageUsage {
+            parameters: client.list_config_keys().len(),
+            market_overrides: 2,
+            named_values: 2,
+        }
+    );
+
+    client.clear_config(&admin, &key);
+    client.clear_market_lot_size(&admin, &0);
+    client.clear_market_lot_size(&admin, &0);
+    let usage = client.get_storage_usage();
+    assert_eq!((usage.market_overrides, usage.named_values), (1, 0));
+}
+
+#[test]
+fn test_named_config_values_are_typed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let router = Address::generate(&env);
+
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+
+    let key = Symbol::new(&env, "router");
+    assert_eq!(client.get_config_address(&key), None);
+
+    // The same name holds a separate value per type
+    client.set_config_address(&admin, &key, &router);
+    client.set_config_bool(&admin, &key, &true);
+    client.set_config_u32(&admin, &key, &7);
+    assert_eq!(client.get_config_address(&key), Some(router));
+    assert_eq!(client.get_config_bool(&key), Some(true));
+    assert_eq!(client.get_config_u32(&key), Some(7));
+
+    client.set_config_bool(&admin, &key, &false);
+    assert_eq!(client.get_config_bool(&key), Some(false));
+
+    client.clear_config(&admin, &key);
+    assert_eq!(client.get_config_u32(&key), None);
+    assert_eq!(client.get_config_bool(&key), None);
+    assert_eq!(client.get_config_address(&key), None);
+
+    // Only the admin may set them
+    let outsider = Address::generate(&env);
+    assert!(client.try_set_config_u32(&outsider, &key, &1).is_err());
+}
+
+#[test]
+fn test_events_carry_sequence() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let contract_id = env.register(ConfigManager, (&admin, NetworkProfile::Testnet));
+    let client = ConfigManagerClient::new(&env, &contract_id);
+    let seq = client.get_event_seq();
+
+    // Each event takes the next number, across calls and event types
+    client.set_blocked(&admin, &trader, &true);
+    let events = env.events().all();
+    let blocked = BlocklistUpdatedEvent {
+        account: trader.clone(),
+        blocked: true,
+        seq: seq + 1,
+    };
+    assert!(events.contains((
+        contract_id.clone(),
+        blocked.topics(&env),
+        blocked.data(&env)
+    )));
+
+    client.set_global_pause(&admin, &true);
+    let events = env.events().all();
+    let paused = GlobalPauseUpdatedEvent {
+        paused: true,
+        seq: seq + 2,
+    };
+    assert!(events.contains((contract_id.clone(), paused.topics(&env), paused.data(&env))));
+    assert_eq!(client.get_event_seq(), seq + 2);
+
+    // Reads don't advance it
+    client.is_globally_paused();
+    assert_eq!(client.get_event_seq(), seq + 2);
+
+    // Numbers the other protocol contracts draw come from the same counter
+    assert_eq!(client.next_event_seq(), seq + 3);
+    client.set_blocked(&admin, &trader, &false);
+    assert_eq!(client.get_event_seq(), seq + 4);
+}
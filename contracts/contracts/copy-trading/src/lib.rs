@@ -0,0 +1,957 @@
+#![no_std]
+
+//! # Copy Trading Contract
+//!
+//! Follower vault that mirrors a lead trader's positions for the Stellars Finance protocol.
+//! Each deployment serves a single lead trader; followers deposit into the vault and their
+//! funds are allocated proportionally whenever the leader mirrors a trade.
+//!
+//! ## Key Features
+//! - **Follower Vault**: Followers deposit tokens and can withdraw idle funds at any time
+//! - **Proportional Mirroring**: Each mirrored trade commits the same share of every
+//!   follower's idle balance, at the leader's market, direction and leverage
+//! - **Follower Risk Caps**: Max collateral per trade and max leverage per follower
+//! - **Fees**: Annual management fee on follower balances and performance fee on realized
+//!   profit, both paid to the leader
+//! - **Exit Any Time**: `exit()` closes the follower's mirrored positions and pays out
+//!
+//! ## Settlement
+//! Followers are credited with the tokens the vault actually receives, not the PnL
+//! PositionManager reports. Profit the pool defers into a payout claim is owed to the
+//! follower and credited as the claim is paid. A mirrored position closed without the
+//! vault (liquidated, swept or settled by a keeper) pays the vault directly and can't be
+//! traced back to it, so those tokens are held unattributed and shared, by collateral,
+//! among mirrored positions found closed when they are settled.
+//!
+//! ## Limits
+//! Every mirrored position opened or closed costs a full PositionManager call, so one
+//! transaction handles at most `MAX_MIRRORS_PER_CALL` of them: `mirror_open()`,
+//! `mirror_close()` and `exit()` process a page and are called again for the rest. A
+//! vault takes at most `MAX_FOLLOWERS` followers, each with at most
+//! `MAX_MIRRORS_PER_FOLLOWER` mirrored positions open.
+//!
+//! Followers queued for later pages are filled at the price when their page is served,
+//! which may be worse than the leader's. Each mirror starts one page further down the
+//! follower list, so over successive trades every follower is served first equally
+//! often; within a single trade the order still matters.
+//!
+//! ## Integration
+//! Mirrored positions are opened through `PositionManager::open_position_for()`, so this
+//! contract must be on the ConfigManager integrator allow-list. Positions are owned by the
+//! vault and attributed to followers internally.
+//!
+//! ## Usage
+//! - Followers call `deposit()`, `set_risk_caps()`, `withdraw()` and `exit()`
+//! - The leader opens a position on PositionManager, then calls `mirror_open()` and
+//!   `continue_mirror_open()` until every follower is served; `mirror_close()` settles
+//!   the mirrored positions when the leader exits
+
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractevent, contractimpl, contracttype, panic_with_error, symbol_short, token,
+    vec, Address, Env, IntoVal, Vec,
+};
+
+pub use errors::CopyTradingError;
+
+mod config_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
+}
+
+mod position_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/position_manager.wasm");
+}
+
+const BPS_DIVISOR: u128 = 10_000;
+const SECONDS_PER_YEAR: u128 = 31_536_000;
+const MAX_MANAGEMENT_FEE_BPS: u32 = 500; // 5% per year
+const MAX_PERFORMANCE_FEE_BPS: u32 = 5_000; // 50% of profit
+const MAX_FOLLOWERS: u32 = 50;
+const MAX_MIRRORS_PER_FOLLOWER: u32 = 10;
+const MAX_MIRRORS_PER_CALL: u32 = 2; // Most that fit the 100-entry transaction footprint
+
+// Data Structures
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Follower {
+    pub balance: u128,                  // Idle funds available for mirroring
+    pub max_collateral_per_trade: u128, // Risk cap per mirrored trade (0 = no cap)
+    pub max_leverage: u32,              // Risk cap on mirrored leverage (0 = no cap)
+    pub last_fee_accrual: u64,          // Last management fee accrual timestamp
+    pub owed: u128,                     // Deferred proceeds, credited as the pool pays them
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MirroredPosition {
+    pub follower: Address,
+    pub leader_position_id: u64,
+    pub collateral: u128,
+}
+
+/// Followers still to be served for a leader position being mirrored
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MirrorQueue {
+    pub allocation_bps: u32,
+    pub followers: Vec<Address>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    ConfigManager,
+    Leader,
+    ManagementFeeBps,         // Annual fee on follower balances
+    PerformanceFeeBps,        // Fee on realized profit
+    Follower(Address),        // Follower -> Follower
+    Followers,                // Vec<Address> of active followers
+    LeaderMirrors(u64),       // Leader position -> Vec<mirrored position ids>
+    Mirror(u64),              // Mirrored position -> MirroredPosition
+    FollowerMirrors(Address), // Follower -> Vec<mirrored position ids>
+    MirrorQueue(u64),         // Leader position -> MirrorQueue
+    IdleTotal,                // Sum of follower idle balances
+    OwedTotal,                // Sum of follower owed proceeds
+    OpenCollateral,           // Sum of collateral in unsettled mirrored positions
+    MirrorRotation,           // Index in Followers the next mirror starts from
+}
+
+// Events
+
+/// Published for every token transfer the contract makes (see `move_tokens`)
+#[contractevent]
+pub struct TokenMovedEvent {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FollowerDepositedEvent {
+    pub follower: Address,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FollowerWithdrawnEvent {
+    pub follower: Address,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct MirrorOpenedEvent {
+    pub leader_position_id: u64,
+    pub position_id: u64,
+    pub follower: Address,
+    pub collateral: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct MirrorClosedEvent {
+    pub leader_position_id: u64,
+    pub position_id: u64,
+    pub follower: Address,
+    pub pnl: i128,
+    pub performance_fee: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct ManagementFeeChargedEvent {
+    pub follower: Address,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+// Helper Functions
+
+fn get_config_manager(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::ConfigManager)
+        .unwrap_or_else(|| panic_with_error!(env, CopyTradingError::NotInitialized))
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_manager(env))
+}
+
+fn get_leader(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Leader)
+        .unwrap_or_else(|| panic_with_error!(env, CopyTradingError::NotInitialized))
+}
+
+fn get_fee_bps(env: &Env, key: &DataKey) -> u32 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+fn get_token(env: &Env) -> Address {
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    config_client.token()
+}
+
+fn get_position_manager(env: &Env) -> Address {
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    config_client.position_manager()
+}
+
+fn get_follower(env: &Env, follower: &Address) -> Option<Follower> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Follower(follower.clone()))
+}
+
+fn set_follower(env: &Env, follower: &Address, info: &Follower) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Follower(follower.clone()), info);
+}
+
+fn get_total(env: &Env, key: &DataKey) -> u128 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+fn add_total(env: &Env, key: &DataKey, delta: i128) {
+    let total = get_total(env, key) as i128 + delta;
+    env.storage().instance().set(key, &(total as u128));
+}
+
+/// Add to a follower's idle balance, keeping the vault-wide total in step
+fn credit(env: &Env, info: &mut Follower, amount: u128) {
+    info.balance += amount;
+    add_total(env, &DataKey::IdleTotal, amount as i128);
+}
+
+/// Take from a follower's idle balance, keeping the vault-wide total in step
+fn debit(env: &Env, info: &mut Follower, amount: u128) {
+    info.balance -= amount;
+    add_total(env, &DataKey::IdleTotal, -(amount as i128));
+}
+
+fn token_balance(env: &Env) -> u128 {
+    token::Client::new(env, &get_token(env)).balance(&env.current_contract_address()) as u128
+}
+
+/// Tokens the vault holds beyond its followers' idle balances: payouts for mirrored
+/// positions closed without the vault, and paid payout claims
+fn unattributed(env: &Env) -> u128 {
+    token_balance(env).saturating_sub(get_total(env, &DataKey::IdleTotal))
+}
+
+/// Credit the follower their share, by amount owed, of the claim payouts received so far.
+fn collect_owed(env: &Env, info: &mut Follower) {
+    if info.owed == 0 {
+        return;
+    }
+    let owed_total = get_total(env, &DataKey::OwedTotal);
+    let received = unattributed(env).min(owed_total);
+    let share = (received * info.owed) / owed_total;
+    if share == 0 {
+        return;
+    }
+    info.owed -= share;
+    add_total(env, &DataKey::OwedTotal, -(share as i128));
+    credit(env, info, share);
+}
+
+fn get_followers(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Followers)
+        .unwrap_or(Vec::new(env))
+}
+
+/// The followers in the order a new mirror serves them. Each mirror starts one page
+/// further down the follower list than the last, so over successive trades every
+/// follower is in the first page, filled at the price the leader mirrored at, equally
+/// often.
+fn rotated_followers(env: &Env) -> Vec<Address> {
+    let followers = get_followers(env);
+    if followers.is_empty() {
+        return followers;
+    }
+    let start = env
+        .storage()
+        .instance()
+        .get::<DataKey, u32>(&DataKey::MirrorRotation)
+        .unwrap_or(0)
+        % followers.len();
+    env.storage()
+        .instance()
+        .set(&DataKey::MirrorRotation, &(start + MAX_MIRRORS_PER_CALL));
+
+    let mut rotated = followers.slice(start..);
+    rotated.append(&followers.slice(..start));
+    rotated
+}
+
+fn get_id_list(env: &Env, key: &DataKey) -> Vec<u64> {
+    env.storage().persistent().get(key).unwrap_or(Vec::new(env))
+}
+
+fn set_id_list(env: &Env, key: &DataKey, ids: &Vec<u64>) {
+    if ids.is_empty() {
+        env.storage().persistent().remove(key);
+    } else {
+        env.storage().persistent().set(key, ids);
+    }
+}
+
+fn remove_id(env: &Env, key: &DataKey, id: u64) {
+    let ids = get_id_list(env, key);
+    let mut remaining = Vec::new(env);
+    for existing in ids.iter() {
+        if existing != id {
+            remaining.push_back(existing);
+        }
+    }
+    set_id_list(env, key, &remaining);
+}
+
+/// Move `amount` of the vault's token from one address to another.
+///
+/// Every token transfer in this contract goes through here so fund movements have a
+/// single path to audit: negative amounts and transfers back to the sender are refused,
+/// zero amounts are skipped, and each transfer publishes a `TokenMovedEvent`.
+fn move_tokens(env: &Env, from: &Address, to: &Address, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(env, CopyTradingError::InvalidTokenTransfer);
+    }
+    if from == to {
+        panic_with_error!(env, CopyTradingError::InvalidTokenTransfer);
+    }
+    if amount == 0 {
+        return;
+    }
+    let token = get_token(env);
+    token::Client::new(env, &token).transfer(from, to, &amount);
+    TokenMovedEvent {
+        token,
+        from: from.clone(),
+        to: to.clone(),
+        amount,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+}
+
+/// Charge the annual management fee accrued since the follower's last interaction.
+fn accrue_management_fee(env: &Env, follower: &Address, info: &mut Follower) {
+    let now = env.ledger().timestamp();
+    let elapsed = (now - info.last_fee_accrual) as u128;
+    info.last_fee_accrual = now;
+
+    let fee_bps = get_fee_bps(env, &DataKey::ManagementFeeBps) as u128;
+    if fee_bps == 0 || elapsed == 0 || info.balance == 0 {
+        return;
+    }
+
+    let fee = (info.balance * fee_bps * elapsed) / (BPS_DIVISOR * SECONDS_PER_YEAR);
+    let fee = fee.min(info.balance);
+    if fee == 0 {
+        return;
+    }
+
+    debit(env, info, fee);
+    move_tokens(
+        env,
+        &env.current_contract_address(),
+        &get_leader(env),
+        fee as i128,
+    );
+
+    ManagementFeeChargedEvent {
+        follower: follower.clone(),
+        amount: fee,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+}
+
+/// Close a mirrored position and credit the tokens it returned (minus performance
+/// fee) to its follower's idle balance.
+///
+/// # Returns
+/// The realized PnL of the mirrored position: tokens received less its collateral
+fn settle_mirror(env: &Env, position_id: u64) -> i128 {
+    let mirror: MirroredPosition = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Mirror(position_id))
+        .unwrap_or_else(|| panic_with_error!(env, CopyTradingError::MirrorNotFound));
+    let mut info = get_follower(env, &mirror.follower)
+        .unwrap_or_else(|| panic_with_error!(env, CopyTradingError::FollowerNotFound));
+
+    let pm_client = position_manager::Client::new(env, &get_position_manager(env));
+    let open_collateral = get_total(env, &DataKey::OpenCollateral);
+
+    let received = if pm_client.try_get_position(&position_id).is_ok() {
+        let before = token_balance(env);
+        let receipt = pm_client.close_position(&env.current_contract_address(), &position_id);
+        let received = token_balance(env) - before;
+
+        // Profit the pool couldn't pay yet is queued as a claim paid to the vault later
+        let deferred = receipt.payout.saturating_sub(received);
+        if deferred > 0 {
+            info.owed += deferred;
+            add_total(env, &DataKey::OwedTotal, deferred as i128);
+        }
+        received
+    } else {
+        // Whatever the position paid out when it was closed without us is in the
+        // unattributed balance; take this position's share of it by collateral
+        let available = unattributed(env).saturating_sub(get_total(env, &DataKey::OwedTotal));
+        (available * mirror.collateral) / open_collateral
+    };
+    add_total(env, &DataKey::OpenCollateral, -(mirror.collateral as i128));
+
+    let pnl = received as i128 - mirror.collateral as i128;
+    let performance_fee = if pnl > 0 {
+        (pnl as u128 * get_fee_bps(env, &DataKey::PerformanceFeeBps) as u128) / BPS_DIVISOR
+    } else {
+        0
+    };
+    credit(env, &mut info, received);
+    debit(env, &mut info, performance_fee);
+    set_follower(env, &mirror.follower, &info);
+    move_tokens(
+        env,
+        &env.current_contract_address(),
+        &get_leader(env),
+        performance_fee as i128,
+    );
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Mirror(position_id));
+    remove_id(
+        env,
+        &DataKey::LeaderMirrors(mirror.leader_position_id),
+        position_id,
+    );
+    remove_id(
+        env,
+        &DataKey::FollowerMirrors(mirror.follower.clone()),
+        position_id,
+    );
+
+    MirrorClosedEvent {
+        leader_position_id: mirror.leader_position_id,
+        position_id,
+        follower: mirror.follower,
+        pnl,
+        performance_fee,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+
+    pnl
+}
+
+/// Open mirrored positions for the next page of followers queued for a leader position.
+///
+/// # Returns
+/// The IDs of the mirrored positions opened
+fn open_queued_mirrors(env: &Env, leader_position_id: u64) -> Vec<u64> {
+    let queue_key = DataKey::MirrorQueue(leader_position_id);
+    let mut queue: MirrorQueue = env
+        .storage()
+        .persistent()
+        .get(&queue_key)
+        .unwrap_or_else(|| panic_with_error!(env, CopyTradingError::NothingToMirror));
+
+    let position_manager = get_position_manager(env);
+    let pm_client = position_manager::Client::new(env, &position_manager);
+    let leader_position = pm_client.get_position(&leader_position_id);
+
+    let leverage = (leader_position.size / leader_position.collateral) as u32;
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    let min_position_size = config_client.min_position_size() as u128;
+    let pool = config_client.liquidity_pool();
+    let token = config_client.token();
+    let vault = env.current_contract_address();
+    let followers = get_followers(env);
+
+    let mut opened = Vec::new(env);
+    for _ in 0..MAX_MIRRORS_PER_CALL {
+        let Some(follower) = queue.followers.pop_front() else {
+            break;
+        };
+        // Followers who exited since the leader mirrored are skipped
+        if !followers.contains(&follower) {
+            continue;
+        }
+        let mut info = get_follower(env, &follower)
+            .unwrap_or_else(|| panic_with_error!(env, CopyTradingError::FollowerNotFound));
+        accrue_management_fee(env, &follower, &mut info);
+
+        let mut collateral = (info.balance * queue.allocation_bps as u128) / BPS_DIVISOR;
+        if info.max_collateral_per_trade > 0 {
+            collateral = collateral.min(info.max_collateral_per_trade);
+        }
+
+        let follower_key = DataKey::FollowerMirrors(follower.clone());
+        let mut follower_mirrors = get_id_list(env, &follower_key);
+        let within_leverage_cap = info.max_leverage == 0 || leverage <= info.max_leverage;
+        if !within_leverage_cap
+            || collateral * (leverage as u128) < min_position_size
+            || follower_mirrors.len() >= MAX_MIRRORS_PER_FOLLOWER
+        {
+            set_follower(env, &follower, &info);
+            continue;
+        }
+
+        debit(env, &mut info, collateral);
+        set_follower(env, &follower, &info);
+        add_total(env, &DataKey::OpenCollateral, collateral as i128);
+
+        // The pool pulls collateral from the vault on our behalf
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: symbol_short!("transfer"),
+                    args: (vault.clone(), pool.clone(), collateral as i128).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+
+        let position_id = pm_client
+            .open_position_for(
+                &vault,
+                &vault,
+                &leader_position.market_id,
+                &collateral,
+                &leverage,
+                &leader_position.is_long,
+            )
+            .position_id;
+
+        let mirror = MirroredPosition {
+            follower: follower.clone(),
+            leader_position_id,
+            collateral,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Mirror(position_id), &mirror);
+
+        follower_mirrors.push_back(position_id);
+        set_id_list(env, &follower_key, &follower_mirrors);
+
+        opened.push_back(position_id);
+
+        MirrorOpenedEvent {
+            leader_position_id,
+            position_id,
+            follower,
+            collateral,
+            seq: next_event_seq(env),
+        }
+        .publish(env);
+    }
+
+    if queue.followers.is_empty() {
+        env.storage().persistent().remove(&queue_key);
+    } else {
+        env.storage().persistent().set(&queue_key, &queue);
+    }
+    let leader_key = DataKey::LeaderMirrors(leader_position_id);
+    let mut leader_mirrors = get_id_list(env, &leader_key);
+    leader_mirrors.append(&opened);
+    set_id_list(env, &leader_key, &leader_mirrors);
+
+    opened
+}
+
+#[contract]
+pub struct CopyTrading;
+
+#[contractimpl]
+impl CopyTrading {
+    /// Initialize the follower vault for a lead trader.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address (must authorize)
+    /// * `config_manager` - Address of the ConfigManager contract
+    /// * `leader` - The lead trader whose positions are mirrored
+    /// * `management_fee_bps` - Annual management fee in basis points (max 500 = 5%)
+    /// * `performance_fee_bps` - Performance fee on realized profit in bps (max 5000 = 50%)
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        config_manager: Address,
+        leader: Address,
+        management_fee_bps: u32,
+        performance_fee_bps: u32,
+    ) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, CopyTradingError::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        if management_fee_bps > MAX_MANAGEMENT_FEE_BPS {
+            panic_with_error!(&env, CopyTradingError::FeeTooHigh);
+        }
+        if performance_fee_bps > MAX_PERFORMANCE_FEE_BPS {
+            panic_with_error!(&env, CopyTradingError::FeeTooHigh);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ConfigManager, &config_manager);
+        env.storage().instance().set(&DataKey::Leader, &leader);
+        env.storage()
+            .instance()
+            .set(&DataKey::ManagementFeeBps, &management_fee_bps);
+        env.storage()
+            .instance()
+            .set(&DataKey::PerformanceFeeBps, &performance_fee_bps);
+    }
+
+    /// Deposit funds into the follower vault.
+    ///
+    /// # Arguments
+    ///
+    /// * `follower` - The follower depositing (must authorize)
+    /// * `amount` - Amount of tokens to deposit
+    pub fn deposit(env: Env, follower: Address, amount: u128) {
+        follower.require_auth();
+
+        if amount == 0 {
+            panic_with_error!(&env, CopyTradingError::ZeroAmount);
+        }
+
+        // A follower who exited with proceeds still owed keeps their record
+        let mut followers = get_followers(&env);
+        if !followers.contains(&follower) {
+            if followers.len() >= MAX_FOLLOWERS {
+                panic_with_error!(&env, CopyTradingError::FollowerLimitReached);
+            }
+            followers.push_back(follower.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::Followers, &followers);
+        }
+        let mut info = get_follower(&env, &follower).unwrap_or(Follower {
+            balance: 0,
+            max_collateral_per_trade: 0,
+            max_leverage: 0,
+            last_fee_accrual: env.ledger().timestamp(),
+            owed: 0,
+        });
+        accrue_management_fee(&env, &follower, &mut info);
+        collect_owed(&env, &mut info);
+
+        move_tokens(
+            &env,
+            &follower,
+            &env.current_contract_address(),
+            amount as i128,
+        );
+
+        credit(&env, &mut info, amount);
+        set_follower(&env, &follower, &info);
+
+        FollowerDepositedEvent {
+            follower,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Set the follower's risk caps for future mirrored trades.
+    ///
+    /// # Arguments
+    ///
+    /// * `follower` - The follower (must authorize)
+    /// * `max_collateral_per_trade` - Max collateral committed per trade (0 = no cap)
+    /// * `max_leverage` - Trades above this leverage are skipped (0 = no cap)
+    pub fn set_risk_caps(
+        env: Env,
+        follower: Address,
+        max_collateral_per_trade: u128,
+        max_leverage: u32,
+    ) {
+        follower.require_auth();
+
+        let mut info = get_follower(&env, &follower)
+            .unwrap_or_else(|| panic_with_error!(&env, CopyTradingError::FollowerNotFound));
+        info.max_collateral_per_trade = max_collateral_per_trade;
+        info.max_leverage = max_leverage;
+        set_follower(&env, &follower, &info);
+    }
+
+    /// Withdraw idle funds from the vault.
+    ///
+    /// # Arguments
+    ///
+    /// * `follower` - The follower withdrawing (must authorize)
+    /// * `amount` - Amount of idle tokens to withdraw
+    pub fn withdraw(env: Env, follower: Address, amount: u128) {
+        follower.require_auth();
+
+        let mut info = get_follower(&env, &follower)
+            .unwrap_or_else(|| panic_with_error!(&env, CopyTradingError::FollowerNotFound));
+        accrue_management_fee(&env, &follower, &mut info);
+        collect_owed(&env, &mut info);
+
+        if amount > info.balance {
+            panic_with_error!(&env, CopyTradingError::InsufficientBalance);
+        }
+        debit(&env, &mut info, amount);
+        set_follower(&env, &follower, &info);
+
+        move_tokens(
+            &env,
+            &env.current_contract_address(),
+            &follower,
+            amount as i128,
+        );
+
+        FollowerWithdrawnEvent {
+            follower,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Exit the vault: stop mirroring, close the follower's mirrored positions and
+    /// withdraw the full balance. Each call closes up to `MAX_MIRRORS_PER_CALL`
+    /// positions; the balance is paid out by the call that closes the last one.
+    /// Proceeds the pool still owes stay with the follower's record, for
+    /// `withdraw()` to collect once paid.
+    ///
+    /// # Arguments
+    ///
+    /// * `follower` - The follower exiting (must authorize)
+    ///
+    /// # Returns
+    ///
+    /// The total amount paid out to the follower (0 while positions remain open)
+    pub fn exit(env: Env, follower: Address) -> u128 {
+        follower.require_auth();
+
+        let mut info = get_follower(&env, &follower)
+            .unwrap_or_else(|| panic_with_error!(&env, CopyTradingError::FollowerNotFound));
+        accrue_management_fee(&env, &follower, &mut info);
+        set_follower(&env, &follower, &info);
+
+        let mut remaining = Vec::new(&env);
+        for existing in get_followers(&env).iter() {
+            if existing != follower {
+                remaining.push_back(existing);
+            }
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Followers, &remaining);
+
+        let mirrors = get_id_list(&env, &DataKey::FollowerMirrors(follower.clone()));
+        for position_id in mirrors.iter().take(MAX_MIRRORS_PER_CALL as usize) {
+            settle_mirror(&env, position_id);
+        }
+        if mirrors.len() > MAX_MIRRORS_PER_CALL {
+            return 0;
+        }
+
+        let mut info = get_follower(&env, &follower)
+            .unwrap_or_else(|| panic_with_error!(&env, CopyTradingError::FollowerNotFound));
+        collect_owed(&env, &mut info);
+        let amount = info.balance;
+        debit(&env, &mut info, amount);
+
+        if info.owed > 0 {
+            set_follower(&env, &follower, &info);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Follower(follower.clone()));
+        }
+
+        move_tokens(
+            &env,
+            &env.current_contract_address(),
+            &follower,
+            amount as i128,
+        );
+
+        FollowerWithdrawnEvent {
+            follower,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        amount
+    }
+
+    /// Mirror one of the leader's open positions for all followers.
+    ///
+    /// Each follower commits `allocation_bps` of their idle balance (capped by their
+    /// max collateral per trade) at the leader's market, direction and leverage.
+    /// Followers whose leverage cap is exceeded, whose resulting size is below
+    /// the protocol minimum, or who already have `MAX_MIRRORS_PER_FOLLOWER` mirrored
+    /// positions open, are skipped. The first `MAX_MIRRORS_PER_CALL` followers are
+    /// served here and the rest are queued for `continue_mirror_open()`. The list is
+    /// rotated by one page per mirror, so no follower is always served first.
+    ///
+    /// # Arguments
+    ///
+    /// * `leader_position_id` - The leader's position on PositionManager
+    /// * `allocation_bps` - Share of each follower's idle balance to commit (1-10000)
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the mirrored positions opened
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not the leader, the position isn't the leader's,
+    /// or it has already been mirrored
+    pub fn mirror_open(env: Env, leader_position_id: u64, allocation_bps: u32) -> Vec<u64> {
+        let leader = get_leader(&env);
+        leader.require_auth();
+
+        if allocation_bps == 0 || allocation_bps as u128 > BPS_DIVISOR {
+            panic_with_error!(&env, CopyTradingError::InvalidAllocation);
+        }
+        let storage = env.storage().persistent();
+        if storage.has(&DataKey::LeaderMirrors(leader_position_id))
+            || storage.has(&DataKey::MirrorQueue(leader_position_id))
+        {
+            panic_with_error!(&env, CopyTradingError::AlreadyMirrored);
+        }
+
+        let pm_client = position_manager::Client::new(&env, &get_position_manager(&env));
+        if pm_client.get_position(&leader_position_id).trader != leader {
+            panic_with_error!(&env, CopyTradingError::NotLeaderPosition);
+        }
+
+        let queue = MirrorQueue {
+            allocation_bps,
+            followers: rotated_followers(&env),
+        };
+        storage.set(&DataKey::MirrorQueue(leader_position_id), &queue);
+        open_queued_mirrors(&env, leader_position_id)
+    }
+
+    /// Serve the next `MAX_MIRRORS_PER_CALL` followers still queued for a leader
+    /// position, at the allocation it was mirrored with.
+    ///
+    /// # Arguments
+    ///
+    /// * `leader_position_id` - The leader's position being mirrored
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the mirrored positions opened
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not the leader, no followers are queued for the
+    /// position, or the leader position has been closed
+    pub fn continue_mirror_open(env: Env, leader_position_id: u64) -> Vec<u64> {
+        get_leader(&env).require_auth();
+        open_queued_mirrors(&env, leader_position_id)
+    }
+
+    /// Close positions mirroring one of the leader's positions, up to
+    /// `MAX_MIRRORS_PER_CALL` per call, and drop any followers still queued for it.
+    /// Call again while `get_leader_mirrors()` returns positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `leader_position_id` - The leader's position that was mirrored
+    ///
+    /// # Returns
+    ///
+    /// The total realized PnL of the positions closed
+    pub fn mirror_close(env: Env, leader_position_id: u64) -> i128 {
+        get_leader(&env).require_auth();
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::MirrorQueue(leader_position_id));
+
+        let mut total_pnl = 0i128;
+        let mirrors = get_id_list(&env, &DataKey::LeaderMirrors(leader_position_id));
+        for position_id in mirrors.iter().take(MAX_MIRRORS_PER_CALL as usize) {
+            total_pnl += settle_mirror(&env, position_id);
+        }
+        total_pnl
+    }
+
+    /// Get a follower's vault state.
+    ///
+    /// # Arguments
+    ///
+    /// * `follower` - The follower address
+    ///
+    /// # Returns
+    ///
+    /// The Follower struct (idle balance, risk caps, fee accrual timestamp)
+    pub fn get_follower(env: Env, follower: Address) -> Follower {
+        get_follower(&env, &follower)
+            .unwrap_or_else(|| panic_with_error!(&env, CopyTradingError::FollowerNotFound))
+    }
+
+    /// Get all active followers.
+    pub fn get_followers(env: Env) -> Vec<Address> {
+        get_followers(&env)
+    }
+
+    /// Get the followers still queued for `continue_mirror_open()` on a leader position.
+    pub fn get_mirror_queue(env: Env, leader_position_id: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MirrorQueue(leader_position_id))
+            .map(|queue: MirrorQueue| queue.followers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the mirrored position IDs opened for a leader position.
+    pub fn get_leader_mirrors(env: Env, leader_position_id: u64) -> Vec<u64> {
+        get_id_list(&env, &DataKey::LeaderMirrors(leader_position_id))
+    }
+
+    /// Get the open mirrored position IDs attributed to a follower.
+    pub fn get_follower_mirrors(env: Env, follower: Address) -> Vec<u64> {
+        get_id_list(&env, &DataKey::FollowerMirrors(follower))
+    }
+
+    /// Get the lead trader address.
+    pub fn leader(env: Env) -> Address {
+        get_leader(&env)
+    }
+
+    /// Get the vault fees.
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (management_fee_bps, performance_fee_bps)
+    pub fn fees(env: Env) -> (u32, u32) {
+        (
+            get_fee_bps(&env, &DataKey::ManagementFeeBps),
+            get_fee_bps(&env, &DataKey::PerformanceFeeBps),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,532 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, token, Address, Env, Map};
+
+mod oracle_integrator {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/oracle_integrator.wasm"
+    );
+}
+
+mod liquidity_pool {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/liquidity_pool.wasm");
+}
+
+mod market_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/market_manager.wasm");
+}
+
+struct TestEnv<'a> {
+    config_client: config_manager::Client<'a>,
+    oracle_client: oracle_integrator::Client<'a>,
+    position_client: position_manager::Client<'a>,
+    pool_client: liquidity_pool::Client<'a>,
+    vault_client: CopyTradingClient<'a>,
+    vault_id: Address,
+    token_client: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+    admin: Address,
+    leader: Address,
+}
+
+/// Deploy the full protocol plus a copy-trading vault (10% performance fee,
+/// no management fee) that is allow-listed as an integrator
+fn setup_test_environment(env: &Env) -> TestEnv<'_> {
+    env.mock_all_auths();
+    // Deploying the protocol exceeds the default test budget; per-call resources are
+    // checked against the network limits in test_full_vault_fits_network_limits
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let leader = Address::generate(env);
+
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::Client::new(env, &token_contract.address());
+    let token_admin = token::StellarAssetClient::new(env, &token_contract.address());
+
+    let config_id = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(env, &config_id);
+
+    let oracle_id = env.register(oracle_integrator::WASM, (&config_id,));
+    let oracle_client = oracle_integrator::Client::new(env, &oracle_id);
+    let mut base_prices = Map::new(env);
+    base_prices.set(0u32, 100_000_000i128); // XLM: $1.00
+    oracle_client.set_test_mode(&admin, &true, &base_prices);
+    oracle_client.set_fixed_price_mode(&admin, &true);
+
+    let market_id = env.register(market_manager::WASM, (&config_id, &admin));
+    let market_client = market_manager::Client::new(env, &market_id);
+
+    let pool_id = env.register(liquidity_pool::WASM, (&config_id, &token_client.address));
+    let pool_client = liquidity_pool::Client::new(env, &pool_id);
+
+    let position_manager_id = env.register(position_manager::WASM, (&config_id,));
+    let position_client = position_manager::Client::new(env, &position_manager_id);
+
+    config_client.set_oracle_integrator(&admin, &oracle_id);
+    config_client.set_market_manager(&admin, &market_id);
+    config_client.set_liquidity_pool(&admin, &pool_id);
+    config_client.set_position_manager(&admin, &position_manager_id);
+    config_client.set_token(&admin, &token_client.address);
+    config_client.set_borrow_rate_per_second(&admin, &0);
+    market_client.set_position_manager(&admin, &position_manager_id);
+    pool_client.set_position_manager(&admin, &position_manager_id);
+    market_client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    token_admin.mint(&admin, &100_000_000_000);
+    pool_client.deposit(&admin, &100_000_000_000);
+    token_admin.mint(&leader, &10_000_000_000);
+
+    let vault_id = env.register(CopyTrading, ());
+    let vault_client = CopyTradingClient::new(env, &vault_id);
+    vault_client.initialize(&admin, &config_id, &leader, &0u32, &1000u32);
+    config_client.set_integrator_allowed(&admin, &vault_id, &true);
+
+    TestEnv {
+        config_client,
+        oracle_client,
+        position_client,
+        pool_client,
+        vault_client,
+        vault_id,
+        token_client,
+        token_admin,
+        admin,
+        leader,
+    }
+}
+
+fn set_xlm_price(t: &TestEnv, price: i128) {
+    let mut base_prices = Map::new(&t.config_client.env);
+    base_prices.set(0u32, price);
+    t.oracle_client.set_test_mode(&t.admin, &true, &base_prices);
+}
+
+fn new_follower(env: &Env, t: &TestEnv, deposit: u128) -> Address {
+    let follower = Address::generate(env);
+    t.token_admin.mint(&follower, &(deposit as i128));
+    t.vault_client.deposit(&follower, &deposit);
+    follower
+}
+
+#[test]
+fn test_deposit_and_withdraw() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower = new_follower(&env, &t, 1_000_000_000);
+    assert_eq!(
+        t.vault_client.get_follower(&follower).balance,
+        1_000_000_000
+    );
+    assert_eq!(t.vault_client.get_followers().len(), 1);
+
+    t.vault_client.withdraw(&follower, &400_000_000);
+    assert_eq!(t.vault_client.get_follower(&follower).balance, 600_000_000);
+    assert_eq!(t.token_client.balance(&follower), 400_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1001)")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    // A second call must not be able to replace the leader or fees
+    let other_leader = Address::generate(&env);
+    t.vault_client.initialize(
+        &t.admin,
+        &t.config_client.address,
+        &other_leader,
+        &0u32,
+        &0u32,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1010)")]
+fn test_withdraw_more_than_balance() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower = new_follower(&env, &t, 1_000_000_000);
+    t.vault_client.withdraw(&follower, &1_000_000_001);
+}
+
+#[test]
+fn test_mirror_open_proportional() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower1 = new_follower(&env, &t, 10_000_000_000); // 1,000 tokens
+    let follower2 = new_follower(&env, &t, 5_000_000_000); // 500 tokens
+
+    // Leader opens 100 tokens at 10x long, mirrors 10% of follower balances
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    let mirrors = t.vault_client.mirror_open(&leader_position, &1000u32);
+    assert_eq!(mirrors.len(), 2);
+
+    let position1 = t.position_client.get_position(&mirrors.get(0).unwrap());
+    let position2 = t.position_client.get_position(&mirrors.get(1).unwrap());
+    assert_eq!(position1.trader, t.vault_id);
+    assert_eq!(position1.collateral, 1_000_000_000);
+    assert_eq!(position1.size, 10_000_000_000);
+    assert_eq!(position2.collateral, 500_000_000);
+    assert!(position2.is_long);
+
+    assert_eq!(
+        t.vault_client.get_follower(&follower1).balance,
+        9_000_000_000
+    );
+    assert_eq!(
+        t.vault_client.get_follower(&follower2).balance,
+        4_500_000_000
+    );
+}
+
+#[test]
+fn test_mirror_open_rotates_followers() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower1 = new_follower(&env, &t, 10_000_000_000);
+    let follower2 = new_follower(&env, &t, 10_000_000_000);
+    let follower3 = new_follower(&env, &t, 10_000_000_000);
+
+    // The first mirror serves followers 1 and 2 and queues 3
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    t.vault_client.mirror_open(&leader_position, &1000u32);
+    assert_eq!(
+        t.vault_client.get_mirror_queue(&leader_position),
+        vec![&env, follower3.clone()]
+    );
+
+    // The next one starts a page further down: 3 and 1 first, then 2
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    let mirrors = t.vault_client.mirror_open(&leader_position, &1000u32);
+    assert_eq!(
+        t.vault_client.get_mirror_queue(&leader_position),
+        vec![&env, follower2]
+    );
+    assert_eq!(
+        t.vault_client.get_follower_mirrors(&follower3),
+        vec![&env, mirrors.get(0).unwrap()]
+    );
+    assert_eq!(t.vault_client.get_follower_mirrors(&follower1).len(), 2);
+}
+
+#[test]
+fn test_mirror_open_respects_risk_caps() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let capped = new_follower(&env, &t, 10_000_000_000);
+    let conservative = new_follower(&env, &t, 10_000_000_000);
+    t.vault_client
+        .set_risk_caps(&capped, &200_000_000u128, &0u32);
+    t.vault_client.set_risk_caps(&conservative, &0u128, &5u32);
+
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    let mirrors = t.vault_client.mirror_open(&leader_position, &1000u32);
+
+    // Collateral capped at 20 tokens; 10x exceeds the conservative follower's cap
+    assert_eq!(mirrors.len(), 1);
+    let position = t.position_client.get_position(&mirrors.get(0).unwrap());
+    assert_eq!(position.collateral, 200_000_000);
+    assert_eq!(t.vault_client.get_follower_mirrors(&conservative).len(), 0);
+}
+
+#[test]
+fn test_mirror_close_charges_performance_fee() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower = new_follower(&env, &t, 10_000_000_000);
+    let leader_balance = t.token_client.balance(&t.leader);
+
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    t.vault_client.mirror_open(&leader_position, &1000u32);
+
+    // Price rises 10%: 1,000 token mirrored size earns 100 tokens
+    set_xlm_price(&t, 110_000_000);
+    t.position_client
+        .close_position(&t.leader, &leader_position);
+    let pnl = t.vault_client.mirror_close(&leader_position);
+    assert_eq!(pnl, 1_000_000_000);
+
+    // Leader receives 10% of the follower's profit on top of their own trade
+    let leader_trade_pnl = 1_000_000_000;
+    assert_eq!(
+        t.token_client.balance(&t.leader),
+        leader_balance + leader_trade_pnl + 100_000_000
+    );
+    assert_eq!(
+        t.vault_client.get_follower(&follower).balance,
+        10_900_000_000
+    );
+    assert_eq!(t.vault_client.get_leader_mirrors(&leader_position).len(), 0);
+}
+
+#[test]
+fn test_exit_closes_mirrored_positions() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower = new_follower(&env, &t, 10_000_000_000);
+
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    t.vault_client.mirror_open(&leader_position, &1000u32);
+
+    // Follower exits while the trade is open at a 5% loss
+    set_xlm_price(&t, 95_000_000);
+    let paid_out = t.vault_client.exit(&follower);
+
+    assert_eq!(paid_out, 9_500_000_000);
+    assert_eq!(t.token_client.balance(&follower), 9_500_000_000);
+    assert_eq!(t.vault_client.get_followers().len(), 0);
+    assert_eq!(
+        t.position_client.get_user_open_positions(&t.vault_id).len(),
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1013)")]
+fn test_mirror_open_foreign_position() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    new_follower(&env, &t, 10_000_000_000);
+
+    let other = Address::generate(&env);
+    t.token_admin.mint(&other, &1_000_000_000);
+    let other_position = t
+        .position_client
+        .open_position(&other, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+
+    t.vault_client.mirror_open(&other_position, &1000u32);
+}
+
+#[test]
+fn test_management_fee_accrues_to_leader() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    // Separate vault charging 2% per year management fee
+    let vault_id = env.register(CopyTrading, ());
+    let vault_client = CopyTradingClient::new(&env, &vault_id);
+    vault_client.initialize(
+        &t.admin,
+        &t.config_client.address,
+        &t.leader,
+        &200u32,
+        &0u32,
+    );
+
+    let follower = Address::generate(&env);
+    t.token_admin.mint(&follower, &10_000_000_000);
+    vault_client.deposit(&follower, &10_000_000_000);
+    let leader_balance = t.token_client.balance(&t.leader);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp += 31_536_000;
+    });
+    vault_client.withdraw(&follower, &0u128);
+
+    assert_eq!(vault_client.get_follower(&follower).balance, 9_800_000_000);
+    assert_eq!(
+        t.token_client.balance(&t.leader),
+        leader_balance + 200_000_000
+    );
+}
+
+#[test]
+fn test_deferred_profit_credited_when_claim_paid() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower = new_follower(&env, &t, 10_000_000_000);
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    t.vault_client.mirror_open(&leader_position, &1000u32);
+
+    // Drain most of the pool's free liquidity, then let the price rise 150%: the
+    // mirrored 1,000 token long earns 1,500 tokens, more than the pool can pay
+    t.config_client
+        .set_min_liquidity_reserve_ratio(&t.admin, &0);
+    t.pool_client.withdraw(&t.admin, &80_000_000_000);
+    set_xlm_price(&t, 250_000_000);
+    let pnl = t.vault_client.mirror_close(&leader_position);
+
+    // Credited with the tokens that arrived, less the 10% fee on their profit; the
+    // rest of the 1,600 token payout is owed through the pool's claim
+    let received = 1_000_000_000 + pnl as u128;
+    let owed = 16_000_000_000 - received;
+    assert!(owed > 0);
+    assert_eq!(t.pool_client.get_outstanding_claims(), owed as i128);
+    let info = t.vault_client.get_follower(&follower);
+    assert_eq!(info.balance, 9_000_000_000 + received - pnl as u128 / 10);
+    assert_eq!(info.owed, owed);
+
+    // Once the pool pays the claim, the follower collects it
+    t.token_admin.mint(&t.admin, &10_000_000_000);
+    t.pool_client.deposit(&t.admin, &10_000_000_000);
+    t.pool_client.settle_claims(&20);
+    t.vault_client.withdraw(&follower, &0u128);
+    let collected = t.vault_client.get_follower(&follower);
+    assert_eq!(collected.balance, info.balance + owed);
+    assert_eq!(collected.owed, 0);
+    assert_eq!(t.token_client.balance(&t.vault_id), collected.balance as i128);
+}
+
+#[test]
+fn test_swept_mirrors_credited_by_collateral() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let follower1 = new_follower(&env, &t, 10_000_000_000);
+    let follower2 = new_follower(&env, &t, 5_000_000_000);
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &1_000_000_000u128, &10u32, &true)
+        .position_id;
+    let mirrors = t.vault_client.mirror_open(&leader_position, &1000u32);
+
+    // A keeper sweeps both mirrored positions as dust, paying the vault directly
+    t.config_client
+        .set_min_position_size(&t.admin, &20_000_000_000);
+    let keeper = Address::generate(&env);
+    for position_id in mirrors.iter() {
+        t.position_client.sweep_dust_position(&keeper, &position_id);
+    }
+    assert_eq!(t.token_client.balance(&t.vault_id), 15_000_000_000);
+
+    // Each follower gets back their own collateral
+    assert_eq!(t.vault_client.mirror_close(&leader_position), 0);
+    assert_eq!(
+        t.vault_client.get_follower(&follower1).balance,
+        10_000_000_000
+    );
+    assert_eq!(
+        t.vault_client.get_follower(&follower2).balance,
+        5_000_000_000
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1009)")]
+fn test_follower_limit() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    for _ in 0..=MAX_FOLLOWERS {
+        new_follower(&env, &t, 1_000_000_000);
+    }
+}
+
+// Per-transaction limits enforced by the network
+const MAX_INSTRUCTIONS: i64 = 600_000_000;
+const MAX_MEM_BYTES: i64 = 41_943_040;
+const MAX_FOOTPRINT_ENTRIES: u32 = 100;
+const MAX_WRITE_ENTRIES: u32 = 50;
+
+/// Assert the last invocation fits the network's per-transaction limits
+fn assert_within_network_limits(env: &Env, label: &str) {
+    let r = env.cost_estimate().resources();
+    let footprint = r.disk_read_entries + r.memory_read_entries + r.write_entries;
+    assert!(r.instructions <= MAX_INSTRUCTIONS, "{label}: {} instructions", r.instructions);
+    assert!(r.mem_bytes <= MAX_MEM_BYTES, "{label}: {} memory bytes", r.mem_bytes);
+    assert!(footprint <= MAX_FOOTPRINT_ENTRIES, "{label}: {footprint} footprint entries");
+    assert!(r.write_entries <= MAX_WRITE_ENTRIES, "{label}: {} write entries", r.write_entries);
+}
+
+#[test]
+fn test_full_vault_fits_network_limits() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let mut followers = Vec::new(&env);
+    for _ in 0..MAX_FOLLOWERS {
+        followers.push_back(new_follower(&env, &t, 5_000_000_000));
+    }
+
+    // Mirroring for every follower takes one page per MAX_MIRRORS_PER_CALL followers
+    let leader_position = t
+        .position_client
+        .open_position(&t.leader, &0u32, &100_000_000u128, &10u32, &true)
+        .position_id;
+    let mut mirrors = t.vault_client.mirror_open(&leader_position, &100u32);
+    assert_within_network_limits(&env, "mirror_open");
+    let mut opened = mirrors.len();
+    while !t.vault_client.get_mirror_queue(&leader_position).is_empty() {
+        mirrors = t.vault_client.continue_mirror_open(&leader_position);
+        assert_within_network_limits(&env, "continue_mirror_open");
+        assert!(mirrors.len() <= MAX_MIRRORS_PER_CALL);
+        opened += mirrors.len();
+    }
+    assert_eq!(opened, MAX_FOLLOWERS);
+
+    let mut pages = 0;
+    while !t.vault_client.get_leader_mirrors(&leader_position).is_empty() {
+        t.vault_client.mirror_close(&leader_position);
+        assert_within_network_limits(&env, "mirror_close");
+        pages += 1;
+    }
+    assert_eq!(pages, MAX_FOLLOWERS.div_ceil(MAX_MIRRORS_PER_CALL));
+
+    // A follower holding the full complement of mirrors exits over several calls
+    let follower = followers.get(0).unwrap();
+    for _ in 1..MAX_FOLLOWERS {
+        t.vault_client.exit(&followers.pop_back().unwrap());
+    }
+    for _ in 0..MAX_MIRRORS_PER_FOLLOWER {
+        let leader_position = t
+            .position_client
+            .open_position(&t.leader, &0u32, &100_000_000u128, &10u32, &true)
+            .position_id;
+        t.vault_client.mirror_open(&leader_position, &100u32);
+    }
+    assert_eq!(t.vault_client.get_follower_mirrors(&follower).len(), MAX_MIRRORS_PER_FOLLOWER);
+
+    let mut paid_out = t.vault_client.exit(&follower);
+    assert_within_network_limits(&env, "exit");
+    assert_eq!(paid_out, 0);
+    assert!(t.vault_client.get_followers().is_empty());
+    while paid_out == 0 {
+        paid_out = t.vault_client.exit(&follower);
+        assert_within_network_limits(&env, "exit");
+    }
+    assert!(t.vault_client.get_follower_mirrors(&follower).is_empty());
+    assert_eq!(t.token_client.balance(&follower), paid_out as i128);
+}
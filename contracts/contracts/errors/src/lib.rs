@@ -0,0 +1,355 @@
+#![no_std]
+
+//! # Errors
+//!
+//! Contract error codes shared by the Stellars Finance contracts, so client SDKs can
+//! match on a stable number instead of parsing panic messages.
+//!
+//! ## Code Ranges
+//! Each contract raises codes from its own range, so a failure surfacing through a
+//! cross-contract call still identifies the contract it came from:
+//!
+//! | Range | Enum                  | Raised by                 |
+//! |-------|-----------------------|---------------------------|
+//! | 1xx   | `ConfigError`         | ConfigManager             |
+//! | 2xx   | `PositionError`       | PositionManager           |
+//! | 3xx   | `PoolError`           | LiquidityPool             |
+//! | 4xx   | `OracleError`         | OracleIntegrator          |
+//! | 5xx   | `MarketError`         | MarketManager             |
+//! | 6xx   | `OrderError`          | OrderManager              |
+//! | 7xx   | `KeeperRegistryError` | KeeperRegistry            |
+//! | 8xx   | `KeeperRewardsError`  | KeeperRewards             |
+//! | 9xx   | `CampaignError`       | Campaign                  |
+//! | 10xx  | `CopyTradingError`    | CopyTrading               |
+//! | 11xx  | `FaucetError`         | FaucetToken, FaucetHelper |
+//!
+//! OrderManager also raises the `PositionError` codes of the position checks it runs
+//! for its orders (pauses, SL/TP ownership, a closed position), so they keep the codes
+//! they had before orders moved out of PositionManager.
+//!
+//! A failed invocation reports `Error(Contract, #<code>)`. Every contract raises a code
+//! for each condition it checks; only broken internal invariants and arithmetic
+//! overflow still panic with a message.
+//!
+//! ## Stability
+//! Codes are part of the public interface: a code is never renumbered or reused for a
+//! different condition. New conditions take the next free code in their range.
+
+use soroban_sdk::contracterror;
+
+/// ConfigManager errors
+#[cfg(feature = "config")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ConfigError {
+    AlreadyInitialized = 101,
+    Unauthorized = 102, // Caller is not the admin (or not the registered LiquidityPool)
+    GlobalPauseRequired = 103, // Replacing the token or oracle needs the global pause
+    OpenInterestNotZero = 104, // Replacing the token or oracle needs zero open interest
+    TestHooksDisabled = 105, // Test hooks refuse to enable on the Mainnet profile
+    UnknownConfigKey = 106, // Not a parameter listed by list_config_keys()
+    InvalidLeverage = 107, // Leverage limit, step-down or decay floor out of range
+    InvalidFee = 108,   // A trading fee or frontend fee share out of range
+    InvalidMargin = 109, // Maintenance margin or liquidation threshold out of range
+    InvalidRiskTiers = 110, // Too many tiers, or notionals or margins out of order
+    InvalidKeeperRewardLimits = 111,
+    InvalidDuration = 112, // A zero interval or threshold, or a window too long
+    InvalidBps = 113,      // A ratio or basis-point parameter out of range
+    InvalidAmount = 114,   // A size, cap or rate below its minimum
+    NotPausablePerMarket = 115, // LP operations pause protocol-wide only
+    InvalidConfigBatch = 116, // Empty, or a key repeated
+    ConfigValueOutOfBounds = 117,
+}
+
+/// PositionManager errors
+///
+/// Full: a contract error enum holds at most 50 codes, so new PositionManager
+/// conditions need a second enum sharing the 2xx range.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PositionError {
+    PositionGone = 201,  // An SL/TP order's position was closed; its orders are void
+    TryNextLedger = 202, // The market's per-ledger open cap is used up
+    AlreadyInitialized = 203,
+    NotAdmin = 204, // Caller is not the ConfigManager admin
+    PositionNotFound = 205,
+    NotPositionOwner = 206,
+    LeverageTooLow = 207,            // Below the market's min leverage
+    LeverageTooHigh = 208,           // Above the market's max leverage
+    LeverageAboveStepDown = 209,     // Above the protocol-wide step-down
+    LeverageAboveOiLimit = 210,      // Above the market's leverage decay for its OI
+    PositionSizeTooSmall = 211,      // Below the min position size
+    AccountNotAllowed = 212,         // Refused by the compliance hook
+    OperationPaused = 213,           // Paused by ConfigManager (globally or per operation)
+    MarketPaused = 214,              // Paused in MarketManager
+    MarketCapacityUnavailable = 215, // Market paused, side disabled or OI limit reached
+    NoAvailableLiquidity = 216,      // The pool has no liquidity to reserve
+    NotOrderManager = 217,           // Caller is not the registered OrderManager
+    InvalidTokenTransfer = 218,      // A negative amount or a transfer to self
+    KeeperNotBonded = 219,
+    LiquidationPriorityWindow = 220, // Only bonded keepers liquidate until the window passes
+    InvalidPageLimit = 221,          // Page or scan limit is 0 or above the maximum
+    PositionUnderwater = 222,        // The realized loss would use up the collateral
+    DailyLossLimitReached = 223,     // The trader's own daily loss limit
+    MaxNotionalExceeded = 224,       // The trader's own total notional limit
+    SizeNotLotMultiple = 225,
+    SizeAboveRiskTiers = 226, // Larger than the market's largest risk tier
+    InsufficientInitialMargin = 227, // Below the risk tier's initial margin
+    ZeroAmount = 228,         // An amount, leverage or price is 0, or nothing changes
+    InsufficientCollateral = 229, // Removing more collateral than the position holds
+    InsufficientSize = 230,   // Reducing more size than the position holds
+    RemovesAllCollateral = 231, // Use close_position instead
+    MaintenanceMarginViolated = 232,
+    WouldBeLiquidatable = 233,
+    TargetSizeTooLarge = 234, // A target or split size is not below the position size
+    UtilizationExceeded = 235, // Over the pool's max utilization
+    IntegratorNotAllowed = 236,
+    FrontendNotRegistered = 237,
+    WithdrawalExceedsFreeMargin = 238,
+    InvalidMerge = 239, // Fewer than two, too many, or a position listed twice
+    PositionsNotMergeable = 240, // Positions differ in market or direction
+    PositionNotDust = 241,
+    PositionNotLiquidatable = 242,
+    TestHooksDisabled = 243, // Test hooks need the oracle's test mode
+    SettlementNotEnabled = 244,
+    InvalidRiskBucket = 245,
+    InvalidThreshold = 246, // Margin warning or maintenance margin bps out of range
+    HookAlreadyRegistered = 247,
+    TooManyHooks = 248,
+    HookNotRegistered = 249,
+    NotInitialized = 250,
+}
+
+/// LiquidityPool errors
+#[cfg(feature = "pool")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PoolError {
+    AlreadyInitialized = 301,
+    NotAdmin = 302,           // Caller is not the ConfigManager admin
+    NotPositionManager = 303, // Caller is not the authorized PositionManager
+    AccountNotAllowed = 304,  // Refused by the compliance hook
+    InsufficientShares = 305,
+    DepositsPaused = 306,
+    WithdrawalsPaused = 307,
+    PausedWithdrawalLimitExceeded = 308, // Over the throttle while globally paused
+    InsufficientLiquidity = 309,         // Not enough unreserved liquidity
+    ReserveRatioViolated = 310,          // Withdrawal would breach the min reserve ratio
+    GlobalPauseRequired = 311,           // Token migration steps need the global pause
+    NotOrderManager = 312,               // Caller is not the registered OrderManager
+    InvalidTokenTransfer = 313,          // A negative amount or a transfer to self
+    PositionManagerNotSet = 314,
+    InvalidAmount = 315,    // An amount, share count or value not in range
+    InvalidBatchSize = 316, // Empty, or above the batch or history limit
+    InvalidInterval = 317,  // A zero checkpoint interval or drawdown window
+    InvalidBps = 318,       // A basis-point parameter above 10000
+    InvalidPhaseTransition = 319,
+    PoolNotTrading = 320,          // Reserving liquidity needs the Trading phase
+    PoolBootstrapping = 321,       // Withdrawals wait for the seed target
+    PoolWindingDown = 322,         // Deposits are closed once wind-down starts
+    PoolNotTerminal = 323,         // Emergency withdrawals need the Terminal phase
+    PoolValueDepleted = 324,       // Shares are outstanding but the pool holds no value
+    OpenInterestOutstanding = 325, // Positions must close before this withdrawal
+    NoFeesToClaim = 326,
+    PoolAlreadySeeded = 327,
+    SeedTargetNotMet = 328,
+    NotHedger = 329,
+    HedgeBorrowOutstanding = 330, // The hedger still owes borrowed principal
+    HedgeBorrowCapExceeded = 331,
+    InsufficientPositionCollateral = 332,
+    ReleaseExceedsReserved = 333,
+    NoTokenMigration = 334,     // No token migration is configured
+    InvalidMigrationRate = 335, // Not positive, or not 1:1 while token amounts are owed
+    AlreadyBackedByToken = 336,
+    OrderEscrowHeld = 337, // OrderManager holds collateral or fees in the pool token
+    MigrationSnapshotRequired = 338,
+    PoolChangedSinceSnapshot = 339,
+    MigrationShortfall = 340, // The migrator returned too few new tokens
+    EscrowAlreadyParked = 341,
+    EscrowTooSmall = 342, // Worth less than one escrow unit
+    NoParkedEscrow = 343,
+}
+
+/// OracleIntegrator errors
+#[cfg(feature = "oracle")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OracleError {
+    AlreadyInitialized = 401,
+    Unauthorized = 402, // Caller is not the ConfigManager admin
+    UnsupportedMarket = 403,
+    NoValidPrice = 404,            // Every source is stale or invalid
+    ExcessivePriceDeviation = 405, // Sources disagree beyond the max deviation
+    TestHooksDisabled = 406,       // Test mode refuses to enable on the Mainnet profile
+    NoTwapRecorded = 407,
+    NotMarketManager = 408,
+    NotInitialized = 409,
+    AdapterNotImplemented = 410, // DIA and Reflector adapters are still stubs
+    TestModeRequired = 411,      // Test builds have no production oracle path
+    InvalidPricePath = 412,      // Zero interval, too long, or a non-positive price
+    InvalidDecimals = 413,
+    InvalidQuorum = 414, // Zero, or more than the registered sources
+    MissingSourceWeight = 415,
+    SourceAlreadyRegistered = 416,
+    TooManySources = 417,
+    SourceNotRegistered = 418,
+}
+
+/// MarketManager errors
+#[cfg(feature = "market")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MarketError {
+    AlreadyInitialized = 501,
+    NotAdmin = 502,
+    NotPositionManager = 503,
+    MarketNotFound = 504,
+    MarketAlreadyExists = 505,
+    MarketPaused = 506,
+    OiCapExceeded = 507,             // Over the market's max open interest
+    OiBelowZero = 508,               // Reducing OI by more than is open
+    OiGrowthLimitExceeded = 509,     // Over the hourly OI growth allowance
+    InsufficientOracleSources = 510, // Too few healthy sources to go live
+    NotInitialized = 511,
+    TestHooksDisabled = 512, // Test hooks need the oracle in test mode
+    PositionManagerNotSet = 513,
+    InvalidPrice = 514,
+    InvalidSize = 515,
+}
+
+/// OrderManager errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OrderError {
+    OrderNotFound = 601,
+    NotOrderOwner = 602,
+    OrderExpired = 603,
+    OrderNotTriggered = 604,
+    SliceNotDue = 605, // The next TWAP slice is not due yet
+    ExecutionFeeBelowMinimum = 606,
+    TooManyOrders = 607,    // The trader's active order limit is reached
+    OrderBookFull = 608,    // The market's active order limit is reached
+    OrderClaimed = 609,     // Another keeper holds the execution claim
+    SlippageExceeded = 610, // Price outside the order's acceptable range
+    AlreadyInitialized = 611,
+    InvalidTokenTransfer = 612, // A negative amount or a transfer to self
+    KeeperNotBonded = 613,
+    InvalidPageLimit = 614, // Page limit is 0 or above the maximum
+    ExpirationInPast = 615,
+    ExpirationNotExtended = 616, // A new expiration must be later than the current one
+    UnsupportedTimeInForce = 617, // Immediate-or-cancel is only for limit orders
+    InvalidTriggerPrice = 618,   // SL/TP trigger on the wrong side of the current price
+    TriggerPastLiquidation = 619, // Stop-loss beyond the liquidation price
+    WrongOrderType = 620,        // The operation does not apply to this order type
+    EscrowParked = 621,          // Parked escrow can't be split, reduced or parked again
+    ParkedEscrowLost = 622,      // Redeeming the parked escrow returned nothing
+    MatchRemainderTooSmall = 623, // A match would leave a remainder below the min size
+    ZeroAmount = 624,            // A price, collateral, interval or limit is 0
+    InvalidClosePercentage = 625,
+    InvalidPnlTrigger = 626,
+    InvalidTwapSlices = 627, // TWAP slices must be between 2 and 100
+    SelfDelegation = 628,
+    NotAReduction = 629, // A reduction must shrink the order and not grow it
+    MatchingDisabled = 630,
+    MarketMismatch = 631, // Matched orders must be in the same market
+    SelfMatch = 632,      // Matched orders must belong to different traders
+    OrdersDoNotCross = 633,
+    PositionNotExpired = 634,
+    BatchTooLarge = 635,
+    NoPositionExpiry = 636,
+    NotInitialized = 637,
+    NotPositionManager = 638, // Caller is not the registered PositionManager
+}
+
+/// KeeperRegistry errors
+#[cfg(feature = "keeper-registry")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum KeeperRegistryError {
+    AlreadyInitialized = 701,
+    NotInitialized = 702,
+    Unauthorized = 703,         // Caller is not the registry admin
+    UnauthorizedReporter = 704, // Caller is not the PositionManager or OracleIntegrator
+    InvalidSlash = 705,         // Slash above 10000 bps
+    InvalidMaxStrikes = 706,
+    InvalidTokenTransfer = 707, // A negative amount or a transfer to self
+    ZeroAmount = 708,
+    InsufficientBond = 709,
+    NoPendingWithdrawal = 710,
+    WithdrawalCooldownActive = 711,
+}
+
+/// KeeperRewards errors
+#[cfg(feature = "keeper-rewards")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum KeeperRewardsError {
+    AlreadyInitialized = 801,
+    UnauthorizedReporter = 802, // Caller is not the registered PositionManager or OrderManager
+    NoRewardsToClaim = 803,
+    InvalidKeeperShare = 804, // Keeper share above 10000 bps
+    NotInitialized = 805,
+    InvalidTokenTransfer = 806, // A negative amount or a transfer to self
+}
+
+/// Campaign errors
+#[cfg(feature = "campaign")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CampaignError {
+    AlreadyInitialized = 901,
+    NotInitialized = 902,
+    Unauthorized = 903,         // Caller is not the campaign admin
+    UnauthorizedReporter = 904, // Caller may not report this activity
+    InvalidThreshold = 905,
+    UnauthorizedAttester = 906,
+    BadgeAlreadyEarned = 907,
+}
+
+/// CopyTrading errors
+#[cfg(feature = "copy-trading")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CopyTradingError {
+    AlreadyInitialized = 1001,
+    NotInitialized = 1002,
+    InvalidTokenTransfer = 1003, // A negative amount or a transfer to self
+    FollowerNotFound = 1004,
+    MirrorNotFound = 1005,
+    NothingToMirror = 1006, // No mirror queue for the leader position
+    FeeTooHigh = 1007,      // Management fee above 500 or performance fee above 5000 bps
+    ZeroAmount = 1008,
+    FollowerLimitReached = 1009,
+    InsufficientBalance = 1010,
+    InvalidAllocation = 1011, // Allocation outside 1-10000 bps
+    AlreadyMirrored = 1012,
+    NotLeaderPosition = 1013,
+}
+
+/// FaucetToken and FaucetHelper errors
+#[cfg(feature = "faucet")]
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FaucetError {
+    AlreadyInitialized = 1101,
+    NotInitialized = 1102,
+    ZeroAmount = 1103,
+    NegativeAmount = 1104,
+    InsufficientBalance = 1105,
+    AllowanceExpired = 1106,
+    InsufficientAllowance = 1107,
+    ExpirationInPast = 1108,
+    FaucetDisabled = 1109, // The faucet refuses to mint on the Mainnet profile
+    CollateralTooLarge = 1110, // Collateral does not fit the token's i128 amounts
+}
@@ -0,0 +1,25 @@
+#![no_std]
+
+//! # Events
+//!
+//! Protocol-wide event sequence shared by the Stellars Finance contracts.
+//!
+//! ## Sequence
+//! Every event a protocol contract emits ends with a `seq` field drawn from one counter
+//! kept by ConfigManager, so indexers can order events across contracts within a
+//! ledger and spot any they missed. ConfigManager advances the counter directly for
+//! its own events; every other contract draws its number with `next_seq()`.
+//!
+//! Drawing a number costs a cross-contract call, and ConfigManager cannot emit
+//! through it: a contract already on the call stack cannot be re-entered.
+
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+/// Draw the next protocol-wide event sequence number from ConfigManager
+pub fn next_seq(env: &Env, config_manager: &Address) -> u64 {
+    env.invoke_contract(
+        config_manager,
+        &Symbol::new(env, "next_event_seq"),
+        Vec::new(env),
+    )
+}
@@ -0,0 +1,212 @@
+#![no_std]
+
+//! # Faucet Helper Contract
+//!
+//! Testnet onboarding shortcut for the Stellars Finance protocol. Mints FaucetToken and
+//! puts it to work in the same call, so a new user can provide liquidity or open a demo
+//! position with one transaction instead of minting, approving and depositing separately.
+//!
+//! ## Key Features
+//! - **Mint and Deposit**: Mints tokens to the user and deposits them into the LiquidityPool
+//! - **Mint and Open**: Mints collateral to the user and opens a position with it
+//!
+//! ## Why a Separate Contract
+//! The LiquidityPool and PositionManager pull tokens with a `transfer` on the token
+//! contract, and Soroban does not allow a contract to be re-entered. The faucet token
+//! therefore cannot deposit on a user's behalf itself; this helper calls the faucet and
+//! the protocol in turn instead.
+//!
+//! ## Usage
+//! - The protocol token registered in ConfigManager must be a FaucetToken
+//! - Users call `mint_and_deposit()` or `mint_and_open_position()` and authorize the whole
+//!   invocation once; positions and LP shares are owned by the user, not the helper
+//!
+//! ## Warning
+//!
+//! This contract is for TESTNET ONLY, like the faucet token it mints. It refuses to mint
+//! when the ConfigManager was initialized with the mainnet network profile.
+
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, panic_with_error, Address, Env,
+};
+
+pub use errors::FaucetError;
+
+mod config_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
+}
+
+mod faucet_token {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/faucet_token.wasm");
+}
+
+mod liquidity_pool {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/liquidity_pool.wasm");
+}
+
+mod position_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/position_manager.wasm");
+}
+
+// Data Structures
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    ConfigManager,
+}
+
+// Events
+
+#[contractevent]
+pub struct MintedAndDepositedEvent {
+    pub user: Address,
+    pub amount: i128,
+    pub shares: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct MintedAndOpenedEvent {
+    pub user: Address,
+    pub position_id: u64,
+    pub collateral: u128,
+    pub seq: u64,
+}
+
+// Helper Functions
+
+fn get_config_client(env: &Env) -> config_manager::Client<'_> {
+    let config_manager: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::ConfigManager)
+        .unwrap_or_else(|| panic_with_error!(env, FaucetError::NotInitialized));
+    config_manager::Client::new(env, &config_manager)
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_client(env).address)
+}
+
+/// Mint `amount` of the protocol's faucet token to `user`
+fn mint_to(env: &Env, config_client: &config_manager::Client, user: &Address, amount: i128) {
+    if !config_client.test_hooks_allowed() {
+        panic_with_error!(env, FaucetError::FaucetDisabled);
+    }
+    let token_client = faucet_token::Client::new(env, &config_client.token());
+    token_client.mint(user, &amount);
+}
+
+#[contract]
+pub struct FaucetHelper;
+
+#[contractimpl]
+impl FaucetHelper {
+    /// Initialize the helper with the ConfigManager it reads protocol addresses from.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The deployer (must authorize)
+    /// * `config_manager` - The Config Manager contract address
+    ///
+    /// # Panics
+    ///
+    /// Panics if already initialized or admin doesn't authorize
+    pub fn initialize(env: Env, admin: Address, config_manager: Address) {
+        if env.storage().instance().has(&DataKey::ConfigManager) {
+            panic_with_error!(&env, FaucetError::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ConfigManager, &config_manager);
+    }
+
+    /// Mint faucet tokens to the user and deposit them into the LiquidityPool.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The new LP (must authorize)
+    /// * `amount` - The amount of tokens to mint and deposit
+    ///
+    /// # Returns
+    ///
+    /// The number of LP shares minted to the user
+    ///
+    /// # Panics
+    ///
+    /// Panics if amount is not positive, on the mainnet network profile, or if the
+    /// deposit would panic
+    pub fn mint_and_deposit(env: Env, user: Address, amount: i128) -> i128 {
+        user.require_auth();
+
+        let config_client = get_config_client(&env);
+        mint_to(&env, &config_client, &user, amount);
+
+        let pool_client = liquidity_pool::Client::new(&env, &config_client.liquidity_pool());
+        let shares = pool_client.deposit(&user, &amount);
+
+        MintedAndDepositedEvent {
+            user,
+            amount,
+            shares,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        shares
+    }
+
+    /// Mint collateral to the user and open a demo position with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The trader (must authorize); owns the opened position
+    /// * `market_id` - The market to trade
+    /// * `collateral` - The amount of tokens to mint and post as collateral
+    /// * `leverage` - The leverage multiplier
+    /// * `is_long` - True for a long position, false for a short
+    ///
+    /// # Returns
+    ///
+    /// The PositionManager's receipt for the opened position
+    ///
+    /// # Panics
+    ///
+    /// Panics if collateral is zero or too large, on the mainnet network profile, or
+    /// if the open would panic
+    pub fn mint_and_open_position(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        collateral: u128,
+        leverage: u32,
+        is_long: bool,
+    ) -> position_manager::OpenReceipt {
+        user.require_auth();
+
+        let amount = i128::try_from(collateral)
+            .unwrap_or_else(|_| panic_with_error!(&env, FaucetError::CollateralTooLarge));
+        let config_client = get_config_client(&env);
+        mint_to(&env, &config_client, &user, amount);
+
+        let position_client =
+            position_manager::Client::new(&env, &config_client.position_manager());
+        let receipt =
+            position_client.open_position(&user, &market_id, &collateral, &leverage, &is_long);
+
+        MintedAndOpenedEvent {
+            user,
+            position_id: receipt.position_id,
+            collateral,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        receipt
+    }
+}
+
+mod test;
@@ -0,0 +1,165 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, Map, String};
+
+mod oracle_integrator {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/oracle_integrator.wasm"
+    );
+}
+
+mod market_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/market_manager.wasm");
+}
+
+struct TestEnv<'a> {
+    token_client: faucet_token::Client<'a>,
+    pool_client: liquidity_pool::Client<'a>,
+    position_client: position_manager::Client<'a>,
+    helper_client: FaucetHelperClient<'a>,
+}
+
+/// Deploy the full protocol on a faucet token plus the helper
+fn setup_test_environment(env: &Env) -> TestEnv<'_> {
+    env.mock_all_auths();
+    // Deploying the protocol exceeds the default test budget
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+
+    let token_id = env.register(faucet_token::WASM, ());
+    let token_client = faucet_token::Client::new(env, &token_id);
+    token_client.initialize(
+        &String::from_str(env, "Test USDC"),
+        &String::from_str(env, "USDC"),
+        &7u32,
+    );
+
+    let config_id = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(env, &config_id);
+
+    let oracle_id = env.register(oracle_integrator::WASM, (&config_id,));
+    let oracle_client = oracle_integrator::Client::new(env, &oracle_id);
+    let mut base_prices = Map::new(env);
+    base_prices.set(0u32, 100_000_000i128); // XLM: $1.00
+    oracle_client.set_test_mode(&admin, &true, &base_prices);
+    oracle_client.set_fixed_price_mode(&admin, &true);
+
+    let market_id = env.register(market_manager::WASM, (&config_id, &admin));
+    let market_client = market_manager::Client::new(env, &market_id);
+
+    let pool_id = env.register(liquidity_pool::WASM, (&config_id, &token_id));
+    let pool_client = liquidity_pool::Client::new(env, &pool_id);
+
+    let position_manager_id = env.register(position_manager::WASM, (&config_id,));
+    let position_client = position_manager::Client::new(env, &position_manager_id);
+
+    config_client.set_oracle_integrator(&admin, &oracle_id);
+    config_client.set_market_manager(&admin, &market_id);
+    config_client.set_liquidity_pool(&admin, &pool_id);
+    config_client.set_position_manager(&admin, &position_manager_id);
+    config_client.set_token(&admin, &token_id);
+    market_client.set_position_manager(&admin, &position_manager_id);
+    pool_client.set_position_manager(&admin, &position_manager_id);
+    market_client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    let helper_id = env.register(FaucetHelper, ());
+    let helper_client = FaucetHelperClient::new(env, &helper_id);
+    helper_client.initialize(&admin, &config_id);
+
+    TestEnv {
+        token_client,
+        pool_client,
+        position_client,
+        helper_client,
+    }
+}
+
+#[test]
+fn test_mint_and_deposit() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let lp = Address::generate(&env);
+    let shares = t.helper_client.mint_and_deposit(&lp, &1_000_000_000);
+
+    // First deposit mints shares 1:1, and every minted token ends up in the pool
+    assert_eq!(shares, 1_000_000_000);
+    assert_eq!(t.pool_client.get_shares(&lp), 1_000_000_000);
+    assert_eq!(t.token_client.balance(&lp), 0);
+    assert_eq!(
+        t.token_client.balance(&t.pool_client.address),
+        1_000_000_000
+    );
+}
+
+#[test]
+fn test_mint_and_open_position() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let lp = Address::generate(&env);
+    t.helper_client.mint_and_deposit(&lp, &100_000_000_000);
+
+    let trader = Address::generate(&env);
+    let receipt =
+        t.helper_client
+            .mint_and_open_position(&trader, &0u32, &1_000_000_000u128, &5u32, &true);
+
+    // The position belongs to the user, not the helper
+    let position = t.position_client.get_position(&receipt.position_id);
+    assert_eq!(position.trader, trader);
+    assert_eq!(position.collateral, 1_000_000_000);
+    assert_eq!(position.size, 5_000_000_000);
+    assert_eq!(t.token_client.balance(&trader), 0);
+}
+
+#[test]
+fn test_mint_and_deposit_zero_amount() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    // The faucet token rejects the mint; its panic message does not cross the WASM boundary
+    let lp = Address::generate(&env);
+    assert!(t.helper_client.try_mint_and_deposit(&lp, &0).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1101)")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    let admin = Address::generate(&env);
+    t.helper_client.initialize(&admin, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1109)")]
+fn test_mint_refused_on_mainnet_profile() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_id = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Mainnet),
+    );
+
+    let helper_id = env.register(FaucetHelper, ());
+    let helper_client = FaucetHelperClient::new(&env, &helper_id);
+    helper_client.initialize(&admin, &config_id);
+
+    // Refused before the token or pool is ever looked up
+    helper_client.mint_and_deposit(&Address::generate(&env), &1_000_000_000);
+}
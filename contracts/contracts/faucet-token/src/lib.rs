@@ -18,7 +18,9 @@
 //!
 //! This token is for TESTNET ONLY and should never be used in production.
 
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, contracttype, panic_with_error, Address, Env, String};
+
+pub use errors::FaucetError;
 
 #[derive(Clone)]
 #[contracttype]
@@ -106,11 +108,11 @@ fn spend_allowance(e: &Env, from: &Address, spender: &Address, amount: i128) {
     let allowance = get_allowance(e, from, spender);
 
     if allowance.live_until_ledger < e.ledger().sequence() {
-        panic!("allowance expired");
+        panic_with_error!(e, FaucetError::AllowanceExpired);
     }
 
     if allowance.amount < amount {
-        panic!("insufficient allowance");
+        panic_with_error!(e, FaucetError::InsufficientAllowance);
     }
 
     put_allowance(
@@ -139,7 +141,7 @@ impl FaucetToken {
     /// Panics if the token is already initialized
     pub fn initialize(env: Env, name: String, symbol: String, decimals: u32) {
         if env.storage().instance().has(&DataKey::Name) {
-            panic!("already initialized");
+            panic_with_error!(&env, FaucetError::AlreadyInitialized);
         }
 
         env.storage().instance().set(&DataKey::Name, &name);
@@ -209,7 +211,7 @@ impl FaucetToken {
     /// Panics if amount is not positive
     pub fn mint(env: Env, to: Address, amount: i128) {
         if amount <= 0 {
-            panic!("amount must be positive");
+            panic_with_error!(&env, FaucetError::ZeroAmount);
         }
 
         let current_balance = get_balance(&env, &to);
@@ -235,12 +237,12 @@ impl FaucetToken {
         from.require_auth();
 
         if amount <= 0 {
-            panic!("amount must be positive");
+            panic_with_error!(&env, FaucetError::ZeroAmount);
         }
 
         let from_balance = get_balance(&env, &from);
         if from_balance < amount {
-            panic!("insufficient balance");
+            panic_with_error!(&env, FaucetError::InsufficientBalance);
         }
 
         let to_balance = get_balance(&env, &to);
@@ -290,11 +292,11 @@ impl FaucetToken {
         from.require_auth();
 
         if amount < 0 {
-            panic!("amount cannot be negative");
+            panic_with_error!(&env, FaucetError::NegativeAmount);
         }
 
         if live_until_ledger <= env.ledger().sequence() {
-            panic!("expiration must be in the future");
+            panic_with_error!(&env, FaucetError::ExpirationInPast);
         }
 
         put_allowance(
@@ -326,14 +328,14 @@ impl FaucetToken {
         spender.require_auth();
 
         if amount <= 0 {
-            panic!("amount must be positive");
+            panic_with_error!(&env, FaucetError::ZeroAmount);
         }
 
         spend_allowance(&env, &from, &spender, amount);
 
         let from_balance = get_balance(&env, &from);
         if from_balance < amount {
-            panic!("insufficient balance");
+            panic_with_error!(&env, FaucetError::InsufficientBalance);
         }
 
         let to_balance = get_balance(&env, &to);
@@ -356,12 +358,12 @@ impl FaucetToken {
         from.require_auth();
 
         if amount <= 0 {
-            panic!("amount must be positive");
+            panic_with_error!(&env, FaucetError::ZeroAmount);
         }
 
         let balance = get_balance(&env, &from);
         if balance < amount {
-            panic!("insufficient balance");
+            panic_with_error!(&env, FaucetError::InsufficientBalance);
         }
 
         put_balance(&env, &from, balance - amount);
@@ -387,14 +389,14 @@ impl FaucetToken {
         spender.require_auth();
 
         if amount <= 0 {
-            panic!("amount must be positive");
+            panic_with_error!(&env, FaucetError::ZeroAmount);
         }
 
         spend_allowance(&env, &from, &spender, amount);
 
         let balance = get_balance(&env, &from);
         if balance < amount {
-            panic!("insufficient balance");
+            panic_with_error!(&env, FaucetError::InsufficientBalance);
         }
 
         put_balance(&env, &from, balance - amount);
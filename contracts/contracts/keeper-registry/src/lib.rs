@@ -0,0 +1,521 @@
+#![no_std]
+
+//! # Keeper Registry Contract
+//!
+//! Bond registry for permissioned keeper mode in the Stellars Finance protocol. Keepers
+//! post a bond before they may liquidate positions or execute orders, and repeated invalid
+//! submissions slash part of that bond to the insurance fund.
+//!
+//! ## Key Features
+//! - **Keeper Bonds**: Keepers deposit tokens; a keeper is bonded while its active bond
+//!   (deposited minus pending withdrawals) meets the minimum
+//! - **Withdrawal Cooldown**: Withdrawals are requested first and paid out after a
+//!   cooldown, so a keeper can't pull its bond right before being slashed
+//! - **Strikes and Slashing**: Protocol contracts report failed on-chain validation (an
+//!   invalid liquidation, a stale price push) as a strike; reaching the strike limit slashes
+//!   a share of the bond to the insurance fund and resets the count
+//!
+//! ## Integration
+//! Permissioned mode is enabled by registering this contract with
+//! `ConfigManager::set_keeper_registry()`. PositionManager then requires `is_bonded()` for
+//! keeper actions and reports invalid liquidations via `record_failure()`.
+//!
+//! ## Usage
+//! - Keepers call `deposit_bond()`, `request_withdrawal()` and `withdraw_bond()`
+//! - PositionManager and OracleIntegrator call `record_failure()`
+
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, panic_with_error, token, Address, Env,
+};
+
+pub use errors::KeeperRegistryError;
+
+mod config_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
+}
+
+const BPS_DIVISOR: u128 = 10_000;
+
+// Data Structures
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperBond {
+    pub amount: u128,             // Total bonded tokens, including pending withdrawals
+    pub pending_withdrawal: u128, // Requested for withdrawal, no longer counts as active
+    pub unlock_at: u64,           // When the pending withdrawal can be paid out
+    pub strikes: u32,             // Failures since the last slash
+}
+
+/// Kind of invalid keeper submission detected by on-chain validation
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeeperFailure {
+    InvalidLiquidation,
+    StalePricePush,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    ConfigManager,
+    MinBond,            // Minimum active bond to count as bonded
+    SlashBps,           // Share of the bond slashed at the strike limit
+    MaxStrikes,         // Strikes that trigger a slash
+    WithdrawalCooldown, // Seconds between requesting and withdrawing a bond
+    Bond(Address),      // Keeper -> KeeperBond
+}
+
+// Events
+
+/// Published for every token transfer the contract makes (see `move_tokens`)
+#[contractevent]
+pub struct TokenMovedEvent {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct BondDepositedEvent {
+    pub keeper: Address,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct BondWithdrawalRequestedEvent {
+    pub keeper: Address,
+    pub amount: u128,
+    pub unlock_at: u64,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct BondWithdrawnEvent {
+    pub keeper: Address,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct KeeperStrikeEvent {
+    pub keeper: Address,
+    pub failure: KeeperFailure,
+    pub strikes: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct KeeperSlashedEvent {
+    pub keeper: Address,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+// Helper Functions
+
+fn get_config_manager(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::ConfigManager)
+        .unwrap_or_else(|| panic_with_error!(env, KeeperRegistryError::NotInitialized))
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_manager(env))
+}
+
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+    let stored_admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| panic_with_error!(env, KeeperRegistryError::NotInitialized));
+    if admin != &stored_admin {
+        panic_with_error!(env, KeeperRegistryError::Unauthorized);
+    }
+}
+
+/// Only protocol contracts that validate keeper submissions may report failures
+fn require_reporter(env: &Env, reporter: &Address) {
+    reporter.require_auth();
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    if reporter != &config_client.position_manager()
+        && reporter != &config_client.oracle_integrator()
+    {
+        panic_with_error!(env, KeeperRegistryError::UnauthorizedReporter);
+    }
+}
+
+fn set_params(
+    env: &Env,
+    min_bond: u128,
+    slash_bps: u32,
+    max_strikes: u32,
+    withdrawal_cooldown: u64,
+) {
+    if slash_bps as u128 > BPS_DIVISOR {
+        panic_with_error!(env, KeeperRegistryError::InvalidSlash);
+    }
+    if max_strikes == 0 {
+        panic_with_error!(env, KeeperRegistryError::InvalidMaxStrikes);
+    }
+
+    env.storage().instance().set(&DataKey::MinBond, &min_bond);
+    env.storage().instance().set(&DataKey::SlashBps, &slash_bps);
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxStrikes, &max_strikes);
+    env.storage()
+        .instance()
+        .set(&DataKey::WithdrawalCooldown, &withdrawal_cooldown);
+}
+
+fn get_bond(env: &Env, keeper: &Address) -> KeeperBond {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Bond(keeper.clone()))
+        .unwrap_or(KeeperBond {
+            amount: 0,
+            pending_withdrawal: 0,
+            unlock_at: 0,
+            strikes: 0,
+        })
+}
+
+fn set_bond(env: &Env, keeper: &Address, bond: &KeeperBond) {
+    if bond.amount == 0 && bond.strikes == 0 {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Bond(keeper.clone()));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bond(keeper.clone()), bond);
+    }
+}
+
+/// Move `amount` of the protocol token from one address to another.
+///
+/// Every token transfer in this contract goes through here so fund movements have a
+/// single path to audit: negative amounts and transfers back to the sender are refused,
+/// zero amounts are skipped, and each transfer publishes a `TokenMovedEvent`.
+fn move_tokens(env: &Env, from: &Address, to: &Address, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(env, KeeperRegistryError::InvalidTokenTransfer);
+    }
+    if from == to {
+        panic_with_error!(env, KeeperRegistryError::InvalidTokenTransfer);
+    }
+    if amount == 0 {
+        return;
+    }
+    let token = config_manager::Client::new(env, &get_config_manager(env)).token();
+    token::Client::new(env, &token).transfer(from, to, &amount);
+    TokenMovedEvent {
+        token,
+        from: from.clone(),
+        to: to.clone(),
+        amount,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contract]
+pub struct KeeperRegistry;
+
+#[contractimpl]
+impl KeeperRegistry {
+    /// Initialize the keeper registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address (must authorize)
+    /// * `config_manager` - Address of the ConfigManager contract
+    /// * `min_bond` - Minimum active bond for a keeper to count as bonded
+    /// * `slash_bps` - Share of the bond slashed when the strike limit is reached
+    /// * `max_strikes` - Number of failures that triggers a slash
+    /// * `withdrawal_cooldown` - Seconds between requesting and withdrawing a bond
+    ///
+    /// # Panics
+    ///
+    /// Panics if already initialized, `slash_bps` exceeds 10000 or `max_strikes` is zero
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        config_manager: Address,
+        min_bond: u128,
+        slash_bps: u32,
+        max_strikes: u32,
+        withdrawal_cooldown: u64,
+    ) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, KeeperRegistryError::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ConfigManager, &config_manager);
+        set_params(&env, min_bond, slash_bps, max_strikes, withdrawal_cooldown);
+    }
+
+    /// Update the bond and slashing parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address
+    /// * `min_bond` - Minimum active bond for a keeper to count as bonded
+    /// * `slash_bps` - Share of the bond slashed when the strike limit is reached
+    /// * `max_strikes` - Number of failures that triggers a slash
+    /// * `withdrawal_cooldown` - Seconds between requesting and withdrawing a bond
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, `slash_bps` exceeds 10000 or `max_strikes` is zero
+    pub fn set_bond_params(
+        env: Env,
+        admin: Address,
+        min_bond: u128,
+        slash_bps: u32,
+        max_strikes: u32,
+        withdrawal_cooldown: u64,
+    ) {
+        require_admin(&env, &admin);
+        set_params(&env, min_bond, slash_bps, max_strikes, withdrawal_cooldown);
+    }
+
+    /// Post or top up a keeper bond.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper posting the bond (must authorize)
+    /// * `amount` - Amount of tokens to bond
+    ///
+    /// # Returns
+    ///
+    /// The keeper's total bond after the deposit
+    pub fn deposit_bond(env: Env, keeper: Address, amount: u128) -> u128 {
+        keeper.require_auth();
+
+        if amount == 0 {
+            panic_with_error!(&env, KeeperRegistryError::ZeroAmount);
+        }
+
+        move_tokens(
+            &env,
+            &keeper,
+            &env.current_contract_address(),
+            amount as i128,
+        );
+
+        let mut bond = get_bond(&env, &keeper);
+        bond.amount += amount;
+        set_bond(&env, &keeper, &bond);
+
+        BondDepositedEvent {
+            keeper,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        bond.amount
+    }
+
+    /// Start the cooldown for withdrawing part of a bond. The requested amount stops
+    /// counting towards the active bond immediately, but can still be slashed until paid out.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper (must authorize)
+    /// * `amount` - Amount of bonded tokens to withdraw
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount exceeds the keeper's active bond
+    pub fn request_withdrawal(env: Env, keeper: Address, amount: u128) {
+        keeper.require_auth();
+
+        let mut bond = get_bond(&env, &keeper);
+        if amount == 0 || amount > bond.amount - bond.pending_withdrawal {
+            panic_with_error!(&env, KeeperRegistryError::InsufficientBond);
+        }
+
+        let cooldown: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::WithdrawalCooldown)
+            .unwrap_or(0);
+        bond.pending_withdrawal += amount;
+        bond.unlock_at = env.ledger().timestamp() + cooldown;
+        set_bond(&env, &keeper, &bond);
+
+        BondWithdrawalRequestedEvent {
+            keeper,
+            amount,
+            unlock_at: bond.unlock_at,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Pay out a pending bond withdrawal once its cooldown has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper (must authorize)
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens paid out
+    ///
+    /// # Panics
+    ///
+    /// Panics if no withdrawal is pending or the cooldown has not elapsed
+    pub fn withdraw_bond(env: Env, keeper: Address) -> u128 {
+        keeper.require_auth();
+
+        let mut bond = get_bond(&env, &keeper);
+        if bond.pending_withdrawal == 0 {
+            panic_with_error!(&env, KeeperRegistryError::NoPendingWithdrawal);
+        }
+        if env.ledger().timestamp() < bond.unlock_at {
+            panic_with_error!(&env, KeeperRegistryError::WithdrawalCooldownActive);
+        }
+
+        let amount = bond.pending_withdrawal;
+        bond.amount -= amount;
+        bond.pending_withdrawal = 0;
+        bond.unlock_at = 0;
+        set_bond(&env, &keeper, &bond);
+
+        move_tokens(
+            &env,
+            &env.current_contract_address(),
+            &keeper,
+            amount as i128,
+        );
+
+        BondWithdrawnEvent {
+            keeper,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        amount
+    }
+
+    /// Record an invalid keeper submission detected by on-chain validation. Reaching
+    /// the strike limit slashes part of the bond to the insurance fund.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The protocol contract reporting the failure (must authorize)
+    /// * `keeper` - The keeper that submitted the invalid action
+    /// * `failure` - The kind of failure detected
+    ///
+    /// # Returns
+    ///
+    /// The amount slashed (0 if the strike limit was not reached)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reporter is not the PositionManager or OracleIntegrator
+    pub fn record_failure(
+        env: Env,
+        reporter: Address,
+        keeper: Address,
+        failure: KeeperFailure,
+    ) -> u128 {
+        require_reporter(&env, &reporter);
+
+        let mut bond = get_bond(&env, &keeper);
+        bond.strikes += 1;
+
+        KeeperStrikeEvent {
+            keeper: keeper.clone(),
+            failure,
+            strikes: bond.strikes,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        let max_strikes: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MaxStrikes)
+            .unwrap_or(0);
+        if bond.strikes < max_strikes {
+            set_bond(&env, &keeper, &bond);
+            return 0;
+        }
+
+        let slash_bps: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SlashBps)
+            .unwrap_or(0);
+        let slashed = bond.amount * slash_bps as u128 / BPS_DIVISOR;
+        bond.amount -= slashed;
+        bond.pending_withdrawal = bond.pending_withdrawal.min(bond.amount);
+        bond.strikes = 0;
+        set_bond(&env, &keeper, &bond);
+
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        move_tokens(
+            &env,
+            &env.current_contract_address(),
+            &config_client.insurance_fund(),
+            slashed as i128,
+        );
+
+        KeeperSlashedEvent {
+            keeper,
+            amount: slashed,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+
+        slashed
+    }
+
+    /// Check whether a keeper's active bond meets the minimum.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper address
+    ///
+    /// # Returns
+    ///
+    /// True if the keeper may act in permissioned keeper mode
+    pub fn is_bonded(env: Env, keeper: Address) -> bool {
+        let bond = get_bond(&env, &keeper);
+        let min_bond: u128 = env.storage().instance().get(&DataKey::MinBond).unwrap_or(0);
+        bond.amount > 0 && bond.amount - bond.pending_withdrawal >= min_bond
+    }
+
+    /// Get a keeper's bond.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper address
+    ///
+    /// # Returns
+    ///
+    /// The keeper's bond (all zero if it never bonded)
+    pub fn get_bond(env: Env, keeper: Address) -> KeeperBond {
+        get_bond(&env, &keeper)
+    }
+}
+
+#[cfg(test)]
+mod test;
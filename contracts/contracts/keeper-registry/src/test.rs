@@ -0,0 +1,164 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Address, Env};
+
+struct TestEnv<'a> {
+    config_client: config_manager::Client<'a>,
+    registry_client: KeeperRegistryClient<'a>,
+    token_client: token::Client<'a>,
+    admin: Address,
+    position_manager: Address,
+    insurance_fund: Address,
+    keeper: Address,
+}
+
+/// Registry with a 100 token minimum bond, 10% slash after 3 strikes and a one day
+/// withdrawal cooldown; the keeper holds 1,000 tokens
+fn setup_test_environment(env: &Env) -> TestEnv<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let keeper = Address::generate(env);
+    let position_manager = Address::generate(env);
+    let insurance_fund = Address::generate(env);
+
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::Client::new(env, &token_contract.address());
+    let token_admin = token::StellarAssetClient::new(env, &token_contract.address());
+    token_admin.mint(&keeper, &10_000_000_000);
+
+    let config_id = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(env, &config_id);
+    config_client.set_token(&admin, &token_client.address);
+    config_client.set_position_manager(&admin, &position_manager);
+    config_client.set_insurance_fund(&admin, &insurance_fund);
+
+    let registry_id = env.register(KeeperRegistry, ());
+    let registry_client = KeeperRegistryClient::new(env, &registry_id);
+    registry_client.initialize(
+        &admin,
+        &config_id,
+        &1_000_000_000u128,
+        &1000u32,
+        &3u32,
+        &86_400u64,
+    );
+
+    TestEnv {
+        config_client,
+        registry_client,
+        token_client,
+        admin,
+        position_manager,
+        insurance_fund,
+        keeper,
+    }
+}
+
+#[test]
+fn test_bond_deposit_and_withdraw_after_cooldown() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    assert!(!t.registry_client.is_bonded(&t.keeper));
+    t.registry_client
+        .deposit_bond(&t.keeper, &1_500_000_000u128);
+    assert!(t.registry_client.is_bonded(&t.keeper));
+
+    // Requested funds stop counting immediately
+    t.registry_client
+        .request_withdrawal(&t.keeper, &1_000_000_000u128);
+    assert!(!t.registry_client.is_bonded(&t.keeper));
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    let paid_out = t.registry_client.withdraw_bond(&t.keeper);
+
+    assert_eq!(paid_out, 1_000_000_000);
+    assert_eq!(t.token_client.balance(&t.keeper), 9_500_000_000);
+    assert_eq!(t.registry_client.get_bond(&t.keeper).amount, 500_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #701)")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    t.registry_client.initialize(
+        &t.admin,
+        &t.config_client.address,
+        &1u128,
+        &0u32,
+        &1u32,
+        &0u64,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #711)")]
+fn test_withdraw_bond_before_cooldown() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    t.registry_client
+        .deposit_bond(&t.keeper, &1_000_000_000u128);
+    t.registry_client
+        .request_withdrawal(&t.keeper, &1_000_000_000u128);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_399);
+    t.registry_client.withdraw_bond(&t.keeper);
+}
+
+#[test]
+fn test_repeated_failures_slash_bond_to_insurance_fund() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    t.registry_client
+        .deposit_bond(&t.keeper, &2_000_000_000u128);
+
+    let failure = KeeperFailure::InvalidLiquidation;
+    assert_eq!(
+        t.registry_client
+            .record_failure(&t.position_manager, &t.keeper, &failure),
+        0
+    );
+    assert_eq!(
+        t.registry_client
+            .record_failure(&t.position_manager, &t.keeper, &failure),
+        0
+    );
+    assert_eq!(t.registry_client.get_bond(&t.keeper).strikes, 2);
+
+    // Third strike slashes 10% and resets the count
+    let slashed = t
+        .registry_client
+        .record_failure(&t.position_manager, &t.keeper, &failure);
+    assert_eq!(slashed, 200_000_000);
+    assert_eq!(t.token_client.balance(&t.insurance_fund), 200_000_000);
+
+    let bond = t.registry_client.get_bond(&t.keeper);
+    assert_eq!(bond.amount, 1_800_000_000);
+    assert_eq!(bond.strikes, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #704)")]
+fn test_record_failure_unauthorized_reporter() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    // OracleIntegrator is the only other valid reporter
+    t.config_client
+        .set_oracle_integrator(&t.admin, &Address::generate(&env));
+
+    t.registry_client.record_failure(
+        &Address::generate(&env),
+        &t.keeper,
+        &KeeperFailure::StalePricePush,
+    );
+}
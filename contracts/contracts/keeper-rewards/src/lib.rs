@@ -0,0 +1,477 @@
+#![no_std]
+
+//! # Keeper Rewards Contract
+//!
+//! Payout routing for keeper rewards in the Stellars Finance protocol. PositionManager
+//! liquidation rewards and OrderManager execution fees are paid through this contract,
+//! which sends them where the keeper asked or holds them as claimable balances.
+//!
+//! ## Key Features
+//! - **Keeper Payouts**: Keepers can route rewards to a payout address with a share split
+//!   off to a second recipient (`set_keeper_payout()`), so operators can split proceeds
+//!   without handing out the keeper key
+//! - **Reward Accrual**: Keepers can let rewards accrue (`set_keeper_reward_accrual()`)
+//!   and claim them in one transfer, or in the pool token as LP shares
+//!
+//! ## Integration
+//! Enabled by registering this contract with `ConfigManager::set_keeper_rewards()`.
+//! Without it, rewards are transferred to the keeper itself.
+//!
+//! ## Usage
+//! - PositionManager and OrderManager transfer a reward here and call `pay_reward()`
+//! - Keepers call `set_keeper_payout()` and `set_keeper_reward_accrual()`
+//! - Recipients call `claim_keeper_rewards()` or `claim_keeper_rewards_as_shares()`
+
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, panic_with_error, token, Address, Env, Vec,
+};
+
+pub use errors::KeeperRewardsError;
+
+mod config_manager {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
+}
+
+mod liquidity_pool {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/liquidity_pool.wasm");
+}
+
+const BPS_DIVISOR: u128 = 10_000;
+
+// Data Structures
+
+/// Where a keeper's liquidation rewards and execution fees are paid
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperPayout {
+    pub payout: Address,          // Receives the rewards after the share
+    pub share_recipient: Address, // Receives share_bps of every reward (e.g. a DAO or bot funder)
+    pub share_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    ConfigManager,
+    KeeperPayout(Address),           // Keeper -> KeeperPayout for its rewards
+    KeeperAccruesRewards(Address),   // bool: the keeper's rewards accrue as claimable balances
+    KeeperRewards(Address, Address), // (Recipient, token) -> claimable keeper rewards
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_manager(env))
+}
+
+// Events
+
+/// Published for every token transfer the contract makes (see `move_tokens`)
+#[contractevent]
+pub struct TokenMovedEvent {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct KeeperPayoutSetEvent {
+    pub keeper: Address,
+    pub payout: Address,
+    pub share_recipient: Address,
+    pub share_bps: u32,
+    pub seq: u64,
+}
+
+/// Published when a keeper reward is credited to a recipient's claimable balance
+#[contractevent]
+pub struct KeeperRewardAccruedEvent {
+    #[topic]
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: u128,
+    pub balance: u128, // Claimable balance after the credit
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct KeeperRewardsClaimedEvent {
+    #[topic]
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: u128,
+    pub shares: i128, // LP shares minted for the claim (0 when paid out as tokens)
+    pub seq: u64,
+}
+
+// Helper Functions
+
+fn get_config_manager(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::ConfigManager)
+        .unwrap_or_else(|| panic_with_error!(env, KeeperRewardsError::NotInitialized))
+}
+
+/// Move `amount` of `token` from one address to another.
+///
+/// Every token transfer in this contract goes through here so fund movements have a
+/// single path to audit: negative amounts and transfers back to the sender are refused,
+/// zero amounts are skipped, and each transfer publishes a `TokenMovedEvent`.
+fn move_tokens(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(env, KeeperRewardsError::InvalidTokenTransfer);
+    }
+    if from == to {
+        panic_with_error!(env, KeeperRewardsError::InvalidTokenTransfer);
+    }
+    if amount == 0 {
+        return;
+    }
+    token::Client::new(env, token).transfer(from, to, &amount);
+    TokenMovedEvent {
+        token: token.clone(),
+        from: from.clone(),
+        to: to.clone(),
+        amount,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+}
+
+/// Only the registered PositionManager and OrderManager may pay rewards
+fn require_reporter(env: &Env, reporter: &Address) {
+    reporter.require_auth();
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    if reporter != &config_client.position_manager() && reporter != &config_client.order_manager() {
+        panic_with_error!(env, KeeperRewardsError::UnauthorizedReporter);
+    }
+}
+
+/// Split a keeper reward between the keeper's registered payout and share recipients;
+/// without a KeeperPayout the whole reward goes to the keeper. Zero amounts are left out.
+fn keeper_reward_split(env: &Env, keeper: &Address, amount: u128) -> Vec<(Address, u128)> {
+    let mut split = Vec::new(env);
+    let payout: Option<KeeperPayout> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::KeeperPayout(keeper.clone()));
+    let Some(payout) = payout else {
+        if amount > 0 {
+            split.push_back((keeper.clone(), amount));
+        }
+        return split;
+    };
+
+    let share = amount * payout.share_bps as u128 / BPS_DIVISOR;
+    if amount > share {
+        split.push_back((payout.payout, amount - share));
+    }
+    if share > 0 {
+        split.push_back((payout.share_recipient, share));
+    }
+    split
+}
+
+fn keeper_accrues_rewards(env: &Env, keeper: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::KeeperAccruesRewards(keeper.clone()))
+        .unwrap_or(false)
+}
+
+fn get_keeper_rewards(env: &Env, recipient: &Address, token: &Address) -> u128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::KeeperRewards(recipient.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+/// Credit a keeper reward already held by this contract to `recipient`'s claimable
+/// balance instead of transferring it
+fn credit_keeper_reward(env: &Env, recipient: &Address, token: &Address, amount: u128) {
+    let balance = get_keeper_rewards(env, recipient, token) + amount;
+    env.storage().persistent().set(
+        &DataKey::KeeperRewards(recipient.clone(), token.clone()),
+        &balance,
+    );
+    KeeperRewardAccruedEvent {
+        recipient: recipient.clone(),
+        token: token.clone(),
+        amount,
+        balance,
+        seq: next_event_seq(env),
+    }
+    .publish(env);
+}
+
+/// Clear and return a recipient's claimable keeper rewards in `token`
+fn take_keeper_rewards(env: &Env, recipient: &Address, token: &Address) -> u128 {
+    let amount = get_keeper_rewards(env, recipient, token);
+    if amount == 0 {
+        panic_with_error!(env, KeeperRewardsError::NoRewardsToClaim);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::KeeperRewards(recipient.clone(), token.clone()));
+    amount
+}
+
+#[contract]
+pub struct KeeperRewards;
+
+#[contractimpl]
+impl KeeperRewards {
+    /// Initialize the contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address (must authorize)
+    /// * `config_manager` - Address of the ConfigManager contract
+    ///
+    /// # Panics
+    ///
+    /// Panics if already initialized
+    pub fn initialize(env: Env, admin: Address, config_manager: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, KeeperRewardsError::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ConfigManager, &config_manager);
+    }
+
+    /// Pay a keeper reward the caller has just transferred to this contract: routed to
+    /// the keeper's payout addresses, or credited as claimable rewards if the keeper
+    /// accrues them.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The PositionManager or OrderManager (must authorize)
+    /// * `keeper` - The keeper earning the reward
+    /// * `token` - The reward token
+    /// * `amount` - The reward, already held by this contract
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reporter is not the PositionManager or OrderManager
+    pub fn pay_reward(env: Env, reporter: Address, keeper: Address, token: Address, amount: u128) {
+        require_reporter(&env, &reporter);
+
+        let accrue = keeper_accrues_rewards(&env, &keeper);
+        for (recipient, share) in keeper_reward_split(&env, &keeper, amount).iter() {
+            if accrue {
+                credit_keeper_reward(&env, &recipient, &token, share);
+            } else {
+                move_tokens(
+                    &env,
+                    &token,
+                    &env.current_contract_address(),
+                    &recipient,
+                    share as i128,
+                );
+            }
+        }
+    }
+
+    /// Route a keeper's liquidation rewards and order execution fees to a payout address,
+    /// sending `share_bps` of each reward to a second recipient.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper (must authorize)
+    /// * `payout` - Receives the rewards less the share
+    /// * `share_recipient` - Receives the share (e.g. a DAO or bot funder)
+    /// * `share_bps` - Share of every reward, 0 to 10000
+    ///
+    /// # Panics
+    ///
+    /// Panics if `share_bps` is above 10000
+    pub fn set_keeper_payout(
+        env: Env,
+        keeper: Address,
+        payout: Address,
+        share_recipient: Address,
+        share_bps: u32,
+    ) {
+        keeper.require_auth();
+        if share_bps as u128 > BPS_DIVISOR {
+            panic_with_error!(&env, KeeperRewardsError::InvalidKeeperShare);
+        }
+
+        env.storage().persistent().set(
+            &DataKey::KeeperPayout(keeper.clone()),
+            &KeeperPayout {
+                payout: payout.clone(),
+                share_recipient: share_recipient.clone(),
+                share_bps,
+            },
+        );
+        KeeperPayoutSetEvent {
+            keeper,
+            payout,
+            share_recipient,
+            share_bps,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Stop routing a keeper's rewards, paying them to the keeper again.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper (must authorize)
+    pub fn clear_keeper_payout(env: Env, keeper: Address) {
+        keeper.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::KeeperPayout(keeper));
+    }
+
+    /// Get where a keeper's rewards are paid.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper
+    ///
+    /// # Returns
+    ///
+    /// The keeper's KeeperPayout, or None if rewards go to the keeper itself
+    pub fn get_keeper_payout(env: Env, keeper: Address) -> Option<KeeperPayout> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::KeeperPayout(keeper))
+    }
+
+    /// Let a keeper's rewards accrue as claimable balances instead of being transferred
+    /// on every execution, so small rewards are collected in one transfer with
+    /// `claim_keeper_rewards()` (or converted into LP shares with
+    /// `claim_keeper_rewards_as_shares()`). Balances are credited to the recipients of
+    /// the keeper's `KeeperPayout` split, or the keeper itself without one.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper (must authorize)
+    /// * `enabled` - true to accrue rewards, false to have them sent again
+    pub fn set_keeper_reward_accrual(env: Env, keeper: Address, enabled: bool) {
+        keeper.require_auth();
+        let key = DataKey::KeeperAccruesRewards(keeper);
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Check whether a keeper's rewards accrue as claimable balances.
+    ///
+    /// # Arguments
+    ///
+    /// * `keeper` - The keeper
+    ///
+    /// # Returns
+    ///
+    /// true if rewards accrue, false if they are sent on every execution
+    pub fn keeper_accrues_rewards(env: Env, keeper: Address) -> bool {
+        keeper_accrues_rewards(&env, &keeper)
+    }
+
+    /// Get a recipient's claimable keeper rewards in a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The keeper or payout recipient
+    /// * `token` - The pool token (liquidation rewards) or fee token (execution fees)
+    ///
+    /// # Returns
+    ///
+    /// The claimable amount
+    pub fn get_keeper_rewards(env: Env, recipient: Address, token: Address) -> u128 {
+        get_keeper_rewards(&env, &recipient, &token)
+    }
+
+    /// Claim every accrued keeper reward in a token in one transfer.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The keeper or payout recipient (must authorize)
+    /// * `token` - The token to claim
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred
+    ///
+    /// # Panics
+    ///
+    /// Panics if nothing is claimable in the token
+    pub fn claim_keeper_rewards(env: Env, recipient: Address, token: Address) -> u128 {
+        recipient.require_auth();
+        let amount = take_keeper_rewards(&env, &recipient, &token);
+        move_tokens(
+            &env,
+            &token,
+            &env.current_contract_address(),
+            &recipient,
+            amount as i128,
+        );
+
+        KeeperRewardsClaimedEvent {
+            recipient,
+            token,
+            amount,
+            shares: 0,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        amount
+    }
+
+    /// Claim every accrued keeper reward in the pool token and deposit it into the
+    /// LiquidityPool as LP shares for the recipient, subject to the pool's deposit rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The keeper or payout recipient (must authorize, including the
+    ///   pool deposit)
+    ///
+    /// # Returns
+    ///
+    /// The LP shares minted
+    ///
+    /// # Panics
+    ///
+    /// Panics if nothing is claimable in the pool token, or the pool refuses the deposit
+    pub fn claim_keeper_rewards_as_shares(env: Env, recipient: Address) -> i128 {
+        recipient.require_auth();
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let token = config_client.token();
+        let amount = take_keeper_rewards(&env, &recipient, &token);
+        move_tokens(
+            &env,
+            &token,
+            &env.current_contract_address(),
+            &recipient,
+            amount as i128,
+        );
+        let pool_client = liquidity_pool::Client::new(&env, &config_client.liquidity_pool());
+        let shares = pool_client.deposit(&recipient, &(amount as i128));
+
+        KeeperRewardsClaimedEvent {
+            recipient,
+            token,
+            amount,
+            shares,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        shares
+    }
+}
+
+#[cfg(test)]
+mod test;
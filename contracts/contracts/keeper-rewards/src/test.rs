@@ -0,0 +1,155 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Events as _},
+    Address, Env, Event as _,
+};
+
+struct TestEnv<'a> {
+    rewards_client: KeeperRewardsClient<'a>,
+    token_client: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+    position_manager: Address,
+    order_manager: Address,
+    keeper: Address,
+    config_id: Address,
+}
+
+/// KeeperRewards paid by stand-in protocol contracts
+fn setup_test_environment(env: &Env) -> TestEnv<'_> {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let keeper = Address::generate(env);
+    let position_manager = Address::generate(env);
+    let order_manager = Address::generate(env);
+
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::Client::new(env, &token_contract.address());
+    let token_admin = token::StellarAssetClient::new(env, &token_contract.address());
+
+    let config_id = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(env, &config_id);
+    config_client.set_token(&admin, &token_client.address);
+    config_client.set_position_manager(&admin, &position_manager);
+    config_client.set_order_manager(&admin, &order_manager);
+
+    let rewards_id = env.register(KeeperRewards, ());
+    let rewards_client = KeeperRewardsClient::new(env, &rewards_id);
+    rewards_client.initialize(&admin, &config_id);
+
+    TestEnv {
+        rewards_client,
+        token_client,
+        token_admin,
+        position_manager,
+        order_manager,
+        keeper,
+        config_id,
+    }
+}
+
+/// Pay a reward the way the protocol contracts do: transfer it in, then report it
+fn pay(t: &TestEnv, reporter: &Address, amount: u128) {
+    t.token_admin
+        .mint(&t.rewards_client.address, &(amount as i128));
+    t.rewards_client
+        .pay_reward(reporter, &t.keeper, &t.token_client.address, &amount);
+}
+
+#[test]
+fn test_keeper_payout_splits_rewards() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+    let payout = Address::generate(&env);
+    let dao = Address::generate(&env);
+
+    // Without a payout the keeper is paid itself
+    pay(&t, &t.position_manager, 1_000_000);
+    assert_eq!(t.token_client.balance(&t.keeper), 1_000_000);
+
+    assert!(t
+        .rewards_client
+        .try_set_keeper_payout(&t.keeper, &payout, &dao, &10_001u32)
+        .is_err());
+    t.rewards_client
+        .set_keeper_payout(&t.keeper, &payout, &dao, &2000u32);
+    assert_eq!(
+        t.rewards_client.get_keeper_payout(&t.keeper),
+        Some(KeeperPayout {
+            payout: payout.clone(),
+            share_recipient: dao.clone(),
+            share_bps: 2000,
+        })
+    );
+
+    // 80% to the payout address, 20% to the DAO
+    pay(&t, &t.order_manager, 1_000_000);
+    assert_eq!(t.token_client.balance(&payout), 800_000);
+    assert_eq!(t.token_client.balance(&dao), 200_000);
+    assert_eq!(t.token_client.balance(&t.keeper), 1_000_000);
+
+    t.rewards_client.clear_keeper_payout(&t.keeper);
+    assert_eq!(t.rewards_client.get_keeper_payout(&t.keeper), None);
+}
+
+#[test]
+fn test_keeper_rewards_accrue_and_claim() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+    let token = t.token_client.address.clone();
+
+    t.rewards_client.set_keeper_reward_accrual(&t.keeper, &true);
+    assert!(t.rewards_client.keeper_accrues_rewards(&t.keeper));
+
+    // Rewards stay here as a claimable balance
+    pay(&t, &t.order_manager, 1_000_000);
+    pay(&t, &t.position_manager, 30_000_000);
+    assert_eq!(t.token_client.balance(&t.keeper), 0);
+    assert_eq!(
+        t.rewards_client.get_keeper_rewards(&t.keeper, &token),
+        31_000_000
+    );
+
+    assert_eq!(
+        t.rewards_client.claim_keeper_rewards(&t.keeper, &token),
+        31_000_000
+    );
+    let events = env.events().all();
+    // The claim is the last event; the transfer before it took the previous number
+    let seq = config_manager::Client::new(&env, &t.config_id).get_event_seq() - 1;
+    let moved = TokenMovedEvent {
+        token: token.clone(),
+        from: t.rewards_client.address.clone(),
+        to: t.keeper.clone(),
+        amount: 31_000_000,
+        seq,
+    };
+    assert!(events.contains((
+        t.rewards_client.address.clone(),
+        moved.topics(&env),
+        moved.data(&env)
+    )));
+    assert_eq!(t.token_client.balance(&t.keeper), 31_000_000);
+    assert!(t
+        .rewards_client
+        .try_claim_keeper_rewards(&t.keeper, &token)
+        .is_err());
+
+    t.rewards_client
+        .set_keeper_reward_accrual(&t.keeper, &false);
+    assert!(!t.rewards_client.keeper_accrues_rewards(&t.keeper));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #802)")]
+fn test_pay_reward_unauthorized_reporter() {
+    let env = Env::default();
+    let t = setup_test_environment(&env);
+
+    pay(&t, &t.keeper.clone(), 1_000_000);
+}
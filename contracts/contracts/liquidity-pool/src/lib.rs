@@ -11,27 +11,97 @@
 //! - **Position Collateral**: Tracks collateral deposited by traders for each position.
 //! - **Liquidity Reservation**: Reserves liquidity when positions open, releases on close.
 //! - **PnL Settlement**: Pays profitable traders from pool reserves.
+//! - **Hedging**: An admin-set hedger may borrow a capped share of the pool to hold
+//!   offsetting spot positions, repaying and reporting their mark-to-market value.
+//! - **Compliance Hook**: When enabled in ConfigManager, refused accounts cannot deposit
+//!   or withdraw.
+//! - **Fee Mode**: Trading fees reported by the PositionManager (borrowing and early-close
+//!   fees) accrue per share with a reward-debt accumulator. Each LP chooses whether theirs
+//!   auto-compound (default: reinvested as shares whenever the LP's balance changes or
+//!   `compound_fees()` is called) or accrue as a token balance withdrawn with `claim_fees()`
+//! - **Frontend Fees**: The PositionManager may route part of the fees on a position to
+//!   the frontend it was opened through. They are held apart from pool value until the
+//!   frontend withdraws them with `claim_frontend_fees()`.
+//! - **Order Escrow**: The PositionManager may park resting limit-order collateral in the
+//!   pool. It is held as the PositionManager's LP shares, split into per-order escrow
+//!   units, and redeemed at its current value when the order executes or is cancelled.
+//! - **Deferred Payouts**: A profit larger than the unreserved liquidity is paid in part
+//!   and the rest queued as a claim. Claims are settled oldest first by `settle_claims()`
+//!   as liquidity frees up, and are excluded from pool value until paid.
+//! - **Pool Phases**: A fresh pool may be launched in `Bootstrapping`, taking deposits
+//!   only until the admin activates it once the seed liquidity target is met. In
+//!   `WindDown` no new positions or deposits are accepted, and LPs may withdraw once
+//!   every position has been closed. Anyone may then move the pool to `Terminal`, where
+//!   `emergency_withdraw()` redeems shares for a pro-rata slice of the remaining tokens
+//!   without consulting ConfigManager, so LPs keep an exit even if it is paused or broken.
+//! - **Unrealized PnL Haircut**: The PositionManager reports traders' aggregate unrealized
+//!   PnL against the pool. While that profit exceeds a configurable share of pool value,
+//!   withdrawals are valued net of a configurable part of it, so LPs exiting early don't
+//!   leave the losses to those who stay.
+//! - **Pause Withdrawal Throttle**: While the protocol is globally paused, withdrawals are
+//!   capped to a configurable share of pool value per interval so a bank run cannot drain
+//!   the buffer owed to winning traders. Deposits stay open.
+//! - **Token Migration**: The admin can move the pool to a new backing token (e.g. from
+//!   the testnet faucet token to USDC) while the protocol is globally paused: configure
+//!   the new token, migrator contract and rate, snapshot the pool, then swap the whole
+//!   balance through the migrator, which repoints ConfigManager's token in the same
+//!   call. OrderManager must first hold no order collateral or execution fees in the
+//!   old token: resting orders are cancelled, filled or (limit orders) parked. LP shares
+//!   carry over unchanged; each step has a dry-run view and an event.
+//! - **Drawdown Circuit Breaker**: When enabled, trader payouts and hedge reports check
+//!   the share price against its peak over a rolling window. A drop beyond the limit
+//!   globally pauses the protocol through ConfigManager, stopping new positions and
+//!   throttling withdrawals while closes and liquidations continue. The admin re-arms it
+//!   with `reset_drawdown_breaker()` and lifts the pause in ConfigManager.
 //!
 //! ## Share Calculation
 //! - First deposit: shares = amount (1:1 ratio)
 //! - Subsequent deposits: shares = (deposit * total_shares) / pool_value_before_deposit
+//!
 //! This ensures existing LPs maintain their proportional ownership.
 //!
+//! Share math goes through `math::mul_div` with a 256-bit intermediate product, so it
+//! cannot overflow at any TVL whose share and token amounts fit an i128. Shares minted
+//! and tokens withdrawn both round down, in favor of the pool.
+//! Shares are not a transferable token, but expose `name()`, `symbol()`, `decimals()` and
+//! `share_price()` so portfolio trackers can value LP holdings. Keeping them
+//! non-transferable means LP exposure can only leave through `withdraw()`, with its
+//! reserve checks and paused throttle, and cannot be handed off mid-crisis. Any future
+//! share transfer must be refused while utilization is stressed or payout claims are
+//! outstanding.
+//! Pool value is the token balance plus the hedger's last reported holdings value, minus
+//! accrued fees not yet compounded or claimed.
+//!
+//! A share price checkpoint is recorded at most once per configurable interval (on deposits,
+//! withdrawals or `checkpoint_share_price()`) in a ring buffer of the last 90, read with
+//! `get_share_price_history()` so LPs can compute returns without an indexer.
+//!
 //! ## Safety Mechanisms
 //! - **Utilization Ratio**: Limits how much liquidity can be reserved for positions
 //! - **Minimum Reserve Ratio**: Ensures minimum liquidity always available for withdrawals
 //! - **Position Manager Authorization**: Only the authorized PositionManager can modify positions
 //!
 //! ## Usage
-//! - LPs call `deposit()` and `withdraw()` directly
+//! - LPs call `deposit()` and `withdraw()` directly, or `deposit_many()` / `withdraw_many()`
+//!   to move funds for several accounts in one transaction
 //! - PositionManager calls collateral and reservation functions when managing positions
 
-use soroban_sdk::{contract, contractimpl, contracttype, log, token, Address, Env};
+use math::{mul_div, perp, Rounding};
+use soroban_sdk::{
+    contract, contractclient, contractevent, contractimpl, contracttype, panic_with_error, token,
+    Address, Env, String, Symbol, Vec,
+};
+
+pub use errors::PoolError;
 
 mod config_manager {
     soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
 }
 
+mod campaign {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/campaign.wasm");
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -45,11 +115,388 @@ pub enum DataKey {
     AuthorizedPositionManager,
     // Position collateral tracking
     PositionCollateral(u64),
+    TotalPositionCollateral, // Sum of PositionCollateral over open positions
+    // Hedger borrowing
+    Hedge,
+    // Withdrawal haircut for traders' unrealized profit
+    PnlHaircut,
+    // Withdrawals made during the current paused window
+    PausedWithdrawalWindow,
+    // Fee accrual
+    AccFeePerShare,        // Fees per share, scaled by FEE_PER_SHARE_PRECISION
+    UnclaimedFees,         // Accrued fees not yet compounded or claimed, excluded from pool value
+    LpFeeAccount(Address), // LP -> LpFeeAccount
+    // Frontend fee share
+    FrontendFees(Address), // Frontend -> fees accrued and not yet claimed
+    TotalFrontendFees,     // Sum of FrontendFees, excluded from pool value
+    // Parked order escrow
+    OrderEscrowUnits(u64), // Order ID -> units of the OrderManager's shares
+    TotalOrderEscrowUnits,
+    // Deferred trader payouts
+    PayoutClaim(u64),  // Claim ID -> PayoutClaim
+    NextClaimId,       // ID the next claim gets (IDs start at 1)
+    ClaimHead,         // Oldest unsettled claim ID
+    OutstandingClaims, // Sum of amounts still owed on claims
+    // Lifecycle
+    Phase,      // PoolPhase (Active if unset)
+    SeedTarget, // Deposits required before a bootstrapping pool can activate
+    // Share price history
+    CheckpointInterval,        // Minimum seconds between share price checkpoints
+    CheckpointCount,           // Number of checkpoints ever recorded
+    LastCheckpointAt,          // Timestamp of the latest checkpoint
+    SharePriceCheckpoint(u32), // Slot -> SharePriceCheckpoint, slot = seq % SHARE_PRICE_HISTORY_SIZE
+    // Backing token migration
+    TokenMigration,         // TokenMigration in progress (absent when none is configured)
+    TokenMigrationSnapshot, // TokenMigrationSnapshot taken for it
+    // Drawdown circuit breaker
+    DrawdownBreaker,
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_manager(env))
+}
+
+/// Profit owed to a trader that the pool could not pay when it was realized
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutClaim {
+    pub claim_id: u64,
+    pub trader: Address,
+    pub amount: i128,    // Still owed
+    pub created_at: u64, // Timestamp the payout was deferred
+}
+
+/// Lifecycle phase of the pool
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolPhase {
+    Bootstrapping, // Deposits only; no trading or withdrawals
+    Active,        // Normal operation
+    WindDown,      // No new positions or deposits; withdrawals once open interest is zero
+    Terminal,      // Wound down for good; LPs may also redeem pro-rata with emergency_withdraw
+}
+
+/// Pool liquidity lent to the hedger
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HedgeState {
+    pub hedger: Option<Address>, // Admin-set strategy address (None = hedging disabled)
+    pub max_borrow_bps: u32,     // Cap on outstanding principal as bps of pool value
+    pub borrowed: u128,          // Outstanding principal
+    pub mark_value: i128,        // Value of the hedger's holdings, counted in pool value
+    pub reported_at: u64,        // Timestamp of the last mark-to-market report
+}
+
+/// Backing token migration configured by the admin
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenMigration {
+    pub new_token: Address,
+    pub migrator: Address, // Contract implementing TokenMigrator
+    pub rate: i128,        // New token units per old token unit, scaled by 1e7
+}
+
+/// Pool state recorded before its backing token is swapped
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenMigrationSnapshot {
+    pub taken_at: u64,
+    pub balance: i128,             // Old tokens held by the pool
+    pub total_shares: i128,        // LP shares, carried over unchanged
+    pub position_collateral: u128, // Collateral held for open positions
+    pub reserved_liquidity: u128,  // Liquidity reserved for open positions
+}
+
+/// Dry run of the configured token migration, from `preview_token_migration()`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenMigrationPreview {
+    pub old_token: Address,
+    pub new_token: Address,
+    pub rate: i128,
+    pub balance: i128,             // Old tokens that would be swapped
+    pub expected_balance: i128,    // New tokens the migrator must return at least
+    pub total_shares: i128,        // LP shares, carried over unchanged
+    pub share_price: i128,         // Share price in new tokens after the swap (1e7 = 1.0)
+    pub position_collateral: u128, // Collateral held for open positions
+    pub order_escrow: u128,        // Collateral and fees OrderManager holds; must be 0 to migrate
+    pub globally_paused: bool,     // Snapshot and swap require the global pause
+    pub rate_allowed: bool,        // False if a non 1:1 rate meets token amounts owed out
+}
+
+/// Interface of the contract that swaps the pool's backing token during a migration.
+/// The pool transfers its whole old-token balance to the migrator, then calls `swap`,
+/// which must send at least `amount * rate / 1e7` new tokens to `recipient`.
+#[contractclient(name = "TokenMigratorClient")]
+pub trait TokenMigrator {
+    fn swap(
+        env: Env,
+        from_token: Address,
+        to_token: Address,
+        amount: i128,
+        rate: i128,
+        recipient: Address,
+    ) -> i128;
+}
+
+/// The part of OrderManager's interface the pool reads before a token migration
+#[contractclient(name = "OrderEscrowClient")]
+pub trait OrderEscrow {
+    fn get_collateral_escrow(env: Env) -> u128;
+    fn get_fee_escrow(env: Env, token: Address) -> u128;
+}
+
+/// Traders' last reported unrealized PnL and the haircut it triggers on withdrawals
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PnlHaircut {
+    pub threshold_bps: u32, // Haircut applies while traders' profit exceeds this share of pool value
+    pub haircut_bps: u32,   // Share of traders' profit deducted from pool value on withdrawals
+    pub traders_pnl: i128,  // Aggregate unrealized PnL of open positions (positive = pool owes)
+    pub reported_at: u64,   // Timestamp of the last report (0 = never reported)
+}
+
+/// Share price drawdown limit and the rolling window it is measured over
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawdownBreaker {
+    pub max_drawdown_bps: u32, // Drop from the window's peak share price that trips it (0 = disabled)
+    pub window: u64,           // Seconds per window; the peak covers the current and previous one
+    pub window_start: u64,     // Timestamp the current window opened
+    pub peak_share_price: i128, // Highest share price seen in the current window
+    pub previous_peak_share_price: i128, // Highest share price seen in the previous window
+    pub tripped_at: u64,       // Timestamp it last tripped (0 = never or since reset)
+}
+
+/// Pool assets against everything it may owe, from `get_solvency()`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solvency {
+    pub pool_balance: i128, // Tokens held, less in-kind fees and payout claims owed
+    pub hedge_value: i128,  // Marked value of the hedger's holdings
+    pub insurance_balance: i128, // Pool tokens held by the insurance fund (0 if unset)
+    pub lp_claims: i128,    // LP principal (total deposits)
+    pub trader_collateral: i128, // Collateral held for open positions
+    pub trader_profit_bound: i128, // Reserved liquidity: the most open positions can win
+    pub assets: i128,       // pool_balance + hedge_value + insurance_balance
+    pub liabilities: i128,  // lp_claims + trader_collateral + trader_profit_bound
+    pub ratio_bps: i128,    // assets / liabilities (10000 = exactly covered)
+}
+
+/// Share price recorded at a point in time
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharePriceCheckpoint {
+    pub timestamp: u64,
+    pub share_price: i128, // Pool value per share with 7 decimals
+    pub pool_value: i128,
+    pub total_shares: i128,
+}
+
+/// An LP's fee preference and reward-debt checkpoint
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LpFeeAccount {
+    pub claim_in_kind: bool, // true = fees accrue as a claimable balance, false = auto-compound
+    pub reward_debt: i128,   // shares * acc_fee_per_share at the last checkpoint
+    pub pending: i128,       // Fees accrued but not yet compounded or claimed
+}
+
+/// Withdrawals made within one throttle window while the protocol is paused
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PausedWithdrawalWindow {
+    pub started_at: u64,  // Timestamp the window opened
+    pub pool_value: i128, // Pool value when the window opened; the cap is a share of it
+    pub withdrawn: i128,  // Tokens withdrawn so far in this window
+}
+
+#[contractevent]
+pub struct PayoutDeferredEvent {
+    #[topic]
+    pub trader: Address,
+    pub claim_id: u64,
+    pub paid: i128,     // Part of the profit paid immediately
+    pub deferred: i128, // Part queued as the claim
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct ClaimSettledEvent {
+    #[topic]
+    pub trader: Address,
+    pub claim_id: u64,
+    pub amount: i128,
+    pub remaining: i128, // Still owed on the claim (0 once settled in full)
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct PhaseChangedEvent {
+    pub from: PoolPhase,
+    pub to: PoolPhase,
+    pub total_deposits: i128,
+    pub seq: u64,
+}
+
+/// Published for every pro-rata redemption made in the terminal phase
+#[contractevent]
+pub struct EmergencyWithdrawEvent {
+    #[topic]
+    pub user: Address,
+    pub shares: i128,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+/// Published when an LP withdraws their entire share balance with `withdraw_all()`
+#[contractevent]
+pub struct WithdrewAllEvent {
+    #[topic]
+    pub user: Address,
+    pub shares: i128, // Shares burned, compounded fees included
+    pub amount: i128, // Tokens paid out; the rounding remainder stays in the pool
+    pub seq: u64,
+}
+
+/// Published for every withdrawal valued net of traders' unrealized profit
+#[contractevent]
+pub struct WithdrawalHaircutEvent {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,  // Tokens paid out
+    pub haircut: i128, // Tokens the withdrawal would have paid on top without the haircut
+    pub seq: u64,
+}
+
+/// Published for every withdrawal made while the protocol is paused
+#[contractevent]
+pub struct PausedWithdrawalEvent {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub remaining: i128, // Allowance left in the current window
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FeesAccruedEvent {
+    pub amount: i128,      // Fees reported by the PositionManager
+    pub distributed: i128, // Part credited per share (the rounding remainder stays in pool value)
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FrontendFeesAccruedEvent {
+    #[topic]
+    pub frontend: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FrontendFeesClaimedEvent {
+    #[topic]
+    pub frontend: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FeesCompoundedEvent {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub shares: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FeeModeUpdatedEvent {
+    #[topic]
+    pub user: Address,
+    pub claim_in_kind: bool,
+    pub seq: u64,
+}
+
+/// Published for every token transfer the pool makes (see `move_tokens`)
+#[contractevent]
+pub struct TokenMovedEvent {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FeesClaimedEvent {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct TokenMigrationConfiguredEvent {
+    pub new_token: Address,
+    pub migrator: Address,
+    pub rate: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct TokenMigrationSnapshotEvent {
+    pub balance: i128,
+    pub total_shares: i128,
+    pub position_collateral: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct TokenMigratedEvent {
+    pub old_token: Address,
+    pub new_token: Address,
+    pub amount_in: i128,  // Old tokens swapped
+    pub amount_out: i128, // New tokens received
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct DrawdownBreakerTrippedEvent {
+    pub share_price: i128,
+    pub peak_share_price: i128,
+    pub drawdown_bps: i128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct TokenMigrationCancelledEvent {
+    pub new_token: Address,
+    pub seq: u64,
+}
+
+/// Published before refusing an interaction; like any event in a failed invocation it
+/// is only visible in the transaction's diagnostic events
+#[contractevent]
+pub struct ComplianceRefusedEvent {
+    pub account: Address,
+    pub action: Symbol,
+    pub seq: u64,
 }
 
 #[contract]
 pub struct LiquidityPool;
 
+const MAX_BATCH_SIZE: u32 = 20; // Accounts per deposit_many / withdraw_many call
+const FEE_PER_SHARE_PRECISION: i128 = 1_000_000_000_000;
+const SHARE_PRICE_HISTORY_SIZE: u32 = 90; // Checkpoints retained (a quarter at the default interval)
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 86_400; // One checkpoint a day
+const DEFAULT_HAIRCUT_THRESHOLD_BPS: u32 = 500; // Traders up 5% of pool value
+const DEFAULT_HAIRCUT_BPS: u32 = 10000; // Their full profit
+const MIGRATION_RATE_PRECISION: i128 = 10_000_000; // Token migration rate of 1:1
+const DEFAULT_DRAWDOWN_WINDOW: u64 = 3_600; // Drawdowns measured over one to two hours
+
 // Helper functions for storage access
 fn get_config_manager(e: &Env) -> Address {
     e.storage().instance().get(&DataKey::ConfigManager).unwrap()
@@ -59,6 +506,21 @@ fn put_config_manager(e: &Env, address: &Address) {
     e.storage().instance().set(&DataKey::ConfigManager, address);
 }
 
+/// Refuse the interaction if ConfigManager's compliance hook does not allow the account
+fn require_compliant(e: &Env, account: &Address, action: &str) {
+    let config_manager = get_config_manager(e);
+    let config_client = crate::config_manager::Client::new(e, &config_manager);
+    if !config_client.is_account_allowed(account) {
+        ComplianceRefusedEvent {
+            account: account.clone(),
+            action: Symbol::new(e, action),
+            seq: next_event_seq(e),
+        }
+        .publish(e);
+        panic_with_error!(e, PoolError::AccountNotAllowed);
+    }
+}
+
 fn get_token(e: &Env) -> Address {
     e.storage().instance().get(&DataKey::Token).unwrap()
 }
@@ -67,11 +529,193 @@ fn put_token(e: &Env, token: Address) {
     e.storage().instance().set(&DataKey::Token, &token);
 }
 
+/// Move `amount` of `token` from one address to another.
+///
+/// Every token transfer in this contract goes through here so fund movements have a
+/// single path to audit: negative amounts and transfers back to the sender are refused,
+/// zero amounts are skipped, and each transfer publishes a `TokenMovedEvent`.
+fn move_tokens(e: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(e, PoolError::InvalidTokenTransfer);
+    }
+    if from == to {
+        panic_with_error!(e, PoolError::InvalidTokenTransfer);
+    }
+    if amount == 0 {
+        return;
+    }
+    token::Client::new(e, token).transfer(from, to, &amount);
+    TokenMovedEvent {
+        token: token.clone(),
+        from: from.clone(),
+        to: to.clone(),
+        amount,
+        seq: next_event_seq(e),
+    }
+    .publish(e);
+}
+
 fn get_balance(e: &Env) -> i128 {
     let token = get_token(e);
     token::Client::new(e, &token).balance(&e.current_contract_address())
 }
 
+/// Accrued fees not yet compounded or claimed. Per-LP rounding can leave the sum of
+/// pending balances a few units apart from it, so decrements are floored at zero.
+fn get_unclaimed_fees(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::UnclaimedFees)
+        .unwrap_or(0)
+}
+
+/// Tokens held, less fees set aside for LPs and frontends claiming them in kind and
+/// profits owed on payout claims
+fn get_liquid_balance(e: &Env) -> i128 {
+    get_balance(e) - get_unclaimed_fees(e) - get_total_frontend_fees(e) - get_outstanding_claims(e)
+}
+
+fn get_total_frontend_fees(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::TotalFrontendFees)
+        .unwrap_or(0)
+}
+
+fn get_frontend_fees(e: &Env, frontend: &Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::FrontendFees(frontend.clone()))
+        .unwrap_or(0)
+}
+
+fn get_outstanding_claims(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::OutstandingClaims)
+        .unwrap_or(0)
+}
+
+fn get_claim_head(e: &Env) -> u64 {
+    e.storage().instance().get(&DataKey::ClaimHead).unwrap_or(1)
+}
+
+fn get_next_claim_id(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::NextClaimId)
+        .unwrap_or(1)
+}
+
+fn get_payout_claim(e: &Env, claim_id: u64) -> Option<PayoutClaim> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::PayoutClaim(claim_id))
+}
+
+/// Queue `amount` of profit owed to `trader` behind every earlier claim
+fn defer_payout(e: &Env, trader: &Address, amount: i128) -> u64 {
+    let claim_id = get_next_claim_id(e);
+    let claim = PayoutClaim {
+        claim_id,
+        trader: trader.clone(),
+        amount,
+        created_at: e.ledger().timestamp(),
+    };
+    e.storage()
+        .persistent()
+        .set(&DataKey::PayoutClaim(claim_id), &claim);
+    e.storage()
+        .instance()
+        .set(&DataKey::NextClaimId, &(claim_id + 1));
+    e.storage().instance().set(
+        &DataKey::OutstandingClaims,
+        &(get_outstanding_claims(e) + amount),
+    );
+    claim_id
+}
+
+fn get_acc_fee_per_share(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::AccFeePerShare)
+        .unwrap_or(0)
+}
+
+/// An LP's fee account; LPs that never had one hold shares from before any fee accrued
+fn get_lp_fee_account(e: &Env, user: &Address) -> LpFeeAccount {
+    e.storage()
+        .persistent()
+        .get(&DataKey::LpFeeAccount(user.clone()))
+        .unwrap_or(LpFeeAccount {
+            claim_in_kind: false,
+            reward_debt: 0,
+            pending: 0,
+        })
+}
+
+fn put_lp_fee_account(e: &Env, user: &Address, account: &LpFeeAccount) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::LpFeeAccount(user.clone()), account);
+}
+
+/// Fees earned by `shares` since the accumulator started
+fn accumulated_fees(e: &Env, shares: i128) -> i128 {
+    mul_div(
+        shares,
+        get_acc_fee_per_share(e),
+        FEE_PER_SHARE_PRECISION,
+        Rounding::Floor,
+    )
+}
+
+/// Move fees earned since the last checkpoint into `pending`
+fn checkpoint_fees(e: &Env, account: &mut LpFeeAccount, shares: i128) {
+    let accumulated = accumulated_fees(e, shares);
+    account.pending += accumulated - account.reward_debt;
+    account.reward_debt = accumulated;
+}
+
+/// Change `user`'s share balance by `delta` (0 to just settle fees). Accrued fees are
+/// checkpointed first; for auto-compounding LPs they are reinvested as shares at the
+/// current share price.
+fn update_shares(e: &Env, user: &Address, delta: i128) {
+    let mut shares = get_shares(e, user);
+    let mut total_shares = get_total_shares(e);
+    let mut account = get_lp_fee_account(e, user);
+    checkpoint_fees(e, &mut account, shares);
+
+    if !account.claim_in_kind && account.pending > 0 && total_shares > 0 {
+        let amount = account.pending;
+        let compounded = mul_div(amount, total_shares, get_pool_value(e), Rounding::Floor);
+        e.storage().instance().set(
+            &DataKey::UnclaimedFees,
+            &(get_unclaimed_fees(e) - amount).max(0),
+        );
+        account.pending = 0;
+        shares += compounded;
+        total_shares += compounded;
+
+        FeesCompoundedEvent {
+            user: user.clone(),
+            amount,
+            shares: compounded,
+            seq: next_event_seq(e),
+        }
+        .publish(e);
+    }
+
+    let new_shares = shares + delta;
+    if new_shares < 0 {
+        panic_with_error!(e, PoolError::InsufficientShares);
+    }
+    account.reward_debt = accumulated_fees(e, new_shares);
+    put_lp_fee_account(e, user, &account);
+    put_shares(e, user, new_shares);
+    put_total_shares(e, total_shares + delta);
+}
+
 fn get_total_shares(e: &Env) -> i128 {
     e.storage()
         .instance()
@@ -108,20 +752,11 @@ fn put_shares(e: &Env, user: &Address, amount: i128) {
 }
 
 fn mint_shares(e: &Env, to: &Address, amount: i128) {
-    let current_shares = get_shares(e, to);
-    let total = get_total_shares(e);
-    put_shares(e, to, current_shares + amount);
-    put_total_shares(e, total + amount);
+    update_shares(e, to, amount);
 }
 
 fn burn_shares(e: &Env, from: &Address, amount: i128) {
-    let current_shares = get_shares(e, from);
-    if current_shares < amount {
-        panic!("insufficient shares");
-    }
-    let total = get_total_shares(e);
-    put_shares(e, from, current_shares - amount);
-    put_total_shares(e, total - amount);
+    update_shares(e, from, -amount);
 }
 
 fn get_reserved_liquidity(e: &Env) -> u128 {
@@ -153,272 +788,1977 @@ fn require_position_manager(e: &Env, caller: &Address) {
     caller.require_auth();
     if let Some(authorized) = get_authorized_position_manager(e) {
         if caller != &authorized {
-            panic!("unauthorized: not position manager");
+            panic_with_error!(e, PoolError::NotPositionManager);
         }
     } else {
-        panic!("position manager not set");
+        panic_with_error!(e, PoolError::PositionManagerNotSet);
     }
 }
 
-fn get_position_collateral(e: &Env, position_id: u64) -> u128 {
-    e.storage()
-        .persistent()
-        .get(&DataKey::PositionCollateral(position_id))
-        .unwrap_or(0)
+/// Require the caller to be the OrderManager registered in ConfigManager
+fn require_order_manager(e: &Env, caller: &Address) {
+    caller.require_auth();
+    let config_client = crate::config_manager::Client::new(e, &get_config_manager(e));
+    if caller != &config_client.order_manager() {
+        panic_with_error!(e, PoolError::NotOrderManager);
+    }
 }
 
-fn put_position_collateral(e: &Env, position_id: u64, amount: u128) {
-    e.storage()
-        .persistent()
-        .set(&DataKey::PositionCollateral(position_id), &amount);
+fn require_admin(e: &Env, admin: &Address) {
+    admin.require_auth();
+    let config_client = crate::config_manager::Client::new(e, &get_config_manager(e));
+    if admin != &config_client.admin() {
+        panic_with_error!(e, PoolError::NotAdmin);
+    }
 }
 
-fn delete_position_collateral(e: &Env, position_id: u64) {
+fn get_token_migration(e: &Env) -> TokenMigration {
     e.storage()
-        .persistent()
-        .remove(&DataKey::PositionCollateral(position_id));
+        .instance()
+        .get(&DataKey::TokenMigration)
+        .unwrap_or_else(|| panic_with_error!(e, PoolError::NoTokenMigration))
 }
 
-#[contractimpl]
-impl LiquidityPool {
-    /// Initialize the liquidity pool with config manager and token addresses.
-    ///
-    /// # Arguments
-    ///
-    /// * `admin` - The administrator address (must authorize)
-    /// * `config_manager` - The Config Manager contract address
-    /// * `token` - The token contract address for this pool
-    ///
-    /// # Panics
-    ///
-    /// Panics if the pool is already initialized or admin doesn't authorize
-    pub fn initialize(env: Env, admin: Address, config_manager: Address, token: Address) {
-        if env.storage().instance().has(&DataKey::ConfigManager) {
-            panic!("already initialized");
+/// Order collateral and execution fees OrderManager holds in the pool token outside the
+/// pool (0 before an OrderManager is registered). Parked escrow is held as pool shares
+/// and needs no swap.
+fn get_order_manager_escrow(e: &Env) -> u128 {
+    let config_client = crate::config_manager::Client::new(e, &get_config_manager(e));
+    match config_client.try_order_manager() {
+        Ok(Ok(order_manager)) => {
+            let escrow_client = OrderEscrowClient::new(e, &order_manager);
+            escrow_client.get_collateral_escrow() + escrow_client.get_fee_escrow(&get_token(e))
         }
+        _ => 0,
+    }
+}
 
-        // Require admin to authorize initialization
-        admin.require_auth();
-
-        put_config_manager(&env, &config_manager);
-        put_token(&env, token);
-        put_total_shares(&env, 0);
-        put_total_deposits(&env, 0);
+/// Refuse a migration step while OrderManager escrows collateral or fees in the old
+/// token, which would be stranded once ConfigManager points at the new one
+fn require_no_order_escrow(e: &Env) {
+    if get_order_manager_escrow(e) > 0 {
+        panic_with_error!(e, PoolError::OrderEscrowHeld);
     }
+}
 
-    /// Get the Config Manager address.
-    ///
-    /// # Returns
-    ///
-    /// The Config Manager contract address
-    pub fn config_manager(env: Env) -> Address {
-        get_config_manager(&env)
+fn require_globally_paused(e: &Env) {
+    let config_client = crate::config_manager::Client::new(e, &get_config_manager(e));
+    if !config_client.is_globally_paused() {
+        panic_with_error!(e, PoolError::GlobalPauseRequired);
     }
+}
 
-    /// Get the token address for this pool.
-    ///
-    /// # Returns
-    ///
-    /// The token contract address
-    pub fn token(env: Env) -> Address {
-        get_token(&env)
+/// Whether a migration at `rate` can keep every recorded amount valid. Position
+/// collateral, reserved liquidity, fees and claims owed out and hedge principal are
+/// recorded in token units, so only a 1:1 swap may run while any is outstanding.
+fn migration_rate_allowed(e: &Env, rate: i128) -> bool {
+    if rate == MIGRATION_RATE_PRECISION {
+        return true;
     }
+    let hedge = get_hedge_state(e);
+    get_total_position_collateral(e) == 0
+        && get_reserved_liquidity(e) == 0
+        && get_unclaimed_fees(e) == 0
+        && get_total_frontend_fees(e) == 0
+        && get_outstanding_claims(e) == 0
+        && hedge.borrowed == 0
+        && hedge.mark_value == 0
+}
 
-    /// Deposit tokens into the liquidity pool and receive LP shares.
-    ///
-    /// # Arguments
-    ///
-    /// * `user` - The address of the depositor
-    /// * `amount` - The amount of tokens to deposit
-    ///
-    /// # Returns
-    ///
-    /// The number of LP shares minted to the user
-    ///
-    /// # Panics
-    ///
-    /// Panics if amount is not positive
-    pub fn deposit(env: Env, user: Address, amount: i128) -> i128 {
-        // Verify user authorization
-        user.require_auth();
+fn get_phase(e: &Env) -> PoolPhase {
+    e.storage()
+        .instance()
+        .get(&DataKey::Phase)
+        .unwrap_or(PoolPhase::Active)
+}
 
-        // Validate amount is positive
-        if amount <= 0 {
-            panic!("amount must be positive");
-        }
+fn get_seed_target(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::SeedTarget)
+        .unwrap_or(0)
+}
 
-        // Get token and current pool state
-        let token = get_token(&env);
-        let total_shares = get_total_shares(&env);
-        let total_deposits = get_total_deposits(&env);
+/// Move the pool from `from` to `to`, refusing if it is in any other phase
+fn transition_phase(e: &Env, from: PoolPhase, to: PoolPhase) {
+    if get_phase(e) != from {
+        panic_with_error!(e, PoolError::InvalidPhaseTransition);
+    }
+    e.storage().instance().set(&DataKey::Phase, &to);
+    PhaseChangedEvent {
+        from,
+        to,
+        total_deposits: get_total_deposits(e),
+        seq: next_event_seq(e),
+    }
+    .publish(e);
+}
 
-        // Transfer tokens from user to contract first
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&user, &env.current_contract_address(), &amount);
+/// Refuse new exposure (positions and parked orders) unless the pool is active
+fn require_trading_phase(e: &Env) {
+    if get_phase(e) != PoolPhase::Active {
+        panic_with_error!(e, PoolError::PoolNotTrading);
+    }
+}
 
-        // Get actual balance after transfer (protects against PnL changes)
-        let balance = get_balance(&env);
+fn get_hedge_state(e: &Env) -> HedgeState {
+    e.storage()
+        .instance()
+        .get(&DataKey::Hedge)
+        .unwrap_or(HedgeState {
+            hedger: None,
+            max_borrow_bps: 0,
+            borrowed: 0,
+            mark_value: 0,
+            reported_at: 0,
+        })
+}
 
-        // Calculate shares to mint using pro-rata formula to maintain fair LP ownership
-        // First deposit: 1:1 ratio (no existing shares to dilute)
-        // Subsequent deposits: new_shares = (deposit * total_shares) / pool_value_before
-        // This ensures new depositors get shares proportional to their contribution
-        // Example: If pool has 1000 tokens and 100 shares, depositing 100 tokens gets 10 shares
-        // because 100 * 100 / 1000 = 10, maintaining 10% ownership for 10% contribution
-        let shares_to_mint = if total_shares == 0 {
-            amount
-        } else {
-            // pool_value_before = current balance minus the just-deposited amount
-            let pool_value_before = balance - amount;
-            if pool_value_before <= 0 {
-                panic!("invalid pool state");
-            }
-            (amount * total_shares) / pool_value_before
-        };
+fn put_hedge_state(e: &Env, state: &HedgeState) {
+    e.storage().instance().set(&DataKey::Hedge, state);
+}
+
+fn require_hedger(e: &Env, caller: &Address) -> HedgeState {
+    caller.require_auth();
+    let state = get_hedge_state(e);
+    if state.hedger.as_ref() != Some(caller) {
+        panic_with_error!(e, PoolError::NotHedger);
+    }
+    state
+}
+
+fn get_pnl_haircut(e: &Env) -> PnlHaircut {
+    e.storage()
+        .instance()
+        .get(&DataKey::PnlHaircut)
+        .unwrap_or(PnlHaircut {
+            threshold_bps: DEFAULT_HAIRCUT_THRESHOLD_BPS,
+            haircut_bps: DEFAULT_HAIRCUT_BPS,
+            traders_pnl: 0,
+            reported_at: 0,
+        })
+}
+
+/// Tokens deducted from `pool_value` when pricing withdrawals: the configured share of
+/// traders' unrealized profit, once it exceeds the threshold share of pool value
+fn withdrawal_haircut(e: &Env, pool_value: i128) -> i128 {
+    let state = get_pnl_haircut(e);
+    if state.traders_pnl <= 0 || pool_value <= 0 {
+        return 0;
+    }
+    let threshold = mul_div(
+        pool_value,
+        state.threshold_bps as i128,
+        10000,
+        Rounding::Floor,
+    );
+    if state.traders_pnl <= threshold {
+        return 0;
+    }
+    mul_div(
+        state.traders_pnl,
+        state.haircut_bps as i128,
+        10000,
+        Rounding::Ceil,
+    )
+    .min(pool_value)
+}
+
+fn get_drawdown_breaker(e: &Env) -> DrawdownBreaker {
+    e.storage()
+        .instance()
+        .get(&DataKey::DrawdownBreaker)
+        .unwrap_or(DrawdownBreaker {
+            max_drawdown_bps: 0,
+            window: DEFAULT_DRAWDOWN_WINDOW,
+            window_start: 0,
+            peak_share_price: 0,
+            previous_peak_share_price: 0,
+            tripped_at: 0,
+        })
+}
+
+/// Open a fresh drawdown window at the current share price, forgetting earlier peaks
+fn restart_drawdown_window(e: &Env, state: &mut DrawdownBreaker) {
+    state.window_start = e.ledger().timestamp();
+    state.peak_share_price = get_share_price(get_pool_value(e), get_total_shares(e));
+    state.previous_peak_share_price = state.peak_share_price;
+}
+
+/// Track the share price peak over the rolling window and, once the share price has
+/// fallen `max_drawdown_bps` below it, globally pause the protocol through ConfigManager.
+/// The pause is best effort so a settlement that moved the price can never fail on it,
+/// but the breaker only records a trip once the protocol is actually paused; otherwise
+/// the next check tries again. Returns whether the breaker tripped.
+fn check_drawdown(e: &Env) -> bool {
+    let mut state = get_drawdown_breaker(e);
+    if state.max_drawdown_bps == 0 {
+        return false;
+    }
+
+    let now = e.ledger().timestamp();
+    let share_price = get_share_price(get_pool_value(e), get_total_shares(e));
+    let elapsed = now.saturating_sub(state.window_start);
+    if elapsed >= state.window {
+        // Roll over: the finished window becomes the previous one, unless a whole
+        // window has passed without a check
+        state.previous_peak_share_price = if elapsed < state.window.saturating_mul(2) {
+            state.peak_share_price
+        } else {
+            share_price
+        };
+        state.window_start = now;
+        state.peak_share_price = share_price;
+    } else {
+        state.peak_share_price = state.peak_share_price.max(share_price);
+    }
+
+    let peak = state.peak_share_price.max(state.previous_peak_share_price);
+    let drawdown_bps = if peak > 0 {
+        mul_div(peak - share_price, 10000, peak, Rounding::Floor).max(0)
+    } else {
+        0
+    };
+    let breached = drawdown_bps >= state.max_drawdown_bps as i128;
+    let tripped = breached && {
+        let config_client = crate::config_manager::Client::new(e, &get_config_manager(e));
+        config_client.is_globally_paused()
+            || matches!(
+                config_client.try_trip_global_pause(&e.current_contract_address()),
+                Ok(Ok(()))
+            )
+    };
+    if tripped {
+        state.tripped_at = now;
+        state.window_start = now;
+        state.peak_share_price = share_price;
+        state.previous_peak_share_price = share_price;
+        DrawdownBreakerTrippedEvent {
+            share_price,
+            peak_share_price: peak,
+            drawdown_bps,
+            seq: next_event_seq(e),
+        }
+        .publish(e);
+    }
+    e.storage()
+        .instance()
+        .set(&DataKey::DrawdownBreaker, &state);
+    tripped
+}
+
+/// The paused withdrawal window in effect at the current timestamp, opening a fresh
+/// one against `pool_value` once the previous window has elapsed
+fn current_paused_window(
+    e: &Env,
+    config_client: &crate::config_manager::Client,
+    pool_value: i128,
+) -> PausedWithdrawalWindow {
+    let now = e.ledger().timestamp();
+    let interval = config_client.paused_withdrawal_interval();
+    match e
+        .storage()
+        .instance()
+        .get::<_, PausedWithdrawalWindow>(&DataKey::PausedWithdrawalWindow)
+    {
+        Some(window) if now < window.started_at.saturating_add(interval) => window,
+        _ => PausedWithdrawalWindow {
+            started_at: now,
+            pool_value,
+            withdrawn: 0,
+        },
+    }
+}
+
+/// Tokens that may still be withdrawn from `window` while paused
+fn paused_window_remaining(
+    config_client: &crate::config_manager::Client,
+    window: &PausedWithdrawalWindow,
+) -> i128 {
+    let limit_bps = config_client.paused_withdrawal_limit_bps();
+    let allowance = mul_div(window.pool_value, limit_bps, 10000, Rounding::Floor);
+    (allowance - window.withdrawn).max(0)
+}
+
+/// Count `amount` against the paused withdrawal window, refusing it past the cap
+fn throttle_paused_withdrawal(
+    e: &Env,
+    config_client: &crate::config_manager::Client,
+    user: &Address,
+    amount: i128,
+    pool_value: i128,
+) {
+    let mut window = current_paused_window(e, config_client, pool_value);
+    let remaining = paused_window_remaining(config_client, &window);
+    if amount > remaining {
+        panic_with_error!(e, PoolError::PausedWithdrawalLimitExceeded);
+    }
+
+    window.withdrawn += amount;
+    e.storage()
+        .instance()
+        .set(&DataKey::PausedWithdrawalWindow, &window);
+
+    PausedWithdrawalEvent {
+        user: user.clone(),
+        amount,
+        remaining: remaining - amount,
+        seq: next_event_seq(e),
+    }
+    .publish(e);
+}
+
+/// Tokens held plus the value of the hedger's holdings, less fees owed to in-kind claimers
+/// and profits owed on payout claims
+fn get_pool_value(e: &Env) -> i128 {
+    get_liquid_balance(e) + get_hedge_state(e).mark_value
+}
+
+fn get_position_collateral(e: &Env, position_id: u64) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::PositionCollateral(position_id))
+        .unwrap_or(0)
+}
+
+fn get_total_position_collateral(e: &Env) -> u128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::TotalPositionCollateral)
+        .unwrap_or(0)
+}
+
+/// Move the aggregate collateral from a position's old amount to its new one
+fn update_total_position_collateral(e: &Env, old_amount: u128, new_amount: u128) {
+    let total = get_total_position_collateral(e) + new_amount;
+    e.storage().instance().set(
+        &DataKey::TotalPositionCollateral,
+        &total.saturating_sub(old_amount),
+    );
+}
+
+fn put_position_collateral(e: &Env, position_id: u64, amount: u128) {
+    update_total_position_collateral(e, get_position_collateral(e, position_id), amount);
+    e.storage()
+        .persistent()
+        .set(&DataKey::PositionCollateral(position_id), &amount);
+}
+
+fn delete_position_collateral(e: &Env, position_id: u64) {
+    update_total_position_collateral(e, get_position_collateral(e, position_id), 0);
+    e.storage()
+        .persistent()
+        .remove(&DataKey::PositionCollateral(position_id));
+}
+
+/// Pool value per share with 7 decimals (1.0 while no shares exist)
+fn get_share_price(pool_value: i128, total_shares: i128) -> i128 {
+    perp::share_price(pool_value, total_shares)
+}
+
+fn get_checkpoint_interval(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::CheckpointInterval)
+        .unwrap_or(DEFAULT_CHECKPOINT_INTERVAL)
+}
+
+fn get_checkpoint_count(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::CheckpointCount)
+        .unwrap_or(0)
+}
+
+/// Record a share price checkpoint unless one was taken within the checkpoint interval,
+/// overwriting the oldest once the history is full. Returns true if one was recorded.
+fn checkpoint_share_price(e: &Env) -> bool {
+    let now = e.ledger().timestamp();
+    let count = get_checkpoint_count(e);
+    if count > 0 {
+        let last: u64 = e
+            .storage()
+            .instance()
+            .get(&DataKey::LastCheckpointAt)
+            .unwrap_or(0);
+        if now < last + get_checkpoint_interval(e) {
+            return false;
+        }
+    }
+
+    let pool_value = get_pool_value(e);
+    let total_shares = get_total_shares(e);
+    let checkpoint = SharePriceCheckpoint {
+        timestamp: now,
+        share_price: get_share_price(pool_value, total_shares),
+        pool_value,
+        total_shares,
+    };
+    e.storage().persistent().set(
+        &DataKey::SharePriceCheckpoint(count % SHARE_PRICE_HISTORY_SIZE),
+        &checkpoint,
+    );
+    e.storage()
+        .instance()
+        .set(&DataKey::CheckpointCount, &(count + 1));
+    e.storage().instance().set(&DataKey::LastCheckpointAt, &now);
+    true
+}
+
+fn validate_batch_size(env: &Env, len: u32) {
+    if len == 0 || len > MAX_BATCH_SIZE {
+        panic_with_error!(env, PoolError::InvalidBatchSize);
+    }
+}
+
+/// Mint LP shares for `amount` tokens deposited by `user` (auth checked by the caller)
+fn deposit_internal(env: &Env, user: &Address, amount: i128) -> i128 {
+    // Validate amount is positive
+    if amount <= 0 {
+        panic_with_error!(env, PoolError::InvalidAmount);
+    }
+
+    // Refuse deposits while ConfigManager has them paused
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    if config_client.is_operation_paused(&config_manager::PauseOp::LpDeposits, &None) {
+        panic_with_error!(env, PoolError::DepositsPaused);
+    }
+    if matches!(get_phase(env), PoolPhase::WindDown | PoolPhase::Terminal) {
+        panic_with_error!(env, PoolError::PoolWindingDown);
+    }
+
+    // Refuse accounts blocked by the compliance hook
+    require_compliant(env, user, "deposit");
+
+    // Settle accrued fees first so share math below sees current balances
+    update_shares(env, user, 0);
+
+    // Get token and current pool state
+    let token = get_token(env);
+    let total_deposits = get_total_deposits(env);
+
+    // Transfer tokens from user to contract first
+    move_tokens(env, &token, user, &env.current_contract_address(), amount);
+
+    let shares_to_mint = shares_for_received(env, amount);
+
+    // Mint shares to user
+    mint_shares(env, user, shares_to_mint);
+
+    // Update total deposits
+    put_total_deposits(env, total_deposits + amount);
+
+    // Report to the testnet campaign, best-effort so it never blocks a deposit
+    if let Some(campaign) = config_client.campaign() {
+        let campaign_client = campaign::Client::new(env, &campaign);
+        let _ = campaign_client.try_record_deposit(&env.current_contract_address(), user, &amount);
+    }
+
+    checkpoint_share_price(env);
+    shares_to_mint
+}
+
+/// LP shares worth `amount` tokens that were just added to the pool balance
+fn shares_for_received(env: &Env, amount: i128) -> i128 {
+    let total_shares = get_total_shares(env);
+
+    // Get actual pool value after transfer (protects against PnL changes)
+    let pool_value = get_pool_value(env);
+
+    // Calculate shares to mint using pro-rata formula to maintain fair LP ownership
+    // First deposit: 1:1 ratio (no existing shares to dilute)
+    // Subsequent deposits: new_shares = (deposit * total_shares) / pool_value_before
+    // This ensures new depositors get shares proportional to their contribution
+    // Example: If pool has 1000 tokens and 100 shares, depositing 100 tokens gets 10 shares
+    // because 100 * 100 / 1000 = 10, maintaining 10% ownership for 10% contribution
+    // pool_value_before = current pool value minus the just-deposited amount
+    let pool_value_before = pool_value - amount;
+    if total_shares > 0 && pool_value_before <= 0 {
+        panic_with_error!(env, PoolError::PoolValueDepleted);
+    }
+    perp::shares_for_deposit(amount, total_shares, pool_value_before)
+}
+
+fn get_order_escrow_units(e: &Env, order_id: u64) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::OrderEscrowUnits(order_id))
+        .unwrap_or(0)
+}
+
+fn get_total_order_escrow_units(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::TotalOrderEscrowUnits)
+        .unwrap_or(0)
+}
+
+/// The OrderManager's shares backing `units` of order escrow. The last units out
+/// take every remaining share, so rounding never strands any.
+fn order_escrow_shares(e: &Env, order_manager: &Address, units: i128) -> i128 {
+    let total_units = get_total_order_escrow_units(e);
+    let held = get_shares(e, order_manager);
+    if units == total_units {
+        held
+    } else {
+        mul_div(held, units, total_units, Rounding::Floor)
+    }
+}
+
+/// Burn `shares` of `user` for their share of pool value (auth checked by the caller)
+fn withdraw_internal(env: &Env, user: &Address, shares: i128) -> i128 {
+    // Validate shares is positive
+    if shares <= 0 {
+        panic_with_error!(env, PoolError::InvalidAmount);
+    }
+
+    // Refuse withdrawals while ConfigManager has them paused
+    let config_manager = get_config_manager(env);
+    let config_client = crate::config_manager::Client::new(env, &config_manager);
+    if config_client.is_operation_paused(&crate::config_manager::PauseOp::LpWithdrawals, &None) {
+        panic_with_error!(env, PoolError::WithdrawalsPaused);
+    }
+    match get_phase(env) {
+        PoolPhase::Bootstrapping => panic_with_error!(env, PoolError::PoolBootstrapping),
+        PoolPhase::WindDown if get_reserved_liquidity(env) > 0 => {
+            panic_with_error!(env, PoolError::OpenInterestOutstanding)
+        }
+        _ => {}
+    }
+
+    // Refuse accounts blocked by the compliance hook
+    require_compliant(env, user, "withdraw");
+
+    // Settle accrued fees first so share math below sees current balances
+    update_shares(env, user, 0);
+
+    // Get token and current pool state
+    let token = get_token(env);
+    let total_shares = get_total_shares(env);
+    let total_deposits = get_total_deposits(env);
+
+    // Prevent division by zero
+    if total_shares == 0 {
+        panic_with_error!(env, PoolError::InsufficientShares);
+    }
+
+    // Get actual balance (reflects PnL from trading), excluding fees owed to in-kind claimers
+    let balance = get_liquid_balance(env);
+
+    // Calculate tokens to return based on actual pool value (including hedge holdings),
+    // net of traders' unrealized profit while it is large enough to trigger the haircut
+    // tokens = (shares * pool_value) / total_shares, rounded down in favor of the pool
+    let pool_value = balance + get_hedge_state(env).mark_value;
+    let haircut = withdrawal_haircut(env, pool_value);
+    let tokens_to_return = mul_div(shares, pool_value - haircut, total_shares, Rounding::Floor);
+    if haircut > 0 {
+        WithdrawalHaircutEvent {
+            user: user.clone(),
+            amount: tokens_to_return,
+            haircut: mul_div(shares, pool_value, total_shares, Rounding::Floor) - tokens_to_return,
+            seq: next_event_seq(env),
+        }
+        .publish(env);
+    }
+
+    // Check available liquidity
+    let reserved = get_reserved_liquidity(env) as i128;
+    let available = balance - reserved;
+
+    if tokens_to_return > available {
+        panic_with_error!(env, PoolError::InsufficientLiquidity);
+    }
+
+    // While the protocol is paused, cap withdrawals per interval so a run on the pool
+    // cannot drain the liquidity owed to winning traders
+    if config_client.is_globally_paused() {
+        throttle_paused_withdrawal(env, &config_client, user, tokens_to_return, pool_value);
+    }
+
+    // Enforce minimum reserve ratio to ensure pool solvency
+    // This protects LPs by ensuring the pool always has enough unreserved liquidity
+    // to handle potential position closures and payouts
+    let min_reserve_ratio = config_client.min_liquidity_reserve_ratio();
+
+    // Calculate how much unreserved liquidity must remain after withdrawal
+    // Example: If min_reserve_ratio = 2000 (20%) and balance_after = 1000,
+    // then min_reserve_required = 200, and (balance - reserved) must be >= 200
+    let balance_after_withdrawal = balance - tokens_to_return;
+    let min_reserve_required = (balance_after_withdrawal * min_reserve_ratio) / 10000;
+
+    if (balance_after_withdrawal - reserved) < min_reserve_required {
+        panic_with_error!(env, PoolError::ReserveRatioViolated);
+    }
+
+    // Burn shares from user (includes validation)
+    burn_shares(env, user, shares);
+
+    // Update total deposits proportionally
+    let deposits_to_reduce = mul_div(shares, total_deposits, total_shares, Rounding::Floor);
+    put_total_deposits(env, total_deposits - deposits_to_reduce);
+
+    // Transfer tokens from contract to user
+    move_tokens(
+        env,
+        &token,
+        &env.current_contract_address(),
+        user,
+        tokens_to_return,
+    );
+
+    checkpoint_share_price(env);
+    tokens_to_return
+}
+
+#[contractimpl]
+impl LiquidityPool {
+    /// Set up the liquidity pool as it is deployed, with its config manager and token
+    /// addresses. Running in the deploy transaction, it cannot be front-run into taking
+    /// another ConfigManager or token.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_manager` - The Config Manager contract address
+    /// * `token` - The token contract address for this pool
+    pub fn __constructor(env: Env, config_manager: Address, token: Address) {
+        put_config_manager(&env, &config_manager);
+        put_token(&env, token);
+        put_total_shares(&env, 0);
+        put_total_deposits(&env, 0);
+    }
+
+    /// Get the Config Manager address.
+    ///
+    /// # Returns
+    ///
+    /// The Config Manager contract address
+    pub fn config_manager(env: Env) -> Address {
+        get_config_manager(&env)
+    }
+
+    /// Get the token address for this pool.
+    ///
+    /// # Returns
+    ///
+    /// The token contract address
+    pub fn token(env: Env) -> Address {
+        get_token(&env)
+    }
+
+    /// Deposit tokens into the liquidity pool and receive LP shares.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The address of the depositor
+    /// * `amount` - The amount of tokens to deposit
+    ///
+    /// # Returns
+    ///
+    /// The number of LP shares minted to the user
+    ///
+    /// # Panics
+    ///
+    /// Panics if amount is not positive, if deposits are paused or the pool is winding
+    /// down, or if the user is refused by the compliance hook
+    pub fn deposit(env: Env, user: Address, amount: i128) -> i128 {
+        // Verify user authorization
+        user.require_auth();
+
+        deposit_internal(&env, &user, amount)
+    }
+
+    /// Withdraw tokens from the liquidity pool by burning LP shares.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The address of the withdrawer
+    /// * `shares` - The number of LP shares to burn
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens returned to the user, net of any unrealized PnL haircut
+    ///
+    /// # Panics
+    ///
+    /// Panics if shares is not positive, if withdrawals are paused, if the pool is
+    /// bootstrapping or winding down with open interest, if total_shares is zero, if the
+    /// user is refused by the compliance hook, if withdrawal would violate liquidity
+    /// constraints, or if the protocol is paused and the withdrawal exceeds the window's
+    /// remaining allowance
+    pub fn withdraw(env: Env, user: Address, shares: i128) -> i128 {
+        // Verify user authorization
+        user.require_auth();
+
+        withdraw_internal(&env, &user, shares)
+    }
+
+    /// Withdraw a user's entire LP position.
+    ///
+    /// Pending fees are compounded first, so the burn leaves no share stub behind. The
+    /// tokens paid out round down and the remainder stays in the pool for the other
+    /// LPs. Fees held for in-kind claiming are not shares and stay claimable.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The address of the withdrawer
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens returned to the user
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user holds no shares, or if withdrawing them would panic in
+    /// `withdraw()`
+    pub fn withdraw_all(env: Env, user: Address) -> i128 {
+        user.require_auth();
+
+        // Compound pending fees into shares so they are burned with the rest
+        update_shares(&env, &user, 0);
+        let shares = get_shares(&env, &user);
+        if shares == 0 {
+            panic_with_error!(&env, PoolError::InsufficientShares);
+        }
+
+        let amount = withdraw_internal(&env, &user, shares);
+        WithdrewAllEvent {
+            user,
+            shares,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        amount
+    }
+
+    /// Deposit for several accounts in one transaction (e.g. a market maker's
+    /// sub-accounts). Every account must authorize its own deposit.
+    ///
+    /// # Arguments
+    ///
+    /// * `deposits` - (user, amount) pairs, processed in order
+    ///
+    /// # Returns
+    ///
+    /// The LP shares minted to each user, in the same order
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch is empty or larger than 20, or if any single deposit
+    /// would panic (the whole batch is reverted)
+    pub fn deposit_many(env: Env, deposits: Vec<(Address, i128)>) -> Vec<i128> {
+        validate_batch_size(&env, deposits.len());
+
+        let mut minted = Vec::new(&env);
+        for (user, amount) in deposits.iter() {
+            user.require_auth();
+            minted.push_back(deposit_internal(&env, &user, amount));
+        }
+        minted
+    }
+
+    /// Withdraw for several accounts in one transaction. Every account must
+    /// authorize its own withdrawal.
+    ///
+    /// # Arguments
+    ///
+    /// * `withdrawals` - (user, shares) pairs, processed in order
+    ///
+    /// # Returns
+    ///
+    /// The tokens returned to each user, in the same order
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch is empty or larger than 20, or if any single withdrawal
+    /// would panic (the whole batch is reverted)
+    pub fn withdraw_many(env: Env, withdrawals: Vec<(Address, i128)>) -> Vec<i128> {
+        validate_batch_size(&env, withdrawals.len());
+
+        let mut returned = Vec::new(&env);
+        for (user, shares) in withdrawals.iter() {
+            user.require_auth();
+            returned.push_back(withdraw_internal(&env, &user, shares));
+        }
+        returned
+    }
+
+    /// Get the tokens LPs may still withdraw in the current paused window.
+    ///
+    /// Withdrawals are only throttled while the protocol is globally paused; the value
+    /// is reported regardless so LPs can see what a pause would allow.
+    ///
+    /// # Returns
+    ///
+    /// The remaining allowance, or the full cap against current pool value if the last
+    /// window has elapsed
+    pub fn get_paused_withdrawal_remaining(env: Env) -> i128 {
+        let config_manager = get_config_manager(&env);
+        let config_client = crate::config_manager::Client::new(&env, &config_manager);
+        let window = current_paused_window(&env, &config_client, get_pool_value(&env));
+        paused_window_remaining(&config_client, &window)
+    }
+
+    /// Get the LP share balance for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The address to query
+    ///
+    /// # Returns
+    ///
+    /// The number of LP shares owned by the user
+    pub fn get_shares(env: Env, user: Address) -> i128 {
+        get_shares(&env, &user)
+    }
+
+    /// Get the total number of LP shares in circulation.
+    ///
+    /// # Returns
+    ///
+    /// The total number of LP shares
+    pub fn get_total_shares(env: Env) -> i128 {
+        get_total_shares(&env)
+    }
+
+    /// Get the total amount of tokens deposited in the pool.
+    ///
+    /// # Returns
+    ///
+    /// The total deposited token amount
+    pub fn get_total_deposits(env: Env) -> i128 {
+        get_total_deposits(&env)
+    }
+
+    /// Display name of the LP shares, for wallets and portfolio trackers.
+    ///
+    /// # Returns
+    ///
+    /// The share name
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "Stellars Finance LP")
+    }
+
+    /// Ticker symbol of the LP shares.
+    ///
+    /// # Returns
+    ///
+    /// The share symbol
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "sfLP")
+    }
+
+    /// Decimals of the LP shares. The first deposit mints shares 1:1, so they share
+    /// the pool token's decimals.
+    ///
+    /// # Returns
+    ///
+    /// The pool token's decimals
+    pub fn decimals(env: Env) -> u32 {
+        token::Client::new(&env, &get_token(&env)).decimals()
+    }
+
+    /// Pool value per LP share, the rate `withdraw()` redeems at before rounding and
+    /// any unrealized PnL haircut (see `get_withdrawal_haircut()`).
+    ///
+    /// # Returns
+    ///
+    /// Tokens per share with 7 decimals (10_000_000 = 1.0, also returned while no
+    /// shares exist since the first deposit mints 1:1)
+    pub fn share_price(env: Env) -> i128 {
+        get_share_price(get_pool_value(&env), get_total_shares(&env))
+    }
+
+    /// Record a share price checkpoint if the checkpoint interval has passed since the
+    /// last one. Permissionless, so keepers can keep the history dense through quiet
+    /// periods without deposits or withdrawals.
+    ///
+    /// # Returns
+    ///
+    /// True if a checkpoint was recorded
+    pub fn checkpoint_share_price(env: Env) -> bool {
+        checkpoint_share_price(&env)
+    }
+
+    /// Set the minimum time between share price checkpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address (must authorize)
+    /// * `interval` - Seconds between checkpoints (default: one day)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not the admin or `interval` is 0
+    pub fn set_checkpoint_interval(env: Env, admin: Address, interval: u64) {
+        require_admin(&env, &admin);
+        if interval == 0 {
+            panic_with_error!(&env, PoolError::InvalidInterval);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::CheckpointInterval, &interval);
+    }
+
+    /// Get the minimum time between share price checkpoints.
+    ///
+    /// # Returns
+    ///
+    /// The interval in seconds
+    pub fn get_checkpoint_interval(env: Env) -> u64 {
+        get_checkpoint_interval(&env)
+    }
+
+    /// Get recent share price checkpoints, for LPs computing their returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Most checkpoints to return (1 to 90)
+    ///
+    /// # Returns
+    ///
+    /// Up to `count` checkpoints, newest first (only the last 90 are retained)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is 0 or larger than 90
+    pub fn get_share_price_history(env: Env, count: u32) -> Vec<SharePriceCheckpoint> {
+        if count == 0 || count > SHARE_PRICE_HISTORY_SIZE {
+            panic_with_error!(&env, PoolError::InvalidBatchSize);
+        }
+
+        let recorded = get_checkpoint_count(&env);
+        let mut history = Vec::new(&env);
+        let mut seq = recorded;
+        while seq > 0 && recorded - seq < count {
+            seq -= 1;
+            let checkpoint: SharePriceCheckpoint = env
+                .storage()
+                .persistent()
+                .get(&DataKey::SharePriceCheckpoint(
+                    seq % SHARE_PRICE_HISTORY_SIZE,
+                ))
+                .unwrap();
+            history.push_back(checkpoint);
+        }
+        history
+    }
+
+    /// Choose how the LP receives trading fees: auto-compounded into shares (the
+    /// default) or accrued as a claimable token balance.
+    ///
+    /// Fees accrued so far are settled under the previous mode; switching to
+    /// auto-compound also reinvests any balance not yet claimed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The LP (must authorize)
+    /// * `claim_in_kind` - True to accrue fees for `claim_fees()`, false to auto-compound
+    pub fn set_fee_mode(env: Env, user: Address, claim_in_kind: bool) {
+        user.require_auth();
+
+        update_shares(&env, &user, 0);
+        let mut account = get_lp_fee_account(&env, &user);
+        account.claim_in_kind = claim_in_kind;
+        put_lp_fee_account(&env, &user, &account);
+        if !claim_in_kind {
+            update_shares(&env, &user, 0);
+        }
+
+        FeeModeUpdatedEvent {
+            user,
+            claim_in_kind,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Withdraw the LP's accrued fees, independently of their shares.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The LP (must authorize)
+    ///
+    /// # Returns
+    ///
+    /// The amount of fees transferred to the user
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user is refused by the compliance hook or has no fees to claim
+    /// (auto-compounding LPs never do)
+    pub fn claim_fees(env: Env, user: Address) -> i128 {
+        user.require_auth();
+        require_compliant(&env, &user, "claim_fees");
+
+        update_shares(&env, &user, 0);
+        let mut account = get_lp_fee_account(&env, &user);
+        let amount = account.pending;
+        if amount <= 0 {
+            panic_with_error!(&env, PoolError::NoFeesToClaim);
+        }
+
+        account.pending = 0;
+        put_lp_fee_account(&env, &user, &account);
+        env.storage().instance().set(
+            &DataKey::UnclaimedFees,
+            &(get_unclaimed_fees(&env) - amount).max(0),
+        );
+
+        move_tokens(
+            &env,
+            &get_token(&env),
+            &env.current_contract_address(),
+            &user,
+            amount,
+        );
+
+        FeesClaimedEvent {
+            user,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        amount
+    }
+
+    /// Reinvest an auto-compounding LP's accrued fees as shares. Anyone may call it;
+    /// deposits and withdrawals do the same for the LP involved.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The LP whose fees to compound
+    ///
+    /// # Returns
+    ///
+    /// The LP's share balance afterwards
+    pub fn compound_fees(env: Env, user: Address) -> i128 {
+        update_shares(&env, &user, 0);
+        get_shares(&env, &user)
+    }
+
+    /// Get an LP's fee mode and accrued fees.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The LP to query
+    ///
+    /// # Returns
+    ///
+    /// The LpFeeAccount with `pending` brought up to date
+    pub fn get_lp_fee_account(env: Env, user: Address) -> LpFeeAccount {
+        let mut account = get_lp_fee_account(&env, &user);
+        checkpoint_fees(&env, &mut account, get_shares(&env, &user));
+        account
+    }
+
+    /// Report trading fees kept by the pool. They are credited to all LPs per share
+    /// and leave pool value until each LP compounds or claims their part.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_manager` - The Position Manager contract address
+    /// * `amount` - Fees retained by the pool (borrowing and early-close fees)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the authorized position manager
+    pub fn accrue_fees(env: Env, position_manager: Address, amount: i128) {
+        require_position_manager(&env, &position_manager);
+        let total_shares = get_total_shares(&env);
+        if amount <= 0 || total_shares == 0 {
+            return;
+        }
+
+        let acc_increase = mul_div(
+            amount,
+            FEE_PER_SHARE_PRECISION,
+            total_shares,
+            Rounding::Floor,
+        );
+        let distributed = mul_div(
+            acc_increase,
+            total_shares,
+            FEE_PER_SHARE_PRECISION,
+            Rounding::Floor,
+        );
+        env.storage().instance().set(
+            &DataKey::AccFeePerShare,
+            &(get_acc_fee_per_share(&env) + acc_increase),
+        );
+        env.storage().instance().set(
+            &DataKey::UnclaimedFees,
+            &(get_unclaimed_fees(&env) + distributed),
+        );
+
+        FeesAccruedEvent {
+            amount,
+            distributed,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Report the part of a position's fees owed to the frontend it was opened
+    /// through. The tokens stay in the pool, outside pool value, until claimed.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_manager` - The Position Manager contract address
+    /// * `frontend` - The frontend's fee recipient address
+    /// * `amount` - Fees owed to the frontend
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the authorized position manager
+    pub fn accrue_frontend_fees(
+        env: Env,
+        position_manager: Address,
+        frontend: Address,
+        amount: i128,
+    ) {
+        require_position_manager(&env, &position_manager);
+        if amount <= 0 {
+            return;
+        }
+
+        env.storage().persistent().set(
+            &DataKey::FrontendFees(frontend.clone()),
+            &(get_frontend_fees(&env, &frontend) + amount),
+        );
+        env.storage().instance().set(
+            &DataKey::TotalFrontendFees,
+            &(get_total_frontend_fees(&env) + amount),
+        );
+
+        FrontendFeesAccruedEvent {
+            frontend,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Withdraw a frontend's accrued fee share.
+    ///
+    /// # Arguments
+    ///
+    /// * `frontend` - The frontend's fee recipient address
+    ///
+    /// # Returns
+    ///
+    /// The amount paid out
+    ///
+    /// # Panics
+    ///
+    /// Panics if the frontend has no fees to claim
+    pub fn claim_frontend_fees(env: Env, frontend: Address) -> i128 {
+        frontend.require_auth();
+
+        let amount = get_frontend_fees(&env, &frontend);
+        if amount <= 0 {
+            panic_with_error!(&env, PoolError::NoFeesToClaim);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::FrontendFees(frontend.clone()));
+        env.storage().instance().set(
+            &DataKey::TotalFrontendFees,
+            &(get_total_frontend_fees(&env) - amount),
+        );
+
+        move_tokens(
+            &env,
+            &get_token(&env),
+            &env.current_contract_address(),
+            &frontend,
+            amount,
+        );
+
+        FrontendFeesClaimedEvent {
+            frontend,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        amount
+    }
+
+    /// Get a frontend's accrued fee share.
+    ///
+    /// # Arguments
+    ///
+    /// * `frontend` - The frontend's fee recipient address
+    ///
+    /// # Returns
+    ///
+    /// Fees accrued and not yet claimed
+    pub fn get_frontend_fees(env: Env, frontend: Address) -> i128 {
+        get_frontend_fees(&env, &frontend)
+    }
+
+    /// Set the authorized position manager that can reserve/release liquidity.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    /// * `position_manager` - The Position Manager contract address
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not authorized
+    pub fn set_position_manager(env: Env, admin: Address, position_manager: Address) {
+        admin.require_auth();
+
+        // Verify caller is the admin from ConfigManager
+        let config_manager = get_config_manager(&env);
+        let config_client = crate::config_manager::Client::new(&env, &config_manager);
+        let config_admin = config_client.admin();
+
+        if admin != config_admin {
+            panic_with_error!(&env, PoolError::NotAdmin);
+        }
+
+        put_authorized_position_manager(&env, &position_manager);
+    }
+
+    /// Launch a fresh pool in the deposit-only bootstrapping phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    /// * `seed_target` - Deposits required before the pool can be activated
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the target is not positive, the pool already
+    /// has shares or it is not in the active phase
+    pub fn begin_bootstrapping(env: Env, admin: Address, seed_target: i128) {
+        require_admin(&env, &admin);
+        if seed_target <= 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+        if get_total_shares(&env) > 0 {
+            panic_with_error!(&env, PoolError::PoolAlreadySeeded);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SeedTarget, &seed_target);
+        transition_phase(&env, PoolPhase::Active, PoolPhase::Bootstrapping);
+    }
+
+    /// Open a bootstrapping pool for trading and withdrawals.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the pool is not bootstrapping or deposits
+    /// have not reached the seed target
+    pub fn activate(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+        if get_total_deposits(&env) < get_seed_target(&env) {
+            panic_with_error!(&env, PoolError::SeedTargetNotMet);
+        }
+
+        transition_phase(&env, PoolPhase::Bootstrapping, PoolPhase::Active);
+    }
+
+    /// Stop new positions and deposits so the pool can be retired. Existing positions
+    /// can still be closed or liquidated; LPs may withdraw once none remain.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or the pool is not active
+    pub fn begin_wind_down(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+        transition_phase(&env, PoolPhase::Active, PoolPhase::WindDown);
+    }
+
+    /// Make a wound-down pool terminal once nothing is left at risk: every position has
+    /// closed (no reserved liquidity or trader collateral) and the hedger has repaid its
+    /// principal. Open to anyone and does not consult ConfigManager, so LPs can reach
+    /// `emergency_withdraw()` even if the admin is gone or other modules are broken.
+    /// Winding the pool down already refuses new positions in every market, so no
+    /// market-level delisting is required.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool is not winding down, open interest or trader collateral
+    /// remains, or hedge principal is outstanding
+    pub fn enter_terminal_phase(env: Env) {
+        if get_reserved_liquidity(&env) > 0 || get_total_position_collateral(&env) > 0 {
+            panic_with_error!(&env, PoolError::OpenInterestOutstanding);
+        }
+        if get_hedge_state(&env).borrowed > 0 {
+            panic_with_error!(&env, PoolError::HedgeBorrowOutstanding);
+        }
+
+        transition_phase(&env, PoolPhase::WindDown, PoolPhase::Terminal);
+    }
+
+    /// Redeem LP shares for their pro-rata slice of the pool's remaining tokens, the
+    /// exit of last resort once the pool is terminal. Unlike `withdraw()` it skips every
+    /// ConfigManager check (pauses, compliance hook, reserve ratio, paused throttle) and
+    /// the campaign, touching only the pool's own storage and token. Fees owed to
+    /// in-kind claimers and frontends and payout claims owed to traders are set aside
+    /// first; accrued auto-compound fees are folded into the LP's shares before the
+    /// slice is taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The LP redeeming (must authorize)
+    /// * `shares` - The number of LP shares to burn
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens sent to the user
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool is not terminal, shares is not positive or exceeds the
+    /// user's balance
+    pub fn emergency_withdraw(env: Env, user: Address, shares: i128) -> i128 {
+        user.require_auth();
+        if get_phase(&env) != PoolPhase::Terminal {
+            panic_with_error!(&env, PoolError::PoolNotTerminal);
+        }
+        if shares <= 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+
+        // Settle accrued fees first so the slice below sees current balances
+        update_shares(&env, &user, 0);
+
+        let total_shares = get_total_shares(&env);
+        let total_deposits = get_total_deposits(&env);
+        burn_shares(&env, &user, shares);
+
+        let amount = mul_div(
+            shares,
+            get_liquid_balance(&env).max(0),
+            total_shares,
+            Rounding::Floor,
+        );
+        let deposits_to_reduce = mul_div(shares, total_deposits, total_shares, Rounding::Floor);
+        put_total_deposits(&env, total_deposits - deposits_to_reduce);
+
+        move_tokens(
+            &env,
+            &get_token(&env),
+            &env.current_contract_address(),
+            &user,
+            amount,
+        );
+
+        EmergencyWithdrawEvent {
+            user,
+            shares,
+            amount,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        amount
+    }
+
+    /// Get the pool's lifecycle phase.
+    ///
+    /// # Returns
+    ///
+    /// The current phase (Active unless the admin bootstrapped or wound down the pool)
+    pub fn get_phase(env: Env) -> PoolPhase {
+        get_phase(&env)
+    }
+
+    /// Get the deposits required before a bootstrapping pool can activate.
+    ///
+    /// # Returns
+    ///
+    /// The seed liquidity target (0 if the pool was never bootstrapped)
+    pub fn get_seed_target(env: Env) -> i128 {
+        get_seed_target(&env)
+    }
+
+    /// Configure a migration of the pool's backing token, the first step of the flow:
+    /// configure, globally pause the protocol (ConfigManager), wait for resting limit and
+    /// TWAP orders to be cancelled or parked, `snapshot_token_migration()`,
+    /// `execute_token_migration()` (which also points ConfigManager's token at the new
+    /// token), then lift the pause. Replaces any migration configured before.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    /// * `new_token` - The token that will back the pool
+    /// * `migrator` - Contract implementing `TokenMigrator` that performs the swap
+    /// * `rate` - New token units per old token unit, scaled by 1e7 (1e7 = 1:1)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the rate is not positive or the new token is
+    /// the current one
+    pub fn configure_token_migration(
+        env: Env,
+        admin: Address,
+        new_token: Address,
+        migrator: Address,
+        rate: i128,
+    ) {
+        require_admin(&env, &admin);
+        if rate <= 0 {
+            panic_with_error!(&env, PoolError::InvalidMigrationRate);
+        }
+        if new_token == get_token(&env) {
+            panic_with_error!(&env, PoolError::AlreadyBackedByToken);
+        }
+
+        let migration = TokenMigration {
+            new_token: new_token.clone(),
+            migrator: migrator.clone(),
+            rate,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenMigration, &migration);
+        env.storage()
+            .instance()
+            .remove(&DataKey::TokenMigrationSnapshot);
+
+        TokenMigrationConfiguredEvent {
+            new_token,
+            migrator,
+            rate,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Dry-run the configured token migration against the pool's current state.
+    ///
+    /// # Returns
+    ///
+    /// The balance that would be swapped, the minimum the migrator must return, the
+    /// resulting share price and whether the pause and rate requirements are met
+    ///
+    /// # Panics
+    ///
+    /// Panics if no migration is configured
+    pub fn preview_token_migration(env: Env) -> TokenMigrationPreview {
+        let migration = get_token_migration(&env);
+        let config_client = crate::config_manager::Client::new(&env, &get_config_manager(&env));
+
+        let balance = get_balance(&env);
+        let total_shares = get_total_shares(&env);
+        let pool_value = mul_div(
+            get_pool_value(&env),
+            migration.rate,
+            MIGRATION_RATE_PRECISION,
+            Rounding::Floor,
+        );
+        TokenMigrationPreview {
+            old_token: get_token(&env),
+            new_token: migration.new_token,
+            rate: migration.rate,
+            balance,
+            expected_balance: mul_div(
+                balance,
+                migration.rate,
+                MIGRATION_RATE_PRECISION,
+                Rounding::Floor,
+            ),
+            total_shares,
+            share_price: get_share_price(pool_value, total_shares),
+            position_collateral: get_total_position_collateral(&env),
+            order_escrow: get_order_manager_escrow(&env),
+            globally_paused: config_client.is_globally_paused(),
+            rate_allowed: migration_rate_allowed(&env, migration.rate),
+        }
+    }
+
+    /// Record the pool's balance, shares and position collateral ahead of the swap.
+    /// `execute_token_migration()` refuses to run if any of them moved since.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    ///
+    /// # Returns
+    ///
+    /// The snapshot taken
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, no migration is configured, the protocol is
+    /// not globally paused, OrderManager still escrows order collateral or fees, or the rate is
+    /// not 1:1 while token amounts are owed out
+    pub fn snapshot_token_migration(env: Env, admin: Address) -> TokenMigrationSnapshot {
+        require_admin(&env, &admin);
+        require_globally_paused(&env);
+        require_no_order_escrow(&env);
+        let migration = get_token_migration(&env);
+        if !migration_rate_allowed(&env, migration.rate) {
+            panic_with_error!(&env, PoolError::InvalidMigrationRate);
+        }
+
+        let snapshot = TokenMigrationSnapshot {
+            taken_at: env.ledger().timestamp(),
+            balance: get_balance(&env),
+            total_shares: get_total_shares(&env),
+            position_collateral: get_total_position_collateral(&env),
+            reserved_liquidity: get_reserved_liquidity(&env),
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenMigrationSnapshot, &snapshot);
+
+        TokenMigrationSnapshotEvent {
+            balance: snapshot.balance,
+            total_shares: snapshot.total_shares,
+            position_collateral: snapshot.position_collateral,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        snapshot
+    }
+
+    /// Swap the pool's whole balance into the new token through the migrator and make
+    /// it the backing token of the pool and, through `ConfigManager::migrate_token()`,
+    /// of the protocol. LP shares are untouched, so each LP keeps its proportion of the
+    /// pool; total deposits are converted at the rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    ///
+    /// # Returns
+    ///
+    /// The amount of new tokens received
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the protocol is not globally paused, no
+    /// snapshot was taken, the pool changed since the snapshot, OrderManager still
+    /// escrows order collateral or fees, or the migrator returned less than the balance
+    /// converted at the rate
+    pub fn execute_token_migration(env: Env, admin: Address) -> i128 {
+        require_admin(&env, &admin);
+        require_globally_paused(&env);
+        require_no_order_escrow(&env);
+        let migration = get_token_migration(&env);
+        let snapshot: TokenMigrationSnapshot = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenMigrationSnapshot)
+            .unwrap_or_else(|| panic_with_error!(&env, PoolError::MigrationSnapshotRequired));
+
+        let balance = get_balance(&env);
+        if balance != snapshot.balance
+            || get_total_shares(&env) != snapshot.total_shares
+            || get_total_position_collateral(&env) != snapshot.position_collateral
+            || get_reserved_liquidity(&env) != snapshot.reserved_liquidity
+        {
+            panic_with_error!(&env, PoolError::PoolChangedSinceSnapshot);
+        }
+
+        let pool = env.current_contract_address();
+        let old_token = get_token(&env);
+        let new_token_client = token::Client::new(&env, &migration.new_token);
+        let balance_before = new_token_client.balance(&pool);
+
+        move_tokens(&env, &old_token, &pool, &migration.migrator, balance);
+        TokenMigratorClient::new(&env, &migration.migrator).swap(
+            &old_token,
+            &migration.new_token,
+            &balance,
+            &migration.rate,
+            &pool,
+        );
+
+        let received = new_token_client.balance(&pool) - balance_before;
+        let expected = mul_div(
+            balance,
+            migration.rate,
+            MIGRATION_RATE_PRECISION,
+            Rounding::Floor,
+        );
+        if received < expected {
+            panic_with_error!(&env, PoolError::MigrationShortfall);
+        }
+
+        put_token(&env, migration.new_token.clone());
+        crate::config_manager::Client::new(&env, &get_config_manager(&env))
+            .migrate_token(&pool, &migration.new_token);
+        put_total_deposits(
+            &env,
+            mul_div(
+                get_total_deposits(&env),
+                migration.rate,
+                MIGRATION_RATE_PRECISION,
+                Rounding::Floor,
+            ),
+        );
+        env.storage().instance().remove(&DataKey::TokenMigration);
+        env.storage()
+            .instance()
+            .remove(&DataKey::TokenMigrationSnapshot);
+
+        TokenMigratedEvent {
+            old_token,
+            new_token: migration.new_token,
+            amount_in: balance,
+            amount_out: received,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        received
+    }
+
+    /// Drop the configured token migration before it executes.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin or no migration is configured
+    pub fn cancel_token_migration(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+        let migration = get_token_migration(&env);
+        env.storage().instance().remove(&DataKey::TokenMigration);
+        env.storage()
+            .instance()
+            .remove(&DataKey::TokenMigrationSnapshot);
+
+        TokenMigrationCancelledEvent {
+            new_token: migration.new_token,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Get the token migration in progress.
+    ///
+    /// # Returns
+    ///
+    /// The configured migration, or None
+    pub fn get_token_migration(env: Env) -> Option<TokenMigration> {
+        env.storage().instance().get(&DataKey::TokenMigration)
+    }
+
+    /// Get the snapshot taken for the token migration in progress.
+    ///
+    /// # Returns
+    ///
+    /// The snapshot `execute_token_migration()` will check against, or None
+    pub fn get_token_migration_snapshot(env: Env) -> Option<TokenMigrationSnapshot> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TokenMigrationSnapshot)
+    }
+
+    /// Set the hedger allowed to borrow pool liquidity, and its borrowing cap.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin address (must match ConfigManager admin)
+    /// * `hedger` - The hedging strategy address (None disables hedging)
+    /// * `max_borrow_bps` - Max outstanding principal as bps of pool value (max 10000)
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the admin, the cap exceeds 10000 bps, or the hedger
+    /// is replaced while it still owes the pool
+    pub fn set_hedger(env: Env, admin: Address, hedger: Option<Address>, max_borrow_bps: u32) {
+        admin.require_auth();
+
+        // Verify caller is the admin from ConfigManager
+        let config_manager = get_config_manager(&env);
+        let config_client = crate::config_manager::Client::new(&env, &config_manager);
+        if admin != config_client.admin() {
+            panic_with_error!(&env, PoolError::NotAdmin);
+        }
 
-        // Mint shares to user
-        mint_shares(&env, &user, shares_to_mint);
+        if max_borrow_bps > 10000 {
+            panic_with_error!(&env, PoolError::InvalidBps);
+        }
 
-        // Update total deposits
-        put_total_deposits(&env, total_deposits + amount);
+        let mut state = get_hedge_state(&env);
+        if state.hedger != hedger && state.borrowed > 0 {
+            panic_with_error!(&env, PoolError::HedgeBorrowOutstanding);
+        }
+        if state.hedger != hedger {
+            state.mark_value = 0;
+            state.reported_at = 0;
+        }
 
-        shares_to_mint
+        state.hedger = hedger;
+        state.max_borrow_bps = max_borrow_bps;
+        put_hedge_state(&env, &state);
     }
 
-    /// Withdraw tokens from the liquidity pool by burning LP shares.
+    /// Borrow pool liquidity to open offsetting hedge positions.
+    ///
+    /// The borrowed tokens stay in pool value as hedge holdings until the hedger
+    /// reports a new mark-to-market value or repays.
     ///
     /// # Arguments
     ///
-    /// * `user` - The address of the withdrawer
-    /// * `shares` - The number of LP shares to burn
+    /// * `hedger` - The hedger address (must authorize)
+    /// * `amount` - Tokens to borrow
     ///
     /// # Returns
     ///
-    /// The amount of tokens returned to the user
+    /// The outstanding principal after borrowing
     ///
     /// # Panics
     ///
-    /// Panics if shares is not positive, if total_shares is zero,
-    /// or if withdrawal would violate liquidity constraints
-    pub fn withdraw(env: Env, user: Address, shares: i128) -> i128 {
-        // Verify user authorization
-        user.require_auth();
-
-        // Validate shares is positive
-        if shares <= 0 {
-            panic!("shares must be positive");
+    /// Panics if caller is not the hedger, the amount is zero, the cap would be
+    /// exceeded, or the amount exceeds unreserved liquidity
+    pub fn hedge_borrow(env: Env, hedger: Address, amount: u128) -> u128 {
+        let mut state = require_hedger(&env, &hedger);
+        if amount == 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
         }
 
-        // Get token and current pool state
-        let token = get_token(&env);
-        let total_shares = get_total_shares(&env);
-        let total_deposits = get_total_deposits(&env);
-
-        // Prevent division by zero
-        if total_shares == 0 {
-            panic!("no shares to burn");
+        let pool_value = get_pool_value(&env);
+        let cap = pool_value.max(0) as u128 * state.max_borrow_bps as u128 / 10000;
+        if state.borrowed + amount > cap {
+            panic_with_error!(&env, PoolError::HedgeBorrowCapExceeded);
         }
 
-        // Get actual balance (reflects PnL from trading)
-        let balance = get_balance(&env);
+        let available = get_liquid_balance(&env) - get_reserved_liquidity(&env) as i128;
+        if amount as i128 > available {
+            panic_with_error!(&env, PoolError::InsufficientLiquidity);
+        }
 
-        // Calculate tokens to return based on actual pool value
-        // tokens = (shares * balance) / total_shares
-        let tokens_to_return = (shares * balance) / total_shares;
+        state.borrowed += amount;
+        state.mark_value += amount as i128;
+        put_hedge_state(&env, &state);
 
-        // Check available liquidity
-        let reserved = get_reserved_liquidity(&env) as i128;
-        let available = balance - reserved;
+        let token = get_token(&env);
+        move_tokens(
+            &env,
+            &token,
+            &env.current_contract_address(),
+            &hedger,
+            amount as i128,
+        );
+
+        state.borrowed
+    }
 
-        if tokens_to_return > available {
-            panic!("insufficient available liquidity");
+    /// Repay borrowed liquidity. Repaying more than the outstanding principal
+    /// returns hedging profit to the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `hedger` - The hedger address (must authorize)
+    /// * `amount` - Tokens to return to the pool
+    ///
+    /// # Returns
+    ///
+    /// The outstanding principal after repaying
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the hedger or the amount is zero
+    pub fn hedge_repay(env: Env, hedger: Address, amount: u128) -> u128 {
+        let mut state = require_hedger(&env, &hedger);
+        if amount == 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
         }
 
-        // Enforce minimum reserve ratio to ensure pool solvency
-        // This protects LPs by ensuring the pool always has enough unreserved liquidity
-        // to handle potential position closures and payouts
-        let config_manager = get_config_manager(&env);
-        let config_client = crate::config_manager::Client::new(&env, &config_manager);
-        let min_reserve_ratio = config_client.min_liquidity_reserve_ratio();
-
-        // Calculate how much unreserved liquidity must remain after withdrawal
-        // Example: If min_reserve_ratio = 2000 (20%) and balance_after = 1000,
-        // then min_reserve_required = 200, and (balance - reserved) must be >= 200
-        let balance_after_withdrawal = balance - tokens_to_return;
-        let min_reserve_required = (balance_after_withdrawal * min_reserve_ratio) / 10000;
+        let token = get_token(&env);
+        move_tokens(
+            &env,
+            &token,
+            &hedger,
+            &env.current_contract_address(),
+            amount as i128,
+        );
+
+        // Repaid tokens move from hedge holdings back to the pool balance
+        state.borrowed = state.borrowed.saturating_sub(amount);
+        state.mark_value = (state.mark_value - amount as i128).max(0);
+        put_hedge_state(&env, &state);
+
+        state.borrowed
+    }
 
-        if (balance_after_withdrawal - reserved) < min_reserve_required {
-            panic!("withdrawal would violate minimum reserve ratio");
+    /// Report the current mark-to-market value of the hedger's holdings, so
+    /// hedging gains and losses flow into LP share value.
+    ///
+    /// # Arguments
+    ///
+    /// * `hedger` - The hedger address (must authorize)
+    /// * `mark_value` - Value of the holdings bought with borrowed liquidity, in pool tokens
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the hedger or the value is negative
+    pub fn report_hedge_value(env: Env, hedger: Address, mark_value: i128) {
+        let mut state = require_hedger(&env, &hedger);
+        if mark_value < 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
         }
 
-        // Burn shares from user (includes validation)
-        burn_shares(&env, &user, shares);
+        state.mark_value = mark_value;
+        state.reported_at = env.ledger().timestamp();
+        put_hedge_state(&env, &state);
+        check_drawdown(&env);
+    }
 
-        // Update total deposits proportionally
-        let deposits_to_reduce = (shares * total_deposits) / total_shares;
-        put_total_deposits(&env, total_deposits - deposits_to_reduce);
+    /// Get the hedger, its borrowing cap, outstanding principal and last report.
+    ///
+    /// # Returns
+    ///
+    /// The current HedgeState
+    pub fn get_hedge_state(env: Env) -> HedgeState {
+        get_hedge_state(&env)
+    }
 
-        // Transfer tokens from contract to user
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &user, &tokens_to_return);
+    /// Set when and how much of traders' unrealized profit is deducted from pool value
+    /// when pricing withdrawals.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The administrator address (must authorize)
+    /// * `threshold_bps` - Traders' profit, as bps of pool value, above which the haircut
+    ///   applies (default 500)
+    /// * `haircut_bps` - Share of traders' profit deducted (default 10000, 0 disables)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not the admin or `haircut_bps` exceeds 10000
+    pub fn set_pnl_haircut(env: Env, admin: Address, threshold_bps: u32, haircut_bps: u32) {
+        require_admin(&env, &admin);
+        if haircut_bps > 10000 {
+            panic_with_error!(&env, PoolError::InvalidBps);
+        }
 
-        tokens_to_return
+        let mut state = get_pnl_haircut(&env);
+        state.threshold_bps = threshold_bps;
+        state.haircut_bps = haircut_bps;
+        env.storage().instance().set(&DataKey::PnlHaircut, &state);
     }
 
-    /// Get the LP share balance for a user.
+    /// Report traders' aggregate unrealized PnL against the pool, which withdrawals are
+    /// haircut by while it is large.
     ///
     /// # Arguments
     ///
-    /// * `user` - The address to query
+    /// * `position_manager` - The Position Manager contract address
+    /// * `traders_pnl` - Unrealized PnL of all open positions (positive = pool owes)
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// The number of LP shares owned by the user
-    pub fn get_shares(env: Env, user: Address) -> i128 {
-        get_shares(&env, &user)
+    /// Panics if caller is not the authorized position manager
+    pub fn report_unrealized_pnl(env: Env, position_manager: Address, traders_pnl: i128) {
+        require_position_manager(&env, &position_manager);
+
+        let mut state = get_pnl_haircut(&env);
+        state.traders_pnl = traders_pnl;
+        state.reported_at = env.ledger().timestamp();
+        env.storage().instance().set(&DataKey::PnlHaircut, &state);
     }
 
-    /// Get the total number of LP shares in circulation.
+    /// Get the haircut settings and traders' last reported unrealized PnL.
     ///
     /// # Returns
     ///
-    /// The total number of LP shares
-    pub fn get_total_shares(env: Env) -> i128 {
-        get_total_shares(&env)
+    /// The current PnlHaircut
+    pub fn get_pnl_haircut(env: Env) -> PnlHaircut {
+        get_pnl_haircut(&env)
     }
 
-    /// Get the total amount of tokens deposited in the pool.
+    /// Configure the drawdown circuit breaker, which globally pauses the protocol when
+    /// the share price falls too far below its recent peak, and restart its window at
+    /// the current share price.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// The total deposited token amount
-    pub fn get_total_deposits(env: Env) -> i128 {
-        get_total_deposits(&env)
+    /// * `admin` - The administrator address (must authorize)
+    /// * `max_drawdown_bps` - Drop from the peak share price that trips it (0 disables)
+    /// * `window` - Seconds per window; the peak spans the current and previous window
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not the admin, `max_drawdown_bps` exceeds 10000 or
+    /// `window` is 0
+    pub fn set_drawdown_breaker(env: Env, admin: Address, max_drawdown_bps: u32, window: u64) {
+        require_admin(&env, &admin);
+        if max_drawdown_bps > 10000 {
+            panic_with_error!(&env, PoolError::InvalidBps);
+        }
+        if window == 0 {
+            panic_with_error!(&env, PoolError::InvalidInterval);
+        }
+
+        let mut state = get_drawdown_breaker(&env);
+        state.max_drawdown_bps = max_drawdown_bps;
+        state.window = window;
+        restart_drawdown_window(&env, &mut state);
+        env.storage()
+            .instance()
+            .set(&DataKey::DrawdownBreaker, &state);
     }
 
-    /// Set the authorized position manager that can reserve/release liquidity.
+    /// Re-arm the drawdown circuit breaker after it tripped, measuring from the current
+    /// share price. The global pause itself is lifted in ConfigManager.
     ///
     /// # Arguments
     ///
-    /// * `admin` - The admin address (must match ConfigManager admin)
-    /// * `position_manager` - The Position Manager contract address
+    /// * `admin` - The administrator address (must authorize)
     ///
     /// # Panics
     ///
-    /// Panics if caller is not authorized
-    pub fn set_position_manager(env: Env, admin: Address, position_manager: Address) {
-        admin.require_auth();
+    /// Panics if the caller is not the admin
+    pub fn reset_drawdown_breaker(env: Env, admin: Address) {
+        require_admin(&env, &admin);
 
-        // Verify caller is the admin from ConfigManager
-        let config_manager = get_config_manager(&env);
-        let config_client = crate::config_manager::Client::new(&env, &config_manager);
-        let config_admin = config_client.admin();
+        let mut state = get_drawdown_breaker(&env);
+        state.tripped_at = 0;
+        restart_drawdown_window(&env, &mut state);
+        env.storage()
+            .instance()
+            .set(&DataKey::DrawdownBreaker, &state);
+    }
 
-        if admin != config_admin {
-            panic!("unauthorized: not admin");
-        }
+    /// Check the share price against the drawdown limit, tripping the global pause if it
+    /// is breached. Settlements and hedge reports check it already; this lets keepers
+    /// catch drops from other sources. Open to anyone.
+    ///
+    /// # Returns
+    ///
+    /// Whether the breaker tripped
+    pub fn check_drawdown_breaker(env: Env) -> bool {
+        check_drawdown(&env)
+    }
 
-        put_authorized_position_manager(&env, &position_manager);
+    /// Get the drawdown limit, its window and the peak share price it is measured from.
+    ///
+    /// # Returns
+    ///
+    /// The current DrawdownBreaker
+    pub fn get_drawdown_breaker(env: Env) -> DrawdownBreaker {
+        get_drawdown_breaker(&env)
+    }
+
+    /// Get the tokens currently deducted from pool value when pricing withdrawals.
+    ///
+    /// # Returns
+    ///
+    /// The haircut (0 while traders' profit is under the threshold)
+    pub fn get_withdrawal_haircut(env: Env) -> i128 {
+        withdrawal_haircut(&env, get_pool_value(&env))
     }
 
     /// Reserve liquidity when a position is opened.
@@ -432,7 +2772,7 @@ impl LiquidityPool {
     ///
     /// # Panics
     ///
-    /// Panics if caller is not the authorized position manager
+    /// Panics if caller is not the authorized position manager or the pool is not active
     pub fn reserve_liquidity(
         env: Env,
         position_manager: Address,
@@ -441,6 +2781,7 @@ impl LiquidityPool {
         collateral: u128,
     ) {
         require_position_manager(&env, &position_manager);
+        require_trading_phase(&env);
 
         let reserved = get_reserved_liquidity(&env);
         let new_reserved = reserved + size;
@@ -454,18 +2795,18 @@ impl LiquidityPool {
     /// # Arguments
     ///
     /// * `position_manager` - The Position Manager contract address
-    /// * `position_id` - The position ID
+    /// * `_position_id` - The position ID (unused)
     /// * `size` - The position size (notional value) to release
     ///
     /// # Panics
     ///
     /// Panics if caller is not the authorized position manager
-    pub fn release_liquidity(env: Env, position_manager: Address, position_id: u64, size: u128) {
+    pub fn release_liquidity(env: Env, position_manager: Address, _position_id: u64, size: u128) {
         require_position_manager(&env, &position_manager);
 
         let reserved = get_reserved_liquidity(&env);
         if size > reserved {
-            panic!("cannot release more than reserved");
+            panic_with_error!(&env, PoolError::ReleaseExceedsReserved);
         }
 
         let new_reserved = reserved - size;
@@ -487,7 +2828,7 @@ impl LiquidityPool {
     ///
     /// The liquidity available for withdrawal or new positions
     pub fn get_available_liquidity(env: Env) -> i128 {
-        let balance = get_balance(&env);
+        let balance = get_liquid_balance(&env);
         let reserved = get_reserved_liquidity(&env) as i128;
         balance - reserved
     }
@@ -498,7 +2839,7 @@ impl LiquidityPool {
     ///
     /// The utilization ratio in basis points (e.g., 8000 = 80%)
     pub fn get_utilization_ratio(env: Env) -> u32 {
-        let balance = get_balance(&env);
+        let balance = get_liquid_balance(&env);
         if balance == 0 {
             return 0;
         }
@@ -509,6 +2850,52 @@ impl LiquidityPool {
         utilization as u32
     }
 
+    /// Get the pool's solvency: what it holds, plus the insurance fund, against LP
+    /// principal, trader collateral and the most open positions could win. A single
+    /// number for risk monitors to alarm on.
+    ///
+    /// Profits are bounded by reserved liquidity (the notional of open positions), so
+    /// the ratio is conservative rather than a mark-to-market valuation.
+    ///
+    /// # Returns
+    ///
+    /// Solvency with the ratio in basis points and its components (ratio is 10000
+    /// when nothing is owed)
+    pub fn get_solvency(env: Env) -> Solvency {
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let token_client = token::Client::new(&env, &get_token(&env));
+
+        let pool_balance = get_liquid_balance(&env);
+        let hedge_value = get_hedge_state(&env).mark_value;
+        let insurance_balance = match config_client.try_insurance_fund() {
+            Ok(Ok(insurance_fund)) => token_client.balance(&insurance_fund),
+            _ => 0,
+        };
+        let lp_claims = get_total_deposits(&env);
+        let trader_collateral = get_total_position_collateral(&env) as i128;
+        let trader_profit_bound = get_reserved_liquidity(&env) as i128;
+
+        let assets = pool_balance + hedge_value + insurance_balance;
+        let liabilities = lp_claims + trader_collateral + trader_profit_bound;
+        let ratio_bps = if liabilities == 0 {
+            10000
+        } else {
+            mul_div(assets, 10000, liabilities, Rounding::Floor)
+        };
+
+        Solvency {
+            pool_balance,
+            hedge_value,
+            insurance_balance,
+            lp_claims,
+            trader_collateral,
+            trader_profit_bound,
+            assets,
+            liabilities,
+            ratio_bps,
+        }
+    }
+
     /// Get the collateral deposited for a specific position.
     ///
     /// # Arguments
@@ -545,8 +2932,13 @@ impl LiquidityPool {
 
         // Transfer collateral from trader to pool
         let token = get_token(&env);
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&trader, &env.current_contract_address(), &(amount as i128));
+        move_tokens(
+            &env,
+            &token,
+            &trader,
+            &env.current_contract_address(),
+            amount as i128,
+        );
 
         // Track collateral for this position
         let current = get_position_collateral(&env, position_id);
@@ -602,7 +2994,7 @@ impl LiquidityPool {
 
         let current = get_position_collateral(&env, position_id);
         if amount > current {
-            panic!("insufficient position collateral");
+            panic_with_error!(&env, PoolError::InsufficientPositionCollateral);
         }
 
         // Update or delete collateral tracking
@@ -613,14 +3005,215 @@ impl LiquidityPool {
             put_position_collateral(&env, position_id, remaining);
         }
 
-        // Transfer collateral from pool to trader
-        let token = get_token(&env);
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &trader, &(amount as i128));
+        // Transfer collateral from pool to trader. Collateral forfeited to the
+        // pool (losses, liquidation remainders) only leaves position tracking.
+        if trader != env.current_contract_address() {
+            let token = get_token(&env);
+            move_tokens(
+                &env,
+                &token,
+                &env.current_contract_address(),
+                &trader,
+                amount as i128,
+            );
+        }
+    }
+
+    /// Pay out what is left of a position's collateral after a loss and stop tracking
+    /// it; the lost part stays in the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_manager` - The Position Manager contract address
+    /// * `position_id` - The position ID
+    /// * `trader` - The trader's address
+    /// * `amount` - The collateral returned to the trader
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the authorized position manager or `amount` exceeds the
+    /// position's collateral
+    pub fn settle_position_collateral(
+        env: Env,
+        position_manager: Address,
+        position_id: u64,
+        trader: Address,
+        amount: u128,
+    ) {
+        require_position_manager(&env, &position_manager);
+
+        if amount > get_position_collateral(&env, position_id) {
+            panic_with_error!(&env, PoolError::InsufficientPositionCollateral);
+        }
+        delete_position_collateral(&env, position_id);
+
+        if amount > 0 {
+            move_tokens(
+                &env,
+                &get_token(&env),
+                &env.current_contract_address(),
+                &trader,
+                amount as i128,
+            );
+        }
+    }
+
+    /// Park limit-order collateral that was already transferred to the pool. It is
+    /// minted as the OrderManager's LP shares and tracked as escrow units of the order.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_manager` - The Order Manager contract address
+    /// * `order_id` - The order whose collateral is parked
+    /// * `amount` - The collateral amount transferred
+    ///
+    /// # Returns
+    ///
+    /// The escrow units credited to the order
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the registered order manager, the pool is not active,
+    /// the amount is not positive or the order's escrow is already parked
+    pub fn record_order_escrow(
+        env: Env,
+        order_manager: Address,
+        order_id: u64,
+        amount: i128,
+    ) -> i128 {
+        require_order_manager(&env, &order_manager);
+        require_trading_phase(&env);
+        if amount <= 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+        if get_order_escrow_units(&env, order_id) > 0 {
+            panic_with_error!(&env, PoolError::EscrowAlreadyParked);
+        }
+
+        // Compound the escrow's fees first so units are priced against current shares
+        update_shares(&env, &order_manager, 0);
+        let held = get_shares(&env, &order_manager);
+        let total_units = get_total_order_escrow_units(&env);
+
+        let shares = shares_for_received(&env, amount);
+        let units = if total_units == 0 || held == 0 {
+            shares
+        } else {
+            mul_div(shares, total_units, held, Rounding::Floor)
+        };
+        if units <= 0 {
+            panic_with_error!(&env, PoolError::EscrowTooSmall);
+        }
+
+        mint_shares(&env, &order_manager, shares);
+        put_total_deposits(&env, get_total_deposits(&env) + amount);
+        env.storage()
+            .persistent()
+            .set(&DataKey::OrderEscrowUnits(order_id), &units);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalOrderEscrowUnits, &(total_units + units));
+
+        units
+    }
+
+    /// Redeem an order's parked escrow at its current value. Escrow is owed back at
+    /// execution or cancellation, so it skips the LP withdrawal reserve checks and the
+    /// paused withdrawal throttle.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_manager` - The Order Manager contract address
+    /// * `order_id` - The order whose escrow is redeemed
+    /// * `recipient` - Receives the tokens (OrderManager at execution, trader on refund)
+    ///
+    /// # Returns
+    ///
+    /// The tokens paid to the recipient
+    ///
+    /// # Panics
+    ///
+    /// Panics if caller is not the registered order manager or the order has no
+    /// parked escrow
+    pub fn redeem_order_escrow(
+        env: Env,
+        order_manager: Address,
+        order_id: u64,
+        recipient: Address,
+    ) -> i128 {
+        require_order_manager(&env, &order_manager);
+        let units = get_order_escrow_units(&env, order_id);
+        if units == 0 {
+            panic_with_error!(&env, PoolError::NoParkedEscrow);
+        }
+
+        update_shares(&env, &order_manager, 0);
+        let shares = order_escrow_shares(&env, &order_manager, units);
+        let total_shares = get_total_shares(&env);
+        let total_deposits = get_total_deposits(&env);
+        let pool_value = get_pool_value(&env);
+        let tokens = mul_div(shares, pool_value, total_shares, Rounding::Floor);
+
+        burn_shares(&env, &order_manager, shares);
+        put_total_deposits(
+            &env,
+            total_deposits - mul_div(shares, total_deposits, total_shares, Rounding::Floor),
+        );
+        env.storage()
+            .persistent()
+            .remove(&DataKey::OrderEscrowUnits(order_id));
+        env.storage().instance().set(
+            &DataKey::TotalOrderEscrowUnits,
+            &(get_total_order_escrow_units(&env) - units),
+        );
+
+        if tokens > 0 {
+            move_tokens(
+                &env,
+                &get_token(&env),
+                &env.current_contract_address(),
+                &recipient,
+                tokens,
+            );
+        }
+        tokens
+    }
+
+    /// Get the current token value of an order's parked escrow.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - The order identifier
+    ///
+    /// # Returns
+    ///
+    /// The tokens the escrow would redeem for now (0 if not parked)
+    pub fn get_order_escrow_value(env: Env, order_id: u64) -> i128 {
+        let units = get_order_escrow_units(&env, order_id);
+        if units == 0 {
+            return 0;
+        }
+
+        // Include fees the escrow would compound on redemption
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let order_manager = config_client.order_manager();
+        let mut account = get_lp_fee_account(&env, &order_manager);
+        let held = get_shares(&env, &order_manager);
+        checkpoint_fees(&env, &mut account, held);
+        let pending = account.pending.max(0);
+        let total_shares = get_total_shares(&env);
+        let pool_value = get_pool_value(&env);
+
+        let total_units = get_total_order_escrow_units(&env);
+        let escrow_total = mul_div(held, pool_value, total_shares, Rounding::Floor) + pending;
+        mul_div(escrow_total, units, total_units, Rounding::Floor)
     }
 
     /// Settle trader PnL by transferring profit from pool reserves.
     ///
+    /// Profit is paid from unreserved liquidity left after earlier payout claims. Any
+    /// excess is queued as a payout claim behind them, to be paid by `settle_claims()`.
+    ///
     /// # Arguments
     ///
     /// * `position_manager` - The Position Manager contract address
@@ -638,10 +3231,150 @@ impl LiquidityPool {
             return;
         }
 
+        let available = get_liquid_balance(&env) - get_reserved_liquidity(&env) as i128;
+        let paid = pnl.min(available.max(0));
+
         // Transfer profit from pool to trader
+        if paid > 0 {
+            let token = get_token(&env);
+            move_tokens(&env, &token, &env.current_contract_address(), &trader, paid);
+        }
+
+        if paid < pnl {
+            let deferred = pnl - paid;
+            let claim_id = defer_payout(&env, &trader, deferred);
+            PayoutDeferredEvent {
+                trader,
+                claim_id,
+                paid,
+                deferred,
+                seq: next_event_seq(&env),
+            }
+            .publish(&env);
+        }
+
+        check_drawdown(&env);
+    }
+
+    /// Pay queued payout claims, oldest first, from unreserved liquidity. The oldest
+    /// claim is paid in part if liquidity runs out, and later claims wait for it.
+    /// Permissionless, so keepers or claimants can settle as liquidity frees up.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_claims` - Most claims to pay in this call (1 to 20)
+    ///
+    /// # Returns
+    ///
+    /// The total amount paid out
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_claims` is 0 or larger than 20
+    pub fn settle_claims(env: Env, max_claims: u32) -> i128 {
+        validate_batch_size(&env, max_claims);
+
         let token = get_token(&env);
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &trader, &pnl);
+        let mut outstanding = get_outstanding_claims(&env);
+        // Owed claims are excluded from the liquid balance, so add them back to pay them
+        let mut available =
+            get_liquid_balance(&env) + outstanding - get_reserved_liquidity(&env) as i128;
+        let mut head = get_claim_head(&env);
+        let next_id = get_next_claim_id(&env);
+        let mut total_paid = 0;
+
+        for _ in 0..max_claims {
+            if head >= next_id || available <= 0 {
+                break;
+            }
+            let mut claim = get_payout_claim(&env, head).unwrap();
+            let amount = claim.amount.min(available);
+            move_tokens(
+                &env,
+                &token,
+                &env.current_contract_address(),
+                &claim.trader,
+                amount,
+            );
+
+            claim.amount -= amount;
+            available -= amount;
+            outstanding -= amount;
+            total_paid += amount;
+            ClaimSettledEvent {
+                trader: claim.trader.clone(),
+                claim_id: head,
+                amount,
+                remaining: claim.amount,
+                seq: next_event_seq(&env),
+            }
+            .publish(&env);
+
+            if claim.amount > 0 {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::PayoutClaim(head), &claim);
+                break;
+            }
+            env.storage()
+                .persistent()
+                .remove(&DataKey::PayoutClaim(head));
+            head += 1;
+        }
+
+        env.storage().instance().set(&DataKey::ClaimHead, &head);
+        env.storage()
+            .instance()
+            .set(&DataKey::OutstandingClaims, &outstanding);
+        total_paid
+    }
+
+    /// Get a payout claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `claim_id` - The claim ID
+    ///
+    /// # Returns
+    ///
+    /// The claim, or None if it was settled in full or never existed
+    pub fn get_claim(env: Env, claim_id: u64) -> Option<PayoutClaim> {
+        get_payout_claim(&env, claim_id)
+    }
+
+    /// List unsettled payout claims in the order they will be paid.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Most claims to return (1 to 20)
+    ///
+    /// # Returns
+    ///
+    /// Up to `limit` claims, oldest first
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0 or larger than 20
+    pub fn get_pending_claims(env: Env, limit: u32) -> Vec<PayoutClaim> {
+        validate_batch_size(&env, limit);
+
+        let mut claims = Vec::new(&env);
+        let next_id = get_next_claim_id(&env);
+        let mut claim_id = get_claim_head(&env);
+        while claim_id < next_id && claims.len() < limit {
+            claims.push_back(get_payout_claim(&env, claim_id).unwrap());
+            claim_id += 1;
+        }
+        claims
+    }
+
+    /// Get the total profit owed on unsettled payout claims.
+    ///
+    /// # Returns
+    ///
+    /// The outstanding amount, excluded from pool value until paid
+    pub fn get_outstanding_claims(env: Env) -> i128 {
+        get_outstanding_claims(&env)
     }
 }
 
@@ -1,7 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, token, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Events as _, Ledger},
+    symbol_short, token, Address, Env, Event as _,
+};
 
 fn create_token_contract<'a>(
     env: &Env,
@@ -22,12 +25,12 @@ mod config_manager {
 
 fn create_mock_config_manager(env: &Env, admin: &Address) -> Address {
     // Deploy actual ConfigManager contract for tests
-    let contract_id = env.register(config_manager::WASM, ());
+    let contract_id = env.register(
+        config_manager::WASM,
+        (admin, config_manager::NetworkProfile::Testnet),
+    );
     let client = config_manager::Client::new(env, &contract_id);
 
-    // Initialize with admin (admin must authorize)
-    client.initialize(admin);
-
     // Set minimum liquidity reserve ratio (e.g., 10% = 1000 bps)
     client.set_min_liquidity_reserve_ratio(admin, &1000);
 
@@ -52,12 +55,9 @@ fn test_deposit_and_withdraw_happy_path() {
     let config_manager_id = create_mock_config_manager(&env, &admin);
 
     // Deploy liquidity pool contract
-    let contract_id = env.register(LiquidityPool, ());
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
     let client = LiquidityPoolClient::new(&env, &contract_id);
 
-    // Initialize the pool with admin, config manager and token
-    client.initialize(&admin, &config_manager_id, &token_client.address);
-
     // Test deposit
     let shares = client.deposit(&user1, &500);
 
@@ -85,6 +85,101 @@ fn test_deposit_and_withdraw_happy_path() {
     assert_eq!(token_client.balance(&contract_id), 250);
 }
 
+#[test]
+fn test_deposit_publishes_token_moved_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&user, &1000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+
+    // The transfer is the first event of the deposit
+    let seq = config_client.get_event_seq() + 1;
+    client.deposit(&user, &500);
+
+    let moved = TokenMovedEvent {
+        token: token_client.address.clone(),
+        from: user,
+        to: contract_id.clone(),
+        amount: 500,
+        seq,
+    };
+    assert!(env
+        .events()
+        .all()
+        .contains((contract_id, moved.topics(&env), moved.data(&env))));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #313)")]
+fn test_move_tokens_refuses_negative_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let (token_client, _) = create_token_contract(&env, &admin);
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+
+    env.as_contract(&contract_id, || {
+        move_tokens(&env, &token_client.address, &contract_id, &user, -1)
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #313)")]
+fn test_move_tokens_refuses_self_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    token_admin.mint(&contract_id, &1000);
+
+    env.as_contract(&contract_id, || {
+        move_tokens(&env, &token_client.address, &contract_id, &contract_id, 100)
+    });
+}
+
+#[test]
+fn test_share_math_at_extreme_tvl() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+
+    // amount * total_shares is 1e60, far beyond i128, at a 1e30 token pool
+    let e30 = 1_000_000_000_000_000_000_000_000_000_000i128;
+    token_admin.mint(&user1, &e30);
+    token_admin.mint(&user2, &(e30 + 1));
+    client.deposit(&user1, &e30);
+    assert_eq!(client.deposit(&user2, &e30), e30);
+
+    // A one-token gain splits evenly; the odd unit rounds down in favor of the pool
+    token_admin.mint(&contract_id, &1);
+    assert_eq!(client.withdraw(&user2, &e30), e30);
+    assert_eq!(client.get_total_shares(), e30);
+    assert_eq!(client.get_total_deposits(), e30);
+    assert_eq!(token_client.balance(&contract_id), e30 + 1);
+}
+
 #[test]
 fn test_multiple_deposits() {
     let env = Env::default();
@@ -105,12 +200,9 @@ fn test_multiple_deposits() {
     let config_manager_id = create_mock_config_manager(&env, &admin);
 
     // Deploy liquidity pool contract
-    let contract_id = env.register(LiquidityPool, ());
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
     let client = LiquidityPoolClient::new(&env, &contract_id);
 
-    // Initialize the pool with admin, config manager and token
-    client.initialize(&admin, &config_manager_id, &token_client.address);
-
     // User1 deposits 500 tokens
     let shares1 = client.deposit(&user1, &500);
     assert_eq!(shares1, 500); // 1:1 ratio for first deposit
@@ -148,12 +240,9 @@ fn test_varying_deposit_sizes() {
     let config_manager_id = create_mock_config_manager(&env, &admin);
 
     // Deploy liquidity pool contract
-    let contract_id = env.register(LiquidityPool, ());
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
     let client = LiquidityPoolClient::new(&env, &contract_id);
 
-    // Initialize the pool with admin, config manager and token
-    client.initialize(&admin, &config_manager_id, &token_client.address);
-
     // User1 deposits 1000 tokens (first deposit, 1:1 ratio)
     let shares1 = client.deposit(&user1, &1000);
     assert_eq!(shares1, 1000);
@@ -213,12 +302,9 @@ fn test_extreme_values() {
     let config_manager_id = create_mock_config_manager(&env, &admin);
 
     // Deploy liquidity pool contract
-    let contract_id = env.register(LiquidityPool, ());
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
     let client = LiquidityPoolClient::new(&env, &contract_id);
 
-    // Initialize the pool with admin, config manager and token
-    client.initialize(&admin, &config_manager_id, &token_client.address);
-
     // Test with very large initial deposit
     let large_deposit = 1_000_000_000;
     let shares1 = client.deposit(&user1, &large_deposit);
@@ -234,3 +320,1065 @@ fn test_extreme_values() {
     let withdrawn = client.withdraw(&user2, &1);
     assert_eq!(withdrawn, 1);
 }
+
+#[test]
+fn test_share_math_rounds_in_favor_of_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&user1, &3);
+    token_admin.mint(&user2, &2);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+
+    // 3 shares backed by 4 tokens after a 1 token trading gain
+    client.deposit(&user1, &3);
+    token_admin.mint(&contract_id, &1);
+
+    // 2 tokens are worth 1.5 shares: the depositor gets 1
+    assert_eq!(client.deposit(&user2, &2), 1);
+    assert_eq!(client.get_total_shares(), 4);
+
+    // 1 share of a 6 token pool is worth 1.5 tokens: the withdrawer gets 1
+    assert_eq!(client.withdraw(&user2, &1), 1);
+    assert_eq!(token_client.balance(&contract_id), 5);
+}
+
+fn setup_hedged_pool(env: &Env) -> (LiquidityPoolClient<'_>, token::Client<'_>, Address, Address) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let lp = Address::generate(env);
+    let hedger = Address::generate(env);
+
+    let (token_client, token_admin) = create_token_contract(env, &admin);
+    token_admin.mint(&lp, &10_000);
+    token_admin.mint(&hedger, &1_000);
+
+    let config_manager_id = create_mock_config_manager(env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(env, &contract_id);
+    client.deposit(&lp, &10_000);
+
+    // Hedger may borrow up to 20% of pool value
+    client.set_hedger(&admin, &Some(hedger.clone()), &2000u32);
+
+    (client, token_client, lp, hedger)
+}
+
+#[test]
+fn test_hedge_borrow_report_and_repay() {
+    let env = Env::default();
+    let (client, token_client, lp, hedger) = setup_hedged_pool(&env);
+
+    assert_eq!(client.hedge_borrow(&hedger, &1_500u128), 1_500);
+    assert_eq!(token_client.balance(&client.address), 8_500);
+
+    // Borrowed tokens still count towards LP share value
+    let state = client.get_hedge_state();
+    assert_eq!(state.borrowed, 1_500);
+    assert_eq!(state.mark_value, 1_500);
+
+    // Hedge gains 10%: LPs own the gain before it is repaid
+    client.report_hedge_value(&hedger, &1_650);
+    assert_eq!(client.get_hedge_state().mark_value, 1_650);
+
+    // Repaying principal plus profit clears the debt
+    assert_eq!(client.hedge_repay(&hedger, &1_650u128), 0);
+    let state = client.get_hedge_state();
+    assert_eq!(state.mark_value, 0);
+    assert_eq!(token_client.balance(&client.address), 10_150);
+
+    assert_eq!(client.withdraw(&lp, &10_000), 10_150);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #331)")]
+fn test_hedge_borrow_cap() {
+    let env = Env::default();
+    let (client, _token_client, _lp, hedger) = setup_hedged_pool(&env);
+
+    client.hedge_borrow(&hedger, &1_500u128);
+    client.hedge_borrow(&hedger, &501u128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #329)")]
+fn test_hedge_borrow_not_hedger() {
+    let env = Env::default();
+    let (client, _token_client, lp, _hedger) = setup_hedged_pool(&env);
+
+    client.hedge_borrow(&lp, &100u128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #304)")]
+fn test_blocked_account_cannot_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &10_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.deposit(&lp, &10_000);
+
+    // Blocking only takes effect once compliance is enabled
+    config_client.set_blocked(&admin, &lp, &true);
+    client.withdraw(&lp, &1_000);
+    config_client.set_compliance_enabled(&admin, &true);
+    client.withdraw(&lp, &1_000);
+}
+
+#[test]
+fn test_deposit_and_withdraw_many() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let sub1 = Address::generate(&env);
+    let sub2 = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&sub1, &1_000);
+    token_admin.mint(&sub2, &1_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+
+    let deposits = soroban_sdk::vec![&env, (sub1.clone(), 600i128), (sub2.clone(), 300i128)];
+    let minted = client.deposit_many(&deposits);
+    assert_eq!(minted, soroban_sdk::vec![&env, 600i128, 300i128]);
+
+    // Each sub-account authorized its own deposit
+    let auths = env.auths();
+    assert!(auths.iter().any(|(address, _)| *address == sub1));
+    assert!(auths.iter().any(|(address, _)| *address == sub2));
+
+    let withdrawals = soroban_sdk::vec![&env, (sub1.clone(), 100i128), (sub2.clone(), 300i128)];
+    let returned = client.withdraw_many(&withdrawals);
+    assert_eq!(returned, soroban_sdk::vec![&env, 100i128, 300i128]);
+    assert_eq!(client.get_shares(&sub1), 500);
+    assert_eq!(client.get_shares(&sub2), 0);
+    assert_eq!(token_client.balance(&sub2), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #316)")]
+fn test_deposit_many_empty_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (token_client, _token_admin) = create_token_contract(&env, &admin);
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+
+    client.deposit_many(&soroban_sdk::Vec::new(&env));
+}
+
+#[test]
+fn test_paused_withdrawals_are_throttled_per_interval() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &20_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.deposit(&lp, &10_000);
+
+    // Default policy: 10% of pool value per day while paused
+    config_client.set_global_pause(&admin, &true);
+    assert_eq!(client.get_paused_withdrawal_remaining(), 1_000);
+
+    client.withdraw(&lp, &600);
+    assert_eq!(client.get_paused_withdrawal_remaining(), 400);
+    assert!(client.try_withdraw(&lp, &500).is_err());
+    client.withdraw(&lp, &400);
+    assert_eq!(client.get_paused_withdrawal_remaining(), 0);
+
+    // Deposits stay open and do not raise the current window's cap
+    client.deposit(&lp, &1_000);
+    assert!(client.try_withdraw(&lp, &1).is_err());
+
+    // A new window is sized against the pool value when it opens
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(client.get_paused_withdrawal_remaining(), 1_000);
+    client.withdraw(&lp, &1_000);
+
+    // Unpausing lifts the throttle
+    config_client.set_global_pause(&admin, &false);
+    client.withdraw(&lp, &5_000);
+    assert_eq!(client.get_shares(&lp), 4_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #308)")]
+fn test_paused_withdrawal_over_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &10_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.deposit(&lp, &10_000);
+
+    config_client.set_paused_withdrawal_limit(&admin, &500, &3600);
+    config_client.set_global_pause(&admin, &true);
+    client.withdraw(&lp, &501);
+}
+
+#[test]
+fn test_lp_operation_pauses() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &10_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.deposit(&lp, &5_000);
+
+    // Paused deposits leave withdrawals open
+    config_client.set_operation_paused(&admin, &config_manager::PauseOp::LpDeposits, &true);
+    assert!(client.try_deposit(&lp, &1_000).is_err());
+    client.withdraw(&lp, &1_000);
+
+    // And the other way around
+    config_client.set_operation_paused(&admin, &config_manager::PauseOp::LpDeposits, &false);
+    config_client.set_operation_paused(&admin, &config_manager::PauseOp::LpWithdrawals, &true);
+    client.deposit(&lp, &1_000);
+    assert!(client.try_withdraw(&lp, &1_000).is_err());
+    assert_eq!(client.get_shares(&lp), 5_000);
+}
+
+/// Pool with two 10,000 token LPs and a stand-in position manager that reports fees
+fn setup_fee_pool(
+    env: &Env,
+) -> (
+    LiquidityPoolClient<'_>,
+    token::StellarAssetClient<'_>,
+    Address,
+    Address,
+    Address,
+) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let position_manager = Address::generate(env);
+    let compounder = Address::generate(env);
+    let claimer = Address::generate(env);
+
+    let (token_client, token_admin) = create_token_contract(env, &admin);
+    token_admin.mint(&compounder, &10_000);
+    token_admin.mint(&claimer, &10_000);
+
+    let config_manager_id = create_mock_config_manager(env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.deposit(&compounder, &10_000);
+    client.deposit(&claimer, &10_000);
+
+    (client, token_admin, position_manager, compounder, claimer)
+}
+
+#[test]
+fn test_fees_compound_or_claim_per_lp() {
+    let env = Env::default();
+    let (client, token_admin, position_manager, compounder, claimer) = setup_fee_pool(&env);
+    let token_client = token::Client::new(&env, &client.token());
+
+    client.set_fee_mode(&claimer, &true);
+    assert!(client.get_lp_fee_account(&claimer).claim_in_kind);
+
+    // 1,000 tokens of fees stay in the pool and are credited 500 to each LP
+    token_admin.mint(&client.address, &1_000);
+    client.accrue_fees(&position_manager, &1_000);
+    assert_eq!(client.get_lp_fee_account(&compounder).pending, 500);
+    assert_eq!(client.get_lp_fee_account(&claimer).pending, 500);
+
+    // Until compounded or claimed, accrued fees don't move the share price
+    assert_eq!(client.get_available_liquidity(), 20_000);
+
+    // The claimer takes fees in kind and keeps their principal
+    assert_eq!(client.claim_fees(&claimer), 500);
+    assert_eq!(token_client.balance(&claimer), 500);
+    assert_eq!(client.get_shares(&claimer), 10_000);
+
+    // The compounder's fees become shares
+    assert_eq!(client.compound_fees(&compounder), 10_500);
+    assert_eq!(client.get_lp_fee_account(&compounder).pending, 0);
+
+    assert_eq!(client.withdraw(&compounder, &10_500), 10_500);
+    assert_eq!(client.withdraw(&claimer, &5_000), 5_000);
+}
+
+#[test]
+fn test_withdraw_all_burns_compounded_fees() {
+    let env = Env::default();
+    let (client, token_admin, position_manager, compounder, claimer) = setup_fee_pool(&env);
+    let token_client = token::Client::new(&env, &client.token());
+
+    // 500 tokens of fees are pending for the compounder, not yet shares
+    token_admin.mint(&client.address, &1_000);
+    client.accrue_fees(&position_manager, &1_000);
+    assert_eq!(client.get_shares(&compounder), 10_000);
+
+    // Withdrawing the visible balance would leave the compounded fees as a stub
+    let amount = client.withdraw_all(&compounder);
+    let events = env.events().all();
+    let expected = WithdrewAllEvent {
+        user: compounder.clone(),
+        shares: 10_500,
+        amount,
+        seq: config_manager::Client::new(&env, &client.config_manager()).get_event_seq(),
+    };
+    let expected = (
+        client.address.clone(),
+        expected.topics(&env),
+        expected.data(&env),
+    );
+    assert!(events.contains(&expected));
+    assert_eq!(amount, 10_500);
+    assert_eq!(client.get_shares(&compounder), 0);
+    assert_eq!(client.get_lp_fee_account(&compounder).pending, 0);
+    assert_eq!(token_client.balance(&compounder), amount);
+
+    // The last LP takes the rest of the pool, rounding dust included
+    let last = client.withdraw_all(&claimer);
+    assert_eq!(client.get_total_shares(), 0);
+    assert_eq!(amount + last, 21_000);
+    assert!(client.try_withdraw_all(&claimer).is_err());
+}
+
+#[test]
+fn test_switching_to_compound_reinvests_pending_fees() {
+    let env = Env::default();
+    let (client, token_admin, position_manager, _compounder, claimer) = setup_fee_pool(&env);
+
+    client.set_fee_mode(&claimer, &true);
+    token_admin.mint(&client.address, &1_000);
+    client.accrue_fees(&position_manager, &1_000);
+
+    client.set_fee_mode(&claimer, &false);
+    assert_eq!(client.get_shares(&claimer), 10_500);
+    assert_eq!(client.get_lp_fee_account(&claimer).pending, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #326)")]
+fn test_claim_fees_when_compounding() {
+    let env = Env::default();
+    let (client, token_admin, position_manager, compounder, _claimer) = setup_fee_pool(&env);
+
+    token_admin.mint(&client.address, &1_000);
+    client.accrue_fees(&position_manager, &1_000);
+    client.claim_fees(&compounder);
+}
+
+#[test]
+fn test_frontend_fees_held_apart_until_claimed() {
+    let env = Env::default();
+    let (client, token_admin, position_manager, _compounder, _claimer) = setup_fee_pool(&env);
+    let token_client = token::Client::new(&env, &client.token());
+    let frontend = Address::generate(&env);
+
+    token_admin.mint(&client.address, &300);
+    client.accrue_frontend_fees(&position_manager, &frontend, &300);
+    assert_eq!(client.get_frontend_fees(&frontend), 300);
+
+    // The frontend's share is not LP value
+    assert_eq!(client.get_available_liquidity(), 20_000);
+
+    assert_eq!(client.claim_frontend_fees(&frontend), 300);
+    assert_eq!(token_client.balance(&frontend), 300);
+    assert_eq!(client.get_frontend_fees(&frontend), 0);
+    assert!(client.try_claim_frontend_fees(&frontend).is_err());
+}
+
+/// Register an order manager in the pool's ConfigManager
+fn register_order_manager(env: &Env, client: &LiquidityPoolClient) -> Address {
+    let config_client = config_manager::Client::new(env, &client.config_manager());
+    let order_manager = Address::generate(env);
+    config_client.set_order_manager(&config_client.admin(), &order_manager);
+    order_manager
+}
+
+#[test]
+fn test_order_escrow_units_track_share_value() {
+    let env = Env::default();
+    let (client, token_admin, _position_manager, _compounder, _claimer) = setup_fee_pool(&env);
+    let token_client = token::Client::new(&env, &client.token());
+    let trader = Address::generate(&env);
+    let order_manager = register_order_manager(&env, &client);
+
+    // The order manager transfers collateral in, then records it against the order
+    token_admin.mint(&client.address, &1_000);
+    assert_eq!(
+        client.record_order_escrow(&order_manager, &1, &1_000),
+        1_000
+    );
+    assert_eq!(client.get_shares(&order_manager), 1_000);
+
+    // 2,100 tokens of trader losses lift the share price to 1.1
+    token_admin.mint(&client.address, &2_100);
+    assert_eq!(client.get_order_escrow_value(&1), 1_100);
+
+    // A later order buys in at the new price and gets the same units per token of value
+    token_admin.mint(&client.address, &1_100);
+    assert_eq!(
+        client.record_order_escrow(&order_manager, &2, &1_100),
+        1_000
+    );
+
+    assert_eq!(
+        client.redeem_order_escrow(&order_manager, &1, &trader),
+        1_100
+    );
+    assert_eq!(
+        client.redeem_order_escrow(&order_manager, &2, &trader),
+        1_100
+    );
+    assert_eq!(token_client.balance(&trader), 2_200);
+    assert_eq!(client.get_shares(&order_manager), 0);
+    assert_eq!(client.get_order_escrow_value(&1), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #343)")]
+fn test_redeem_order_escrow_not_parked() {
+    let env = Env::default();
+    let (client, _token_admin, _position_manager, compounder, _claimer) = setup_fee_pool(&env);
+    let order_manager = register_order_manager(&env, &client);
+
+    client.redeem_order_escrow(&order_manager, &1, &compounder);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #312)")]
+fn test_record_order_escrow_requires_order_manager() {
+    let env = Env::default();
+    let (client, token_admin, position_manager, _compounder, _claimer) = setup_fee_pool(&env);
+    register_order_manager(&env, &client);
+
+    // The position manager no longer holds order escrow
+    token_admin.mint(&client.address, &1_000);
+    client.record_order_escrow(&position_manager, &1, &1_000);
+}
+
+#[test]
+fn test_solvency_counts_insurance_against_trader_claims() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+    let insurance_fund = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &20_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+
+    // Nothing owed yet
+    assert_eq!(client.get_solvency().ratio_bps, 10000);
+
+    client.deposit(&lp, &20_000);
+    token_admin.mint(&contract_id, &1_000);
+    client.record_position_collateral(&position_manager, &1, &1_000u128);
+    client.reserve_liquidity(&position_manager, &1, &5_000u128, &1_000u128);
+
+    // 21,000 held against 20,000 LP principal, 1,000 collateral and 5,000 of profit bound
+    let solvency = client.get_solvency();
+    assert_eq!(solvency.pool_balance, 21_000);
+    assert_eq!(solvency.insurance_balance, 0);
+    assert_eq!(solvency.lp_claims, 20_000);
+    assert_eq!(solvency.trader_collateral, 1_000);
+    assert_eq!(solvency.trader_profit_bound, 5_000);
+    assert_eq!(solvency.liabilities, 26_000);
+    assert_eq!(solvency.ratio_bps, 8076);
+
+    // The insurance fund's balance covers the gap
+    config_manager::Client::new(&env, &config_manager_id)
+        .set_insurance_fund(&admin, &insurance_fund);
+    token_admin.mint(&insurance_fund, &5_000);
+    let solvency = client.get_solvency();
+    assert_eq!(solvency.insurance_balance, 5_000);
+    assert_eq!(solvency.assets, 26_000);
+    assert_eq!(solvency.ratio_bps, 10000);
+
+    // Closing at a loss pays out the rest and drops the collateral from the liabilities
+    client.release_liquidity(&position_manager, &1, &5_000u128);
+    client.settle_position_collateral(&position_manager, &1, &trader, &400u128);
+    assert_eq!(token_client.balance(&trader), 400);
+    assert_eq!(client.get_position_collateral(&1), 0);
+    let solvency = client.get_solvency();
+    assert_eq!(solvency.trader_collateral, 0);
+    assert_eq!(solvency.liabilities, 20_000);
+}
+
+#[test]
+fn test_share_metadata_and_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &20_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+
+    assert_eq!(client.name(), String::from_str(&env, "Stellars Finance LP"));
+    assert_eq!(client.symbol(), String::from_str(&env, "sfLP"));
+    assert_eq!(client.decimals(), token_client.decimals());
+
+    // 1.0 before the first deposit and after it
+    assert_eq!(client.share_price(), 10_000_000);
+    client.deposit(&lp, &8_000);
+    assert_eq!(client.share_price(), 10_000_000);
+
+    // Trader losses paid into the pool raise the price by 25%
+    token_admin.mint(&contract_id, &2_000);
+    assert_eq!(client.share_price(), 12_500_000);
+    assert_eq!(client.withdraw(&lp, &4_000), 5_000);
+    assert_eq!(client.share_price(), 12_500_000);
+}
+
+#[test]
+fn test_pool_phase_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &20_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    assert_eq!(client.get_phase(), PoolPhase::Active);
+
+    // Bootstrapping: deposits only, and no activation before the seed target
+    client.begin_bootstrapping(&admin, &10_000);
+    assert_eq!(client.get_phase(), PoolPhase::Bootstrapping);
+    client.deposit(&lp, &6_000);
+    assert!(client.try_withdraw(&lp, &1_000).is_err());
+    assert!(client
+        .try_reserve_liquidity(&position_manager, &1, &1_000u128, &100u128)
+        .is_err());
+    assert!(client.try_activate(&admin).is_err());
+
+    client.deposit(&lp, &4_000);
+    client.activate(&admin);
+    assert_eq!(client.get_phase(), PoolPhase::Active);
+    assert!(client.try_begin_bootstrapping(&admin, &10_000).is_err());
+    client.reserve_liquidity(&position_manager, &1, &1_000u128, &100u128);
+
+    // Wind down: no new positions or deposits, withdrawals wait for open interest to close
+    client.begin_wind_down(&admin);
+    assert!(client
+        .try_reserve_liquidity(&position_manager, &2, &1_000u128, &100u128)
+        .is_err());
+    assert!(client.try_deposit(&lp, &1_000).is_err());
+    assert!(client.try_withdraw(&lp, &1_000).is_err());
+
+    client.release_liquidity(&position_manager, &1, &1_000u128);
+    client.withdraw(&lp, &1_000);
+    assert_eq!(client.get_shares(&lp), 9_000);
+    assert!(client.try_activate(&admin).is_err());
+}
+
+#[test]
+fn test_terminal_phase_emergency_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp1 = Address::generate(&env);
+    let lp2 = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp1, &6_000);
+    token_admin.mint(&lp2, &4_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.deposit(&lp1, &6_000);
+    client.deposit(&lp2, &4_000);
+    client.reserve_liquidity(&position_manager, &1, &1_000u128, &0u128);
+
+    // Only a wound-down pool with no open interest can become terminal
+    assert!(client.try_enter_terminal_phase().is_err());
+    client.begin_wind_down(&admin);
+    assert!(client.try_enter_terminal_phase().is_err());
+    assert!(client.try_emergency_withdraw(&lp1, &1_000).is_err());
+    client.release_liquidity(&position_manager, &1, &1_000u128);
+    client.enter_terminal_phase();
+    assert_eq!(client.get_phase(), PoolPhase::Terminal);
+    assert!(client.try_enter_terminal_phase().is_err());
+    assert!(client.try_deposit(&lp1, &1_000).is_err());
+
+    // Trader losses left in the pool are shared pro-rata
+    token_admin.mint(&contract_id, &1_000);
+
+    // ConfigManager pausing withdrawals does not block the exit of last resort
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    config_client.set_operation_paused(&admin, &config_manager::PauseOp::LpWithdrawals, &true);
+    assert!(client.try_withdraw(&lp1, &1_000).is_err());
+
+    assert!(client.try_emergency_withdraw(&lp1, &6_001).is_err());
+    assert!(client.try_emergency_withdraw(&lp1, &0).is_err());
+    assert_eq!(client.emergency_withdraw(&lp1, &6_000), 6_600);
+    assert_eq!(client.emergency_withdraw(&lp2, &4_000), 4_400);
+    assert_eq!(token_client.balance(&lp1), 6_600);
+    assert_eq!(token_client.balance(&lp2), 4_400);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(client.get_total_shares(), 0);
+    assert_eq!(client.get_total_deposits(), 0);
+}
+
+#[test]
+fn test_profit_beyond_free_liquidity_becomes_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &10_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+
+    client.deposit(&lp, &10_000);
+    client.reserve_liquidity(&position_manager, &1, &9_000u128, &0u128);
+
+    // Only 1,000 is unreserved: alice is paid that and owed the rest
+    client.settle_trader_pnl(&position_manager, &alice, &3_000);
+    assert_eq!(token_client.balance(&alice), 1_000);
+    assert_eq!(client.get_outstanding_claims(), 2_000);
+    let claim = client.get_claim(&1).unwrap();
+    assert_eq!(claim.trader, alice);
+    assert_eq!(claim.amount, 2_000);
+
+    // Owed profit comes out of pool value, and later payouts queue behind it
+    assert_eq!(client.share_price(), 7_000_000);
+    client.settle_trader_pnl(&position_manager, &bob, &500);
+    assert_eq!(token_client.balance(&bob), 0);
+    assert_eq!(client.get_pending_claims(&20).len(), 2);
+    assert_eq!(client.settle_claims(&20), 0);
+
+    // Freed liquidity pays the oldest claim first, in part if it runs short
+    client.release_liquidity(&position_manager, &1, &1_500u128);
+    assert_eq!(client.settle_claims(&20), 1_500);
+    assert_eq!(token_client.balance(&alice), 2_500);
+    assert_eq!(client.get_claim(&1).unwrap().amount, 500);
+    assert_eq!(token_client.balance(&bob), 0);
+
+    client.release_liquidity(&position_manager, &1, &7_500u128);
+    assert_eq!(client.settle_claims(&20), 1_000);
+    assert_eq!(token_client.balance(&alice), 3_000);
+    assert_eq!(token_client.balance(&bob), 500);
+    assert_eq!(client.get_claim(&1), None);
+    assert_eq!(client.get_pending_claims(&20).len(), 0);
+    assert_eq!(client.get_outstanding_claims(), 0);
+    assert_eq!(client.share_price(), 6_500_000);
+}
+
+#[test]
+fn test_drawdown_breaker_trips_global_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &10_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    config_client.set_liquidity_pool(&admin, &contract_id);
+    client.deposit(&lp, &10_000);
+
+    assert!(client.try_set_drawdown_breaker(&admin, &10001, &3_600).is_err());
+    assert!(client.try_set_drawdown_breaker(&admin, &1000, &0).is_err());
+    client.set_drawdown_breaker(&admin, &1000, &3_600);
+    assert_eq!(client.get_drawdown_breaker().peak_share_price, 10_000_000);
+
+    // A 5% drop stays under the 10% limit
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    client.settle_trader_pnl(&position_manager, &trader, &500);
+    assert!(!config_client.is_globally_paused());
+
+    // Spread over two windows, the drop is still measured from the earlier peak
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    client.settle_trader_pnl(&position_manager, &trader, &600);
+    let events = env.events().all();
+    let expected = DrawdownBreakerTrippedEvent {
+        share_price: 8_900_000,
+        peak_share_price: 10_000_000,
+        drawdown_bps: 1100,
+        seq: config_client.get_event_seq(),
+    };
+    let expected = (
+        contract_id.clone(),
+        expected.topics(&env),
+        expected.data(&env),
+    );
+    assert!(events.contains(&expected));
+    assert!(config_client.is_globally_paused());
+    let state = client.get_drawdown_breaker();
+    assert_eq!(state.tripped_at, env.ledger().timestamp());
+    assert_eq!(state.peak_share_price, 8_900_000);
+
+    // Once re-armed, a drop is measured from the price at reset
+    config_client.set_global_pause(&admin, &false);
+    client.reset_drawdown_breaker(&admin);
+    assert_eq!(client.get_drawdown_breaker().tripped_at, 0);
+    token_admin.mint(&contract_id, &100);
+    client.settle_trader_pnl(&position_manager, &trader, &800);
+    assert!(!client.check_drawdown_breaker());
+    assert!(!config_client.is_globally_paused());
+
+    // Disabled, nothing trips
+    client.set_drawdown_breaker(&admin, &0, &3_600);
+    client.settle_trader_pnl(&position_manager, &trader, &5_000);
+    assert!(!client.check_drawdown_breaker());
+    assert!(!config_client.is_globally_paused());
+}
+
+#[test]
+fn test_drawdown_breaker_waits_for_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &10_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.deposit(&lp, &10_000);
+    client.set_drawdown_breaker(&admin, &1000, &3_600);
+
+    // The pool isn't registered with ConfigManager, so the pause is refused and the
+    // breach is not recorded as a trip
+    let tripped = |seq| {
+        let event = DrawdownBreakerTrippedEvent {
+            share_price: 8_000_000,
+            peak_share_price: 10_000_000,
+            drawdown_bps: 2000,
+            seq,
+        };
+        (contract_id.clone(), event.topics(&env), event.data(&env))
+    };
+    let seq = config_client.get_event_seq();
+    client.settle_trader_pnl(&position_manager, &trader, &2_000);
+    let events = env.events().all();
+    assert!(!(seq + 1..=config_client.get_event_seq()).any(|seq| events.contains(tripped(seq))));
+    assert!(!config_client.is_globally_paused());
+    let state = client.get_drawdown_breaker();
+    assert_eq!(state.tripped_at, 0);
+    assert_eq!(state.peak_share_price, 10_000_000);
+
+    // Once the pause can go through, the next check trips against the same peak
+    config_client.set_liquidity_pool(&admin, &contract_id);
+    assert!(client.check_drawdown_breaker());
+    let events = env.events().all();
+    assert!(events.contains(tripped(config_client.get_event_seq())));
+    assert!(config_client.is_globally_paused());
+    assert_eq!(
+        client.get_drawdown_breaker().tripped_at,
+        env.ledger().timestamp()
+    );
+}
+
+#[test]
+fn test_share_price_history_checkpoints() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&lp, &20_000);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &token_client.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    assert_eq!(client.get_checkpoint_interval(), 86_400);
+    client.set_checkpoint_interval(&admin, &3_600);
+
+    // The first deposit records a checkpoint; more activity within the hour does not
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    client.deposit(&lp, &10_000);
+    token_admin.mint(&contract_id, &2_000);
+    client.deposit(&lp, &1_200);
+    assert!(!client.checkpoint_share_price());
+    let history = client.get_share_price_history(&10);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().timestamp, 10_000);
+    assert_eq!(history.get(0).unwrap().share_price, 10_000_000);
+
+    // An hour later anyone can record the 20% gain
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    assert!(client.checkpoint_share_price());
+    let history = client.get_share_price_history(&10);
+    assert_eq!(history.len(), 2);
+    let latest = history.get(0).unwrap();
+    assert_eq!(latest.timestamp, 13_600);
+    assert_eq!(latest.share_price, 12_000_000);
+    assert_eq!(latest.pool_value, 13_200);
+    assert_eq!(latest.total_shares, 11_000);
+
+    // Only the last 90 are retained
+    for _ in 0..95 {
+        env.ledger().with_mut(|li| li.timestamp += 3_600);
+        client.checkpoint_share_price();
+    }
+    let history = client.get_share_price_history(&90);
+    assert_eq!(history.len(), 90);
+    assert_eq!(history.get(0).unwrap().timestamp, 13_600 + 95 * 3_600);
+    assert_eq!(history.get(89).unwrap().timestamp, 13_600 + 6 * 3_600);
+    assert!(client.try_get_share_price_history(&91).is_err());
+}
+
+#[test]
+fn test_withdrawals_haircut_by_traders_unrealized_profit() {
+    let env = Env::default();
+    let (client, _token_admin, position_manager, compounder, claimer) = setup_fee_pool(&env);
+    let admin = config_manager::Client::new(&env, &client.config_manager()).admin();
+
+    // Traders up 2.5% of pool value: under the default 5% threshold
+    client.report_unrealized_pnl(&position_manager, &500);
+    assert_eq!(client.get_withdrawal_haircut(), 0);
+    assert_eq!(client.withdraw(&compounder, &1_000), 1_000);
+
+    // Traders up 4,000 of 19,000: withdrawals are valued net of their profit
+    client.report_unrealized_pnl(&position_manager, &4_000);
+    let state = client.get_pnl_haircut();
+    assert_eq!(state.traders_pnl, 4_000);
+    assert_eq!(client.get_withdrawal_haircut(), 4_000);
+    assert_eq!(client.withdraw(&compounder, &1_900), 1_500);
+
+    // Half the profit, and only above 30% of pool value
+    client.set_pnl_haircut(&admin, &3000, &5000);
+    assert_eq!(client.get_withdrawal_haircut(), 0);
+    client.set_pnl_haircut(&admin, &1000, &5000);
+    assert_eq!(client.get_withdrawal_haircut(), 2_000);
+    assert_eq!(client.withdraw(&claimer, &1_000), 906);
+
+    // Traders losing never lifts the redemption value
+    client.report_unrealized_pnl(&position_manager, &-4_000);
+    assert_eq!(client.get_withdrawal_haircut(), 0);
+
+    assert!(client
+        .try_report_unrealized_pnl(&compounder, &4_000)
+        .is_err());
+    assert!(client.try_set_pnl_haircut(&admin, &1000, &10001).is_err());
+}
+
+/// Pays out new tokens it was funded with at the requested rate
+#[contract]
+pub struct FundedMigrator;
+
+#[contractimpl]
+impl FundedMigrator {
+    pub fn swap(
+        env: Env,
+        _from_token: Address,
+        to_token: Address,
+        amount: i128,
+        rate: i128,
+        recipient: Address,
+    ) -> i128 {
+        let amount_out = amount * rate / 10_000_000;
+        token::Client::new(&env, &to_token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount_out,
+        );
+        amount_out
+    }
+}
+
+/// Reports whatever order collateral and fee escrow the test sets
+#[contract]
+pub struct StubOrderManager;
+
+#[contractimpl]
+impl StubOrderManager {
+    pub fn set_collateral_escrow(env: Env, amount: u128) {
+        env.storage().instance().set(&symbol_short!("escrow"), &amount);
+    }
+
+    pub fn get_collateral_escrow(env: Env) -> u128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("escrow"))
+            .unwrap_or(0)
+    }
+
+    pub fn set_fee_escrow(env: Env, token: Address, amount: u128) {
+        env.storage().instance().set(&token, &amount);
+    }
+
+    pub fn get_fee_escrow(env: Env, token: Address) -> u128 {
+        env.storage().instance().get(&token).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_token_migration_flow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp = Address::generate(&env);
+
+    let (old_token, old_token_admin) = create_token_contract(&env, &admin);
+    let (new_token, new_token_admin) = create_token_contract(&env, &admin);
+    old_token_admin.mint(&lp, &10_100);
+
+    let config_manager_id = create_mock_config_manager(&env, &admin);
+    let config_client = config_manager::Client::new(&env, &config_manager_id);
+    let contract_id = env.register(LiquidityPool, (&config_manager_id, &old_token.address));
+    let client = LiquidityPoolClient::new(&env, &contract_id);
+    config_client.set_token(&admin, &old_token.address);
+    config_client.set_liquidity_pool(&admin, &contract_id);
+    let order_manager = env.register(StubOrderManager, ());
+    let order_manager_client = StubOrderManagerClient::new(&env, &order_manager);
+    config_client.set_order_manager(&admin, &order_manager);
+    client.deposit(&lp, &10_000);
+
+    let migrator = env.register(FundedMigrator, ());
+    new_token_admin.mint(&migrator, &100_000);
+
+    // 1 old token buys 2 new tokens
+    assert!(client.try_preview_token_migration().is_err());
+    client.configure_token_migration(&admin, &new_token.address, &migrator, &20_000_000);
+    let preview = client.preview_token_migration();
+    assert_eq!(preview.balance, 10_000);
+    assert_eq!(preview.expected_balance, 20_000);
+    assert_eq!(preview.total_shares, 10_000);
+    assert_eq!(preview.share_price, 20_000_000);
+    assert_eq!(preview.order_escrow, 0);
+    assert!(!preview.globally_paused);
+    assert!(preview.rate_allowed);
+
+    // Snapshot and swap only run under the global pause
+    assert!(client.try_snapshot_token_migration(&admin).is_err());
+    config_client.set_global_pause(&admin, &true);
+    assert!(client.try_execute_token_migration(&admin).is_err());
+
+    // Order collateral escrowed in the old token blocks both steps
+    order_manager_client.set_collateral_escrow(&500);
+    assert_eq!(client.preview_token_migration().order_escrow, 500);
+    assert!(client.try_snapshot_token_migration(&admin).is_err());
+    order_manager_client.set_collateral_escrow(&0);
+    client.snapshot_token_migration(&admin);
+    order_manager_client.set_collateral_escrow(&500);
+    assert!(client.try_execute_token_migration(&admin).is_err());
+    order_manager_client.set_collateral_escrow(&0);
+
+    // So do execution fees escrowed in the old token, but not in another fee token
+    order_manager_client.set_fee_escrow(&old_token.address, &100);
+    assert_eq!(client.preview_token_migration().order_escrow, 100);
+    assert!(client.try_execute_token_migration(&admin).is_err());
+    order_manager_client.set_fee_escrow(&old_token.address, &0);
+    order_manager_client.set_fee_escrow(&new_token.address, &100);
+    assert_eq!(client.preview_token_migration().order_escrow, 0);
+
+    // A deposit after the snapshot invalidates it
+    client.deposit(&lp, &100);
+    assert!(client.try_execute_token_migration(&admin).is_err());
+    let snapshot = client.snapshot_token_migration(&admin);
+    assert_eq!(snapshot.balance, 10_100);
+    assert_eq!(client.get_token_migration_snapshot(), Some(snapshot));
+
+    assert_eq!(client.execute_token_migration(&admin), 20_200);
+    assert_eq!(client.token(), new_token.address);
+    assert_eq!(config_client.token(), new_token.address);
+    assert_eq!(client.get_token_migration(), None);
+    assert_eq!(client.get_token_migration_snapshot(), None);
+    assert_eq!(old_token.balance(&contract_id), 0);
+    assert_eq!(new_token.balance(&contract_id), 20_200);
+    assert_eq!(client.get_total_deposits(), 20_200);
+
+    // LPs keep their shares, now redeemed in the new token
+    config_client.set_global_pause(&admin, &false);
+    assert_eq!(client.withdraw(&lp, &5_050), 10_100);
+    assert_eq!(new_token.balance(&lp), 10_100);
+}
@@ -0,0 +1,116 @@
+//! Funding accrual model.
+//!
+//! Funding is settled lazily, per position, against two cumulative indices on the
+//! market (`cumulative_funding_long` / `cumulative_funding_short`, in bps * seconds):
+//!
+//! - **Market side**: every write that can change the funding rate (open interest
+//!   changes, pauses, checkpoints) first calls `accrue_funding()`, which charges the
+//!   seconds since `last_funding_update` at the rate the old open interest implied and
+//!   adds them to the paying side's index. This touches the market entry only.
+//! - **Position side**: PositionManager snapshots both indices when a position opens,
+//!   and settles `math::perp::funding_payment(index_now - snapshot, size)` when the
+//!   position is modified, closed or liquidated.
+//!
+//! Nothing here reads or writes per-position state, so `update_funding_rate()` costs the
+//! same storage reads and writes with one open position as with ten thousand. Anything
+//! that needs a position's funding computes it from the indices on demand; adding a
+//! loop over positions to a funding update would break this and belongs in a keeper
+//! job instead.
+
+use math::constants::BPS_DENOMINATOR;
+use soroban_sdk::Env;
+
+use crate::Market;
+
+/// Funding rate implied by a market's current long/short imbalance, in bps per hour
+pub(crate) fn funding_rate_for(market: &Market) -> i128 {
+    // Calculate total OI
+    let total_oi = market
+        .long_open_interest
+        .checked_add(market.short_open_interest)
+        .expect("OI overflow");
+
+    if total_oi == 0 {
+        // No open interest, funding rate stays at 0
+        return 0;
+    }
+
+    // === FUNDING RATE CALCULATION ===
+    // The funding rate incentivizes balance between longs and shorts by making
+    // the dominant side pay the minority side. Uses quadratic scaling to increase
+    // pressure as imbalance grows.
+
+    // Step 1: Calculate imbalance ratio as (long_oi - short_oi) / total_oi
+    // Positive = longs dominate, Negative = shorts dominate
+    let oi_diff = (market.long_open_interest as i128) - (market.short_open_interest as i128);
+
+    // Convert to basis points (10000 bps = 100%)
+    // Example: If long=60, short=40, total=100, then imbalance = 2000 bps (20%)
+    let imbalance_ratio_bps = (oi_diff * BPS_DENOMINATOR) / (total_oi as i128);
+
+    // Step 2: Apply quadratic scaling - funding pressure grows with square of imbalance
+    // This creates gentle pressure at small imbalances but strong pressure at large ones
+    // Example: 20% imbalance (2000 bps) -> squared = (2000 * 2000) / 10000 = 400 bps
+    let imbalance_squared = (imbalance_ratio_bps * imbalance_ratio_bps) / BPS_DENOMINATOR;
+
+    // Step 3: Scale by base funding rate (default 100 bps = 1% per hour)
+    // funding_rate = base_rate * imbalance_squared / 10000
+    // Example: 100 * 400 / 10000 = 4 bps per hour
+    let mut funding_rate = (market.base_funding_rate * imbalance_squared) / BPS_DENOMINATOR;
+
+    // Step 4: Restore direction - squaring loses the sign, so reapply based on imbalance
+    // Positive imbalance (longs > shorts) = positive rate = longs pay shorts
+    // Negative imbalance (shorts > longs) = negative rate = shorts pay longs
+    if imbalance_ratio_bps < 0 {
+        funding_rate = -funding_rate;
+    }
+
+    // Cap at max funding rate
+    if funding_rate > market.max_funding_rate {
+        funding_rate = market.max_funding_rate;
+    } else if funding_rate < -market.max_funding_rate {
+        funding_rate = -market.max_funding_rate;
+    }
+
+    funding_rate
+}
+
+/// Settle funding accrued since the market was last touched.
+///
+/// Open interest only changes through `update_open_interest()`, which settles first, so
+/// the elapsed period is charged at the rate implied by the open interest actually held
+/// during it. Positions pay for exactly the seconds they were open, whenever funding is
+/// checkpointed. Paused markets accrue nothing.
+pub(crate) fn accrue_funding(env: &Env, market: &mut Market) {
+    let now = env.ledger().timestamp();
+    if !market.is_paused {
+        accrue_funding_over(market, now - market.last_funding_update);
+    }
+    market.last_funding_update = now;
+}
+
+/// Charge `time_elapsed` seconds of funding at the rate implied by the current open interest
+pub(crate) fn accrue_funding_over(market: &mut Market, time_elapsed: u64) {
+    let funding_rate = funding_rate_for(market);
+
+    // === CUMULATIVE FUNDING ACCUMULATION ===
+    // Store funding as (bps_per_hour * seconds_elapsed) to preserve precision
+    // Division by 3600 (seconds per hour) happens in PositionManager's PnL calculation
+    // This avoids integer truncation that would occur if we divided here
+    // Example: 4 bps/hour * 60 seconds = 240 bps·seconds stored
+    let total_funding = funding_rate * (time_elapsed as i128);
+
+    // Track cumulative funding separately for longs and shorts
+    // - cumulative_funding_long: total funding longs have paid (when rate > 0)
+    // - cumulative_funding_short: total funding shorts have paid (when rate < 0)
+    // Positions calculate their owed funding by comparing current cumulative vs entry snapshot
+    if funding_rate > 0 {
+        // Positive rate: longs pay shorts
+        market.cumulative_funding_long += total_funding;
+    } else if funding_rate < 0 {
+        // Negative rate: shorts pay longs
+        market.cumulative_funding_short += total_funding.abs();
+    }
+
+    market.funding_rate = funding_rate;
+}
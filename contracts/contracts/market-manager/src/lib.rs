@@ -10,6 +10,40 @@
 //! - **Open Interest Tracking**: Tracks long and short OI separately for each market
 //! - **Funding Rate Calculation**: Calculates funding rates based on market imbalance
 //! - **Market Controls**: Admin can pause/unpause markets to halt new position openings
+//! - **OI Growth Limiter**: Caps how fast a market's OI may grow, as a share of pool TVL
+//!   per hour, so positions can't be built up all at once ahead of a known price event
+//! - **Remaining Capacity**: `get_remaining_oi_capacity()` reports how much more OI a side
+//!   can take under the OI cap and growth limit
+//! - **Leverage Limits**: `get_max_leverage()` and `get_effective_max_leverage()` report
+//!   the leverage new positions may use after the protocol step-down, the circuit breaker
+//!   cap and the market's OI leverage decay; PositionManager enforces the same limits
+//! - **Market Overview**: `get_market_overview()` gathers what a trading UI header shows
+//!   (prices, funding, open interest, leverage limit and fees) in one call
+//! - **Risk Metrics**: Tracks net exposure and average entry price per side so admins can
+//!   size OI caps from the pool's live mark-to-market PnL, and `preview_pool_settlement()`
+//!   values the pool's side of every position at a hypothetical price for LP tail-risk
+//!   checks
+//! - **Oracle Source Minimum**: With `set_min_oracle_sources()`, markets can only be
+//!   created or unpaused while their asset has that many healthy oracle sources
+//! - **One-Sided Markets**: `set_market_sides()` disables new longs or shorts per market
+//! - **Oracle Funding Push**: With `set_oracle_funding_push()`, funding checkpoints share
+//!   the market's rate with the oracle's mark price guard
+//! - **Pause Metadata**: Pauses record a reason code, the pauser and a resume estimate,
+//!   exposed by `get_market_status()` so frontends can explain why trading stopped
+//! - **Stale Settlement**: Markets paused beyond the configured max pause duration open a
+//!   settlement window where positions can be closed at the last valid TWAP
+//! - **Fee Accounting**: Fees PositionManager charges on a market's positions are
+//!   accrued per market and kind, for listing incentives and analytics
+//! - **Accrual Audits**: `get_market_accrual_state()` exposes the funding indices, the
+//!   borrow index and lifetime fees so the integrals can be checked without event replay
+//! - **Invariant Checks**: With `set_invariant_checks(true)` (testnet), every market write
+//!   is checked against the stored market: side OI may only move by the intended size
+//!   delta and the cumulative funding indices never decrease. Disabled by default, where
+//!   the check is a single flag read
+//! - **Time Warp**: While the oracle runs in test mode, `warp_funding()` accrues N funding
+//!   intervals in one call, so tests and testnet demos can fast-forward funding state
+//! - **Storage Usage**: `get_storage_usage()` counts stored markets, pause records and
+//!   fee records for monitoring rent exposure
 //!
 //! ## Funding Rate Mechanism
 //! Funding payments balance long and short positions by transferring value from the
@@ -22,37 +56,199 @@
 //!
 //! ## Cumulative Funding
 //! Funding is tracked cumulatively (bps * seconds) to allow precise per-position
-//! calculations without iterating through all positions on each update.
+//! calculations without iterating through all positions on each update. It accrues
+//! continuously: every open interest change first settles the elapsed seconds at the
+//! rate the previous open interest implied, so positions pay for the time they were
+//! open and cannot dodge funding by trading around keeper updates. The `accrual` module
+//! holds the model; funding updates never iterate positions.
 //!
 //! ## Usage
 //! - Admin creates markets via `create_market()`
 //! - Keeper bot calls `update_funding_rate()` every 60 seconds
 //! - PositionManager calls `update_open_interest()` when positions open/close
+//! - PositionManager calls `record_fee()` when it charges a fee
 
 use soroban_sdk::{
-    contract, contractevent, contractimpl, contracttype, symbol_short, Address, Env,
+    contract, contractevent, contractimpl, contracttype, panic_with_error, Address, Env, Symbol,
+    Vec,
 };
 
+use math::perp;
+
+pub use errors::MarketError;
+
+mod accrual;
+
+use accrual::{accrue_funding, accrue_funding_over, funding_rate_for};
+
 mod config_manager {
     soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/config_manager.wasm");
 }
 
+mod liquidity_pool {
+    soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/release/liquidity_pool.wasm");
+}
+
+mod oracle_integrator {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32v1-none/release/oracle_integrator.wasm"
+    );
+}
+
 // Data Structures
 
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Market {
     pub market_id: u32,
+    pub symbol: Symbol, // Human-readable name included in events (e.g. "XLMPERP")
     pub max_open_interest: u128,
     pub long_open_interest: u128,
     pub short_open_interest: u128,
-    pub funding_rate: i128, // bps per hour
+    pub long_entry_value: i128, // Sum of size * entry_price across open longs
+    pub short_entry_value: i128, // Sum of size * entry_price across open shorts
+    pub funding_rate: i128,     // bps per hour
     pub last_funding_update: u64,
     pub cumulative_funding_long: i128, // Total funding paid by longs
     pub cumulative_funding_short: i128, // Total funding paid by shorts
     pub is_paused: bool,
+    pub paused_at: u64,          // Timestamp the market was paused (0 when active)
     pub base_funding_rate: i128, // Default: 100 (0.01% per hour)
     pub max_funding_rate: i128,
+    pub max_oi_growth_bps: u32, // Max OI growth per hour as bps of pool TVL (0 = unlimited)
+    pub oi_growth_used: u128,   // OI added within the rolling window
+    pub oi_growth_updated_at: u64,
+    pub allow_longs: bool,  // New longs may be opened (default true)
+    pub allow_shorts: bool, // New shorts may be opened (default true)
+}
+
+/// Live risk snapshot for a market, valued at the current oracle price
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MarketRisk {
+    pub market_id: u32,
+    pub long_open_interest: u128,
+    pub short_open_interest: u128,
+    pub net_exposure: i128,          // Long OI - short OI
+    pub avg_long_entry_price: i128,  // 0 when no longs are open
+    pub avg_short_entry_price: i128, // 0 when no shorts are open
+    pub mark_price: i128,
+    pub pool_pnl: i128,       // Pool's unrealized PnL against all open positions
+    pub utilization_bps: u32, // Busier side's OI as a share of max_open_interest
+}
+
+/// Pool outcome if every position in a market settled at one price
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolSettlementPreview {
+    pub market_id: u32,
+    pub price: i128,     // Hypothetical settlement price (1e7 scaled)
+    pub long_pnl: i128,  // Longs' price PnL at the price
+    pub short_pnl: i128, // Shorts' price PnL at the price
+    pub pool_pnl: i128,  // Pool's gain (positive) or payout (negative): -(long_pnl + short_pnl)
+}
+
+/// Kind of fee charged on a position
+#[contracttype]
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum FeeKind {
+    Borrowing,   // Time-based leverage fee, realized when size is closed
+    EarlyClose,  // Fee for closing inside the minimum hold duration
+    Liquidation, // Keeper reward taken from a liquidated position's collateral
+}
+
+/// Stored entry counts, from `get_storage_usage()`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageUsage {
+    pub markets: u32,        // Created markets
+    pub paused_markets: u32, // Markets with pause metadata
+    pub fee_records: u32,    // Markets with fees recorded
+}
+
+/// Fees accrued by a market since creation, by kind
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarketFees {
+    pub borrowing: u128,
+    pub early_close: u128,
+    pub liquidation: u128,
+}
+
+/// Why a market was paused
+#[contracttype]
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum PauseReason {
+    NotPaused,      // Market is trading
+    Admin,          // Discretionary admin action
+    OracleFailure,  // Price feeds stale or unreliable
+    CircuitBreaker, // Abnormal price move or loss
+    OiBreach,       // Open interest above safe limits
+}
+
+/// Who paused a market, why and when
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PauseInfo {
+    pub reason: PauseReason,
+    pub paused_by: Address,
+    pub paused_at: u64,
+    pub expected_resume_at: u64, // Pauser's resume estimate (0 = unknown)
+}
+
+/// Trading status of a market for frontends
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketStatus {
+    pub market_id: u32,
+    pub is_paused: bool,
+    pub reason: PauseReason,
+    pub paused_by: Option<Address>,
+    pub paused_at: u64,             // 0 while trading
+    pub expected_resume_at: u64,    // Pauser's resume estimate (0 = unknown)
+    pub settlement_enabled_at: u64, // When force settlement opens (0 if never or not paused)
+}
+
+/// Funding and fee accumulators of a market as of its last funding checkpoint
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketAccrualState {
+    pub market_id: u32,
+    pub last_funding_update: u64, // Timestamp the funding indices were last settled
+    pub funding_rate: i128,       // Rate (bps per hour) applied up to that checkpoint
+    pub cumulative_funding_long: i128, // bps * seconds paid by longs since creation
+    pub cumulative_funding_short: i128, // bps * seconds paid by shorts since creation
+    pub cumulative_borrow_index: i128, // Protocol-wide borrow rate * seconds, as of now
+    pub fees: MarketFees,
+    pub total_fees: u128, // Sum of all fee kinds since creation
+}
+
+/// Estimated cost of a contemplated trade, returned by `get_price_impact()`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceImpact {
+    pub impact_bps: i128, // Execution price relative to the oracle price (positive: worse)
+    pub execution_price: i128, // Price the trade would fill at
+}
+
+/// Everything a market header needs, gathered in one call by `get_market_overview()`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketOverview {
+    pub market_id: u32,
+    pub symbol: Symbol,
+    pub is_paused: bool,
+    pub price_valid: bool, // False while every oracle source is stale or invalid
+    pub mark_price: i128,  // Median oracle price (0 when no valid price)
+    pub index_twap: i128,  // TWAP of served prices (0 if none was ever served)
+    pub index_updated_at: u64, // When the TWAP last folded in a price
+    pub funding_rate: i128, // Current rate in bps per hour (positive: longs pay shorts)
+    pub next_funding_time: u64, // Last funding checkpoint plus the funding interval
+    pub long_open_interest: u128,
+    pub short_open_interest: u128,
+    pub max_leverage: u32, // Effective limit for new positions, step-down and OI decay included (lower side)
+    pub maker_fee_bps: i128,
+    pub taker_fee_bps: i128,
 }
 
 #[contracttype]
@@ -62,7 +258,20 @@ pub enum DataKey {
     Admin,
     Market(u32),
     MarketCount,
+    MarketIds, // Vec<u32> of created market IDs, in creation order
     AuthorizedPositionManager,
+    MarketFees(u32),   // MarketFees: fees accrued per market
+    PauseInfo(u32),    // PauseInfo of a paused market
+    InvariantChecks,   // bool: check market writes against the stored market (testnet)
+    MinOracleSources,  // u32: healthy oracle sources a market needs to go live (0 = off)
+    OracleFundingPush, // bool: push each funding checkpoint's rate to the oracle mark price
+    // Storage usage counters
+    PausedMarketCount, // PauseInfo entries
+    FeeRecordCount,    // MarketFees entries
+}
+
+fn next_event_seq(env: &Env) -> u64 {
+    events::next_seq(env, &get_config_manager(env))
 }
 
 // Events
@@ -70,15 +279,42 @@ pub enum DataKey {
 #[contractevent]
 pub struct MarketCreatedEvent {
     pub market_id: u32,
+    pub symbol: Symbol,
     pub max_oi: u128,
+    pub seq: u64,
 }
 
 #[contractevent]
 pub struct FundingRateUpdatedEvent {
     pub market_id: u32,
+    pub symbol: Symbol,
     pub funding_rate: i128,
     pub long_oi: u128,
     pub short_oi: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FundingWarpedEvent {
+    pub market_id: u32,
+    pub intervals: u32,
+    pub seconds: u64, // Funding time accrued on top of the ledger clock
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct OIGrowthLimitUpdatedEvent {
+    pub market_id: u32,
+    pub max_oi_growth_bps: u32,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct MarketSidesUpdatedEvent {
+    pub market_id: u32,
+    pub allow_longs: bool,
+    pub allow_shorts: bool,
+    pub seq: u64,
 }
 
 #[contractevent]
@@ -86,6 +322,30 @@ pub struct OIUpdatedEvent {
     pub market_id: u32,
     pub long_oi: u128,
     pub short_oi: u128,
+    pub seq: u64,
+}
+
+#[contractevent]
+pub struct FeeCollectedEvent {
+    pub market_id: u32,
+    pub kind: FeeKind,
+    pub amount: u128,
+    pub seq: u64,
+}
+
+/// Published as `("paused",)` with `[market_id, seq]` data, the shape it had before
+/// events were declared as types
+#[contractevent(topics = ["paused"], data_format = "vec")]
+pub struct MarketPausedEvent {
+    pub market_id: u32,
+    pub seq: u64,
+}
+
+/// Published as `("unpaused",)` with `[market_id, seq]` data
+#[contractevent(topics = ["unpaused"], data_format = "vec")]
+pub struct MarketUnpausedEvent {
+    pub market_id: u32,
+    pub seq: u64,
 }
 
 // Helper Functions
@@ -94,21 +354,21 @@ fn get_config_manager(env: &Env) -> Address {
     env.storage()
         .instance()
         .get(&DataKey::ConfigManager)
-        .expect("ConfigManager not initialized")
+        .unwrap_or_else(|| panic_with_error!(env, MarketError::NotInitialized))
 }
 
 fn get_admin(env: &Env) -> Address {
     env.storage()
         .instance()
         .get(&DataKey::Admin)
-        .expect("Admin not set")
+        .unwrap_or_else(|| panic_with_error!(env, MarketError::NotInitialized))
 }
 
 fn require_admin(env: &Env, admin: &Address) {
     admin.require_auth();
     let stored_admin = get_admin(env);
     if admin != &stored_admin {
-        panic!("unauthorized: not admin");
+        panic_with_error!(env, MarketError::NotAdmin);
     }
 }
 
@@ -116,7 +376,7 @@ fn get_market(env: &Env, market_id: u32) -> Market {
     env.storage()
         .instance()
         .get(&DataKey::Market(market_id))
-        .expect("market not found")
+        .unwrap_or_else(|| panic_with_error!(env, MarketError::MarketNotFound))
 }
 
 fn set_market(env: &Env, market: &Market) {
@@ -125,6 +385,209 @@ fn set_market(env: &Env, market: &Market) {
         .set(&DataKey::Market(market.market_id), market);
 }
 
+/// Persist a market write, first checking it against the stored market when invariant
+/// checks are enabled.
+///
+/// # Arguments
+///
+/// * `market` - The market to store
+/// * `long_oi_delta` - Intended change in long open interest by this write
+/// * `short_oi_delta` - Intended change in short open interest by this write
+fn set_market_checked(env: &Env, market: &Market, long_oi_delta: i128, short_oi_delta: i128) {
+    let checks_enabled: bool = env
+        .storage()
+        .instance()
+        .get(&DataKey::InvariantChecks)
+        .unwrap_or(false);
+    if checks_enabled {
+        let before = get_market(env, market.market_id);
+        check_market_invariants(&before, market, long_oi_delta, short_oi_delta);
+    }
+    set_market(env, market);
+}
+
+/// Check a market write against the market it replaces.
+///
+/// # Panics
+///
+/// Panics if a side's open interest would go negative or moved by anything other than
+/// its intended delta, or if a cumulative funding index decreased
+fn check_market_invariants(
+    before: &Market,
+    after: &Market,
+    long_oi_delta: i128,
+    short_oi_delta: i128,
+) {
+    let expected_long = before.long_open_interest as i128 + long_oi_delta;
+    let expected_short = before.short_open_interest as i128 + short_oi_delta;
+    if expected_long < 0 || expected_short < 0 {
+        panic!("invariant violated: negative open interest");
+    }
+    if after.long_open_interest as i128 != expected_long
+        || after.short_open_interest as i128 != expected_short
+    {
+        panic!("invariant violated: OI delta does not match size delta");
+    }
+    if after.cumulative_funding_long < before.cumulative_funding_long
+        || after.cumulative_funding_short < before.cumulative_funding_short
+        || after.last_funding_update < before.last_funding_update
+    {
+        panic!("invariant violated: funding index decreased");
+    }
+}
+
+fn get_storage_count(env: &Env, key: &DataKey) -> u32 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+/// Count a stored entry in or out of a storage usage counter
+fn adjust_storage_count(env: &Env, key: &DataKey, added: bool) {
+    let count = get_storage_count(env, key);
+    let count = if added {
+        count + 1
+    } else {
+        count.saturating_sub(1)
+    };
+    env.storage().instance().set(key, &count);
+}
+
+fn get_market_ids(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::MarketIds)
+        .unwrap_or(Vec::new(env))
+}
+
+const SECONDS_PER_HOUR: u64 = 3600;
+
+/// Hourly OI growth allowance for a market, derived from the pool's current TVL
+fn oi_growth_limit(env: &Env, market: &Market) -> u128 {
+    let config_manager = get_config_manager(env);
+    let config_client = config_manager::Client::new(env, &config_manager);
+    let pool_client = liquidity_pool::Client::new(env, &config_client.liquidity_pool());
+    let tvl = pool_client.get_total_deposits().max(0) as u128;
+
+    tvl * market.max_oi_growth_bps as u128 / 10000
+}
+
+/// OI growth consumed in the rolling window, after replenishing the allowance
+/// linearly for the time elapsed since the last update
+fn decayed_oi_growth(env: &Env, market: &Market, limit: u128) -> u128 {
+    let elapsed = env.ledger().timestamp() - market.oi_growth_updated_at;
+    let replenished = limit * elapsed as u128 / SECONDS_PER_HOUR as u128;
+    market.oi_growth_used.saturating_sub(replenished)
+}
+
+/// OI growth still allowed in the rolling window (u128::MAX when unlimited)
+fn oi_growth_remaining(env: &Env, market: &Market) -> u128 {
+    if market.max_oi_growth_bps == 0 {
+        return u128::MAX;
+    }
+
+    let limit = oi_growth_limit(env, market);
+    limit.saturating_sub(decayed_oi_growth(env, market, limit))
+}
+
+/// Charge an OI increase against the market's hourly growth allowance.
+///
+/// # Panics
+/// Panics if the increase would exceed the allowance for the rolling window
+fn consume_oi_growth(env: &Env, market: &mut Market, size: u128) {
+    if market.max_oi_growth_bps == 0 {
+        return;
+    }
+
+    let limit = oi_growth_limit(env, market);
+    let used = decayed_oi_growth(env, market, limit) + size;
+    if used > limit {
+        panic_with_error!(env, MarketError::OiGrowthLimitExceeded);
+    }
+
+    market.oi_growth_used = used;
+    market.oi_growth_updated_at = env.ledger().timestamp();
+}
+
+/// Average entry price of one side, weighted by size the same way PositionManager
+/// averages a position's entry price when it is increased
+fn average_entry_price(open_interest: u128, entry_value: i128) -> i128 {
+    if open_interest == 0 {
+        return 0;
+    }
+    entry_value / open_interest as i128
+}
+
+/// Traders' unrealized price PnL on one side, treated as a single position
+fn side_pnl(open_interest: u128, avg_entry_price: i128, mark_price: i128, is_long: bool) -> i128 {
+    if open_interest == 0 {
+        return 0;
+    }
+    let price_diff = if is_long {
+        mark_price - avg_entry_price
+    } else {
+        avg_entry_price - mark_price
+    };
+    price_diff * open_interest as i128 / avg_entry_price
+}
+
+/// Refuse test hooks unless the oracle runs in test mode
+fn require_test_mode(env: &Env) {
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    let oracle_client = oracle_integrator::Client::new(env, &config_client.oracle_integrator());
+    if !oracle_client.get_test_mode() {
+        panic_with_error!(env, MarketError::TestHooksDisabled);
+    }
+}
+
+/// Refuse to take a market live while its asset has too few healthy oracle sources
+fn require_oracle_sources(env: &Env, market_id: u32) {
+    let min_sources: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MinOracleSources)
+        .unwrap_or(0);
+    if min_sources == 0 {
+        return;
+    }
+
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    let oracle_client = oracle_integrator::Client::new(env, &config_client.oracle_integrator());
+    if oracle_client.get_source_health(&market_id).healthy < min_sources {
+        panic_with_error!(env, MarketError::InsufficientOracleSources);
+    }
+}
+
+/// Share a market's funding rate with the oracle's mark price guard, when enabled
+fn push_oracle_funding_rate(env: &Env, market_id: u32, funding_rate: i128) {
+    let enabled: bool = env
+        .storage()
+        .instance()
+        .get(&DataKey::OracleFundingPush)
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    let oracle_client = oracle_integrator::Client::new(env, &config_client.oracle_integrator());
+    oracle_client.set_mark_funding_rate(&env.current_contract_address(), &market_id, &funding_rate);
+}
+
+/// Protocol-wide cap on new positions' leverage: ConfigManager's step-down and, while
+/// the circuit breaker is tripped, its circuit breaker cap (0 = no cap)
+fn leverage_step_down(env: &Env) -> u32 {
+    let config_client = config_manager::Client::new(env, &get_config_manager(env));
+    let step_down = config_client.leverage_step_down() as u32;
+    let breaker_cap = config_client.circuit_breaker_max_leverage() as u32;
+    if breaker_cap == 0 || !MarketManager::is_circuit_breaker_tripped(env.clone()) {
+        return step_down;
+    }
+    if step_down == 0 {
+        breaker_cap
+    } else {
+        step_down.min(breaker_cap)
+    }
+}
+
 fn require_position_manager(env: &Env, caller: &Address) {
     caller.require_auth();
     if let Some(authorized) = env
@@ -133,10 +596,10 @@ fn require_position_manager(env: &Env, caller: &Address) {
         .get::<DataKey, Address>(&DataKey::AuthorizedPositionManager)
     {
         if caller != &authorized {
-            panic!("unauthorized: not position manager");
+            panic_with_error!(env, MarketError::NotPositionManager);
         }
     } else {
-        panic!("position manager not set");
+        panic_with_error!(env, MarketError::PositionManagerNotSet);
     }
 }
 
@@ -145,17 +608,14 @@ pub struct MarketManager;
 
 #[contractimpl]
 impl MarketManager {
-    /// Initialize the MarketManager contract.
+    /// Set up the MarketManager as it is deployed. Running in the deploy transaction,
+    /// it cannot be front-run into taking another admin or ConfigManager.
     ///
     /// # Arguments
     ///
     /// * `config_manager` - Address of the ConfigManager contract
     /// * `admin` - Address of the admin
-    pub fn initialize(env: Env, config_manager: Address, admin: Address) {
-        if env.storage().instance().has(&DataKey::ConfigManager) {
-            panic!("already initialized");
-        }
-
+    pub fn __constructor(env: Env, config_manager: Address, admin: Address) {
         env.storage()
             .instance()
             .set(&DataKey::ConfigManager, &config_manager);
@@ -182,12 +642,19 @@ impl MarketManager {
     ///
     /// * `admin` - Address of the admin
     /// * `market_id` - Unique identifier for the market (e.g., 0 = XLM-PERP)
+    /// * `symbol` - Human-readable market symbol included in events (e.g., "XLMPERP")
     /// * `max_open_interest` - Maximum total open interest allowed for this market
     /// * `max_funding_rate` - Maximum funding rate per hour (in basis points)
+    ///
+    /// # Panics
+    ///
+    /// If the market exists, or its asset has fewer healthy oracle sources than
+    /// `get_min_oracle_sources()` requires
     pub fn create_market(
         env: Env,
         admin: Address,
         market_id: u32,
+        symbol: Symbol,
         max_open_interest: u128,
         max_funding_rate: i128,
     ) {
@@ -195,22 +662,32 @@ impl MarketManager {
 
         // Verify market doesn't already exist
         if env.storage().instance().has(&DataKey::Market(market_id)) {
-            panic!("market already exists");
+            panic_with_error!(&env, MarketError::MarketAlreadyExists);
         }
+        require_oracle_sources(&env, market_id);
 
         // Create market with defaults
         let market = Market {
             market_id,
+            symbol: symbol.clone(),
             max_open_interest,
             long_open_interest: 0,
             short_open_interest: 0,
+            long_entry_value: 0,
+            short_entry_value: 0,
             funding_rate: 0,
             last_funding_update: env.ledger().timestamp(),
             cumulative_funding_long: 0,
             cumulative_funding_short: 0,
             is_paused: false,
+            paused_at: 0,
             base_funding_rate: 100, // 1% per hour = 100 basis points
             max_funding_rate,
+            max_oi_growth_bps: 0,
+            oi_growth_used: 0,
+            oi_growth_updated_at: env.ledger().timestamp(),
+            allow_longs: true,
+            allow_shorts: true,
         };
 
         set_market(&env, &market);
@@ -225,131 +702,87 @@ impl MarketManager {
             .instance()
             .set(&DataKey::MarketCount, &(count + 1));
 
+        let mut market_ids = get_market_ids(&env);
+        market_ids.push_back(market_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::MarketIds, &market_ids);
+
         // Emit event
         MarketCreatedEvent {
             market_id,
+            symbol,
             max_oi: max_open_interest,
+            seq: next_event_seq(&env),
         }
         .publish(&env);
     }
 
-    /// Update the funding rate for a market.
+    /// Limit how fast a market's open interest may grow.
     ///
-    /// Called every 60 seconds by the keeper bot.
-    /// Calculates funding rate based on market imbalance and updates cumulative funding.
-    /// Funding rate is expressed in basis points per hour.
+    /// OI increases on either side are charged against an hourly allowance of
+    /// `max_oi_growth_bps` of the pool's TVL, which replenishes linearly over a rolling
+    /// one hour window.
     ///
     /// # Arguments
     ///
-    /// * `caller` - Address calling this function
+    /// * `admin` - Address of the admin
     /// * `market_id` - The market identifier
-    pub fn update_funding_rate(env: Env, caller: Address, market_id: u32) {
-        caller.require_auth();
+    /// * `max_oi_growth_bps` - Max OI growth per hour in bps of pool TVL (0 disables the limit)
+    pub fn set_oi_growth_limit(env: Env, admin: Address, market_id: u32, max_oi_growth_bps: u32) {
+        require_admin(&env, &admin);
 
         let mut market = get_market(&env, market_id);
+        market.max_oi_growth_bps = max_oi_growth_bps;
+        market.oi_growth_used = 0;
+        market.oi_growth_updated_at = env.ledger().timestamp();
+        set_market_checked(&env, &market, 0, 0);
 
-        // Check if market is paused - funding updates should be suspended
-        if market.is_paused {
-            panic!("cannot update funding rate for paused market");
+        OIGrowthLimitUpdatedEvent {
+            market_id,
+            max_oi_growth_bps,
+            seq: next_event_seq(&env),
         }
+        .publish(&env);
+    }
 
-        // Verify funding interval has passed (60s from ConfigManager)
-        let config_manager = get_config_manager(&env);
-        let config_client = config_manager::Client::new(&env, &config_manager);
-        let funding_interval = config_client.funding_interval();
-
-        let now = env.ledger().timestamp();
-        let time_elapsed = now - market.last_funding_update;
-
-        if time_elapsed < funding_interval {
-            panic!("funding interval not elapsed");
-        }
-
-        // Calculate total OI
-        let total_oi = market
-            .long_open_interest
-            .checked_add(market.short_open_interest)
-            .expect("OI overflow");
-
-        if total_oi == 0 {
-            // No open interest, funding rate stays at 0
-            market.last_funding_update = now;
-            set_market(&env, &market);
-            return;
-        }
-
-        // === FUNDING RATE CALCULATION ===
-        // The funding rate incentivizes balance between longs and shorts by making
-        // the dominant side pay the minority side. Uses quadratic scaling to increase
-        // pressure as imbalance grows.
-
-        // Step 1: Calculate imbalance ratio as (long_oi - short_oi) / total_oi
-        // Positive = longs dominate, Negative = shorts dominate
-        let oi_diff = (market.long_open_interest as i128) - (market.short_open_interest as i128);
-
-        // Convert to basis points (10000 bps = 100%)
-        // Example: If long=60, short=40, total=100, then imbalance = 2000 bps (20%)
-        let imbalance_ratio_bps = (oi_diff * 10000) / (total_oi as i128);
-
-        // Step 2: Apply quadratic scaling - funding pressure grows with square of imbalance
-        // This creates gentle pressure at small imbalances but strong pressure at large ones
-        // Example: 20% imbalance (2000 bps) -> squared = (2000 * 2000) / 10000 = 400 bps
-        let imbalance_squared = (imbalance_ratio_bps * imbalance_ratio_bps) / 10000;
-
-        // Step 3: Scale by base funding rate (default 100 bps = 1% per hour)
-        // funding_rate = base_rate * imbalance_squared / 10000
-        // Example: 100 * 400 / 10000 = 4 bps per hour
-        let mut funding_rate = (market.base_funding_rate * imbalance_squared) / 10000;
-
-        // Step 4: Restore direction - squaring loses the sign, so reapply based on imbalance
-        // Positive imbalance (longs > shorts) = positive rate = longs pay shorts
-        // Negative imbalance (shorts > longs) = negative rate = shorts pay longs
-        if imbalance_ratio_bps < 0 {
-            funding_rate = -funding_rate;
-        }
-
-        // Cap at max funding rate
-        if funding_rate > market.max_funding_rate {
-            funding_rate = market.max_funding_rate;
-        } else if funding_rate < -market.max_funding_rate {
-            funding_rate = -market.max_funding_rate;
-        }
-
-        // === CUMULATIVE FUNDING ACCUMULATION ===
-        // Store funding as (bps_per_hour * seconds_elapsed) to preserve precision
-        // Division by 3600 (seconds per hour) happens in PositionManager's PnL calculation
-        // This avoids integer truncation that would occur if we divided here
-        // Example: 4 bps/hour * 60 seconds = 240 bps·seconds stored
-        let total_funding = funding_rate * (time_elapsed as i128);
-
-        // Track cumulative funding separately for longs and shorts
-        // - cumulative_funding_long: total funding longs have paid (when rate > 0)
-        // - cumulative_funding_short: total funding shorts have paid (when rate < 0)
-        // Positions calculate their owed funding by comparing current cumulative vs entry snapshot
-        if funding_rate > 0 {
-            // Positive rate: longs pay shorts
-            market.cumulative_funding_long += total_funding;
-        } else if funding_rate < 0 {
-            // Negative rate: shorts pay longs
-            market.cumulative_funding_short += total_funding.abs();
-        }
-
-        // Update market state
-        market.funding_rate = funding_rate;
-        market.last_funding_update = now;
-        set_market(&env, &market);
+    /// Allow or disallow new positions on each side of a market.
+    ///
+    /// For one-sided markets (e.g. an asset that cannot be borrowed, or a launch phase).
+    /// A disabled side cannot open or increase positions, by market order or limit
+    /// order; existing positions can still be reduced and closed. Both sides are
+    /// enabled when a market is created.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - Address of the admin
+    /// * `market_id` - The market identifier
+    /// * `allow_longs` - True to allow new longs
+    /// * `allow_shorts` - True to allow new shorts
+    pub fn set_market_sides(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        allow_longs: bool,
+        allow_shorts: bool,
+    ) {
+        require_admin(&env, &admin);
 
-        // Emit event
-        FundingRateUpdatedEvent {
+        let mut market = get_market(&env, market_id);
+        market.allow_longs = allow_longs;
+        market.allow_shorts = allow_shorts;
+        set_market_checked(&env, &market, 0, 0);
+
+        MarketSidesUpdatedEvent {
             market_id,
-            funding_rate,
-            long_oi: market.long_open_interest,
-            short_oi: market.short_open_interest,
+            allow_longs,
+            allow_shorts,
+            seq: next_event_seq(&env),
         }
         .publish(&env);
     }
 
-    /// Get the current funding rate for a market.
+    /// Get how much more open interest a market can add right now.
     ///
     /// # Arguments
     ///
@@ -357,72 +790,309 @@ impl MarketManager {
     ///
     /// # Returns
     ///
-    /// The current funding rate (in basis points per hour)
-    pub fn get_funding_rate(env: Env, market_id: u32) -> i128 {
-        let market = get_market(&env, market_id);
-        market.funding_rate
+    /// Remaining OI growth allowance in the rolling window (u128::MAX when unlimited)
+    pub fn get_oi_growth_remaining(env: Env, market_id: u32) -> u128 {
+        oi_growth_remaining(&env, &get_market(&env, market_id))
     }
 
-    /// Get cumulative funding for a position side.
+    /// Enable or disable write-time invariant checks on market state.
+    ///
+    /// Meant for testnet: while enabled, every market write panics if a side's open
+    /// interest goes negative or moves by anything other than the size delta being
+    /// applied, or if a cumulative funding index decreases. Disabled by default.
     ///
     /// # Arguments
     ///
-    /// * `market_id` - The market identifier
-    /// * `is_long` - True if long position, false if short
+    /// * `admin` - Address of the admin
+    /// * `enabled` - True to check market writes
+    pub fn set_invariant_checks(env: Env, admin: Address, enabled: bool) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::InvariantChecks, &enabled);
+    }
+
+    /// Check whether write-time invariant checks are enabled.
     ///
     /// # Returns
     ///
-    /// The cumulative funding paid by the specified side
-    pub fn get_cumulative_funding(env: Env, market_id: u32, is_long: bool) -> i128 {
-        let market = get_market(&env, market_id);
-        if is_long {
-            market.cumulative_funding_long
-        } else {
-            market.cumulative_funding_short
-        }
+    /// True if market writes are checked against the stored market
+    pub fn invariant_checks_enabled(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::InvariantChecks)
+            .unwrap_or(false)
     }
 
-    /// Update open interest when positions are opened or closed.
+    /// Set how many healthy oracle sources a market's asset needs before the market
+    /// can be created or unpaused. Sources are counted by the OracleIntegrator's
+    /// `get_source_health()`. Disabled (0) by default.
     ///
     /// # Arguments
     ///
-    /// * `position_manager` - Address of the PositionManager contract
-    /// * `market_id` - The market identifier
-    /// * `is_long` - True if long position, false if short
-    /// * `size_delta` - Change in position size (positive = increase, negative = decrease)
-    pub fn update_open_interest(
-        env: Env,
-        position_manager: Address,
-        market_id: u32,
-        is_long: bool,
-        size_delta: i128,
-    ) {
-        require_position_manager(&env, &position_manager);
+    /// * `admin` - Address of the admin
+    /// * `min_sources` - Minimum healthy sources (0 disables the check)
+    pub fn set_min_oracle_sources(env: Env, admin: Address, min_sources: u32) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::MinOracleSources, &min_sources);
+    }
 
-        let mut market = get_market(&env, market_id);
+    /// Get the minimum healthy oracle sources a market needs to go live.
+    ///
+    /// # Returns
+    ///
+    /// Minimum healthy sources (0 = not enforced)
+    pub fn get_min_oracle_sources(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinOracleSources)
+            .unwrap_or(0)
+    }
 
-        if is_long {
-            // Update long OI
-            if size_delta > 0 {
-                // Opening or increasing position
-                let new_long_oi = market
-                    .long_open_interest
-                    .checked_add(size_delta as u128)
-                    .expect("long OI overflow");
+    /// Enable or disable pushing funding rates to the OracleIntegrator.
+    ///
+    /// While enabled, every `update_funding_rate()` checkpoint records the market's rate
+    /// with the oracle, whose mark price guard carries the last served price forward by
+    /// it. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - Address of the admin
+    /// * `enabled` - True to push funding rates
+    pub fn set_oracle_funding_push(env: Env, admin: Address, enabled: bool) {
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::OracleFundingPush, &enabled);
+    }
 
-                // Check against max OI limit
-                if new_long_oi > market.max_open_interest {
-                    panic!("exceeds max open interest");
-                }
+    /// Check whether funding rates are pushed to the OracleIntegrator.
+    ///
+    /// # Returns
+    ///
+    /// True if funding checkpoints update the oracle's mark price guard
+    pub fn oracle_funding_push_enabled(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::OracleFundingPush)
+            .unwrap_or(false)
+    }
 
-                market.long_open_interest = new_long_oi;
+    /// Checkpoint funding for a market and publish its current rate.
+    ///
+    /// Called every 60 seconds by the keeper bot. Funding accrues continuously and is
+    /// also settled on every open interest change, so the checkpoint cadence does not
+    /// change what any position pays.
+    /// Funding rate is expressed in basis points per hour.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - Address calling this function
+    /// * `market_id` - The market identifier
+    pub fn update_funding_rate(env: Env, caller: Address, market_id: u32) {
+        caller.require_auth();
+
+        let mut market = get_market(&env, market_id);
+
+        // Check if market is paused - funding updates should be suspended
+        if market.is_paused {
+            panic_with_error!(&env, MarketError::MarketPaused);
+        }
+
+        accrue_funding(&env, &mut market);
+        set_market_checked(&env, &market, 0, 0);
+        let funding_rate = market.funding_rate;
+        push_oracle_funding_rate(&env, market_id, funding_rate);
+
+        // Emit event
+        FundingRateUpdatedEvent {
+            market_id,
+            symbol: market.symbol.clone(),
+            funding_rate,
+            long_oi: market.long_open_interest,
+            short_oi: market.short_open_interest,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Accrue `intervals` funding intervals in one call (test mode only).
+    ///
+    /// Settles funding up to the ledger clock, then charges `intervals` times the
+    /// configured funding interval at the rate the current open interest implies, as if
+    /// that much time had passed. The ledger clock is untouched, so pair with
+    /// PositionManager's `warp_position()` for funding caps and borrowing fees to match.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - Address of the admin
+    /// * `market_id` - The market identifier
+    /// * `intervals` - Funding intervals to accrue
+    ///
+    /// # Returns
+    ///
+    /// The seconds of funding accrued
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the oracle runs in test mode, or if the market is paused
+    pub fn warp_funding(env: Env, admin: Address, market_id: u32, intervals: u32) -> u64 {
+        require_admin(&env, &admin);
+        require_test_mode(&env);
+
+        let mut market = get_market(&env, market_id);
+        if market.is_paused {
+            panic_with_error!(&env, MarketError::MarketPaused);
+        }
+
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let seconds = config_client.funding_interval() * intervals as u64;
+        accrue_funding(&env, &mut market);
+        accrue_funding_over(&mut market, seconds);
+        set_market_checked(&env, &market, 0, 0);
+
+        FundingWarpedEvent {
+            market_id,
+            intervals,
+            seconds,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+        seconds
+    }
+
+    /// Get the current funding rate for a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// The current funding rate (in basis points per hour)
+    pub fn get_funding_rate(env: Env, market_id: u32) -> i128 {
+        let market = get_market(&env, market_id);
+        funding_rate_for(&market)
+    }
+
+    /// Preview the funding rate after a hypothetical open interest change, so traders and
+    /// UIs can see a trade's funding impact before submitting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `is_long` - Side of the hypothetical change
+    /// * `size_delta` - Change in that side's open interest (positive = open, negative = close)
+    ///
+    /// # Returns
+    ///
+    /// The funding rate (in basis points per hour) the market would have after the change
+    ///
+    /// # Panics
+    ///
+    /// Panics if the market does not exist or the change would reduce OI below zero
+    pub fn preview_funding_after(
+        env: Env,
+        market_id: u32,
+        is_long: bool,
+        size_delta: i128,
+    ) -> i128 {
+        let mut market = get_market(&env, market_id);
+        let open_interest = if is_long {
+            &mut market.long_open_interest
+        } else {
+            &mut market.short_open_interest
+        };
+        let new_open_interest = *open_interest as i128 + size_delta;
+        if new_open_interest < 0 {
+            panic_with_error!(&env, MarketError::OiBelowZero);
+        }
+        *open_interest = new_open_interest as u128;
+
+        funding_rate_for(&market)
+    }
+
+    /// Get cumulative funding for a position side.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `is_long` - True if long position, false if short
+    ///
+    /// # Returns
+    ///
+    /// The cumulative funding paid by the specified side, including funding accrued
+    /// since the last checkpoint
+    pub fn get_cumulative_funding(env: Env, market_id: u32, is_long: bool) -> i128 {
+        let mut market = get_market(&env, market_id);
+        accrue_funding(&env, &mut market);
+        if is_long {
+            market.cumulative_funding_long
+        } else {
+            market.cumulative_funding_short
+        }
+    }
+
+    /// Update open interest when positions are opened or closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_manager` - Address of the PositionManager contract
+    /// * `market_id` - The market identifier
+    /// * `is_long` - True if long position, false if short
+    /// * `size_delta` - Change in position size (positive = increase, negative = decrease)
+    /// * `entry_price` - Entry price of the size being added, or of the position being reduced
+    pub fn update_open_interest(
+        env: Env,
+        position_manager: Address,
+        market_id: u32,
+        is_long: bool,
+        size_delta: i128,
+        entry_price: i128,
+    ) {
+        require_position_manager(&env, &position_manager);
+
+        let mut market = get_market(&env, market_id);
+        // Settle funding at the open interest held until now
+        accrue_funding(&env, &mut market);
+        let entry_value_delta = size_delta
+            .checked_mul(entry_price)
+            .expect("entry value overflow");
+
+        if size_delta > 0 {
+            consume_oi_growth(&env, &mut market, size_delta as u128);
+        }
+
+        if is_long {
+            // Update long OI
+            if size_delta > 0 {
+                // Opening or increasing position
+                let new_long_oi = market
+                    .long_open_interest
+                    .checked_add(size_delta as u128)
+                    .expect("long OI overflow");
+
+                // Check against max OI limit
+                if new_long_oi > market.max_open_interest {
+                    panic_with_error!(&env, MarketError::OiCapExceeded);
+                }
+
+                market.long_open_interest = new_long_oi;
+                market.long_entry_value += entry_value_delta;
             } else {
                 // Closing or decreasing position
                 let decrease = (-size_delta) as u128;
                 if decrease > market.long_open_interest {
-                    panic!("cannot reduce OI below zero");
+                    panic_with_error!(&env, MarketError::OiBelowZero);
                 }
                 market.long_open_interest -= decrease;
+                market.long_entry_value += entry_value_delta;
+                // Drop rounding residue left by averaged entry prices
+                if market.long_open_interest == 0 {
+                    market.long_entry_value = 0;
+                }
             }
         } else {
             // Update short OI
@@ -433,30 +1103,180 @@ impl MarketManager {
                     .expect("short OI overflow");
 
                 if new_short_oi > market.max_open_interest {
-                    panic!("exceeds max open interest");
+                    panic_with_error!(&env, MarketError::OiCapExceeded);
                 }
 
                 market.short_open_interest = new_short_oi;
+                market.short_entry_value += entry_value_delta;
             } else {
                 let decrease = (-size_delta) as u128;
                 if decrease > market.short_open_interest {
-                    panic!("cannot reduce OI below zero");
+                    panic_with_error!(&env, MarketError::OiBelowZero);
                 }
                 market.short_open_interest -= decrease;
+                market.short_entry_value += entry_value_delta;
+                if market.short_open_interest == 0 {
+                    market.short_entry_value = 0;
+                }
             }
         }
 
-        set_market(&env, &market);
+        if is_long {
+            set_market_checked(&env, &market, size_delta, 0);
+        } else {
+            set_market_checked(&env, &market, 0, size_delta);
+        }
 
         // Emit event
         OIUpdatedEvent {
             market_id,
             long_oi: market.long_open_interest,
             short_oi: market.short_open_interest,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Accrue a fee charged on one of a market's positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `position_manager` - Address of the PositionManager contract
+    /// * `market_id` - The market the position belongs to
+    /// * `kind` - The kind of fee charged
+    /// * `amount` - The fee amount in collateral token units
+    ///
+    /// # Panics
+    ///
+    /// Panics if the caller is not the authorized position manager or the market
+    /// does not exist
+    pub fn record_fee(
+        env: Env,
+        position_manager: Address,
+        market_id: u32,
+        kind: FeeKind,
+        amount: u128,
+    ) {
+        require_position_manager(&env, &position_manager);
+        get_market(&env, market_id);
+
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::MarketFees(market_id))
+        {
+            adjust_storage_count(&env, &DataKey::FeeRecordCount, true);
+        }
+        let mut fees = Self::get_market_fees(env.clone(), market_id);
+        match kind {
+            FeeKind::Borrowing => fees.borrowing += amount,
+            FeeKind::EarlyClose => fees.early_close += amount,
+            FeeKind::Liquidation => fees.liquidation += amount,
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::MarketFees(market_id), &fees);
+
+        FeeCollectedEvent {
+            market_id,
+            kind,
+            amount,
+            seq: next_event_seq(&env),
         }
         .publish(&env);
     }
 
+    /// Get the fees accrued by a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// Fees charged on the market's positions since creation, by kind
+    pub fn get_market_fees(env: Env, market_id: u32) -> MarketFees {
+        env.storage()
+            .instance()
+            .get(&DataKey::MarketFees(market_id))
+            .unwrap_or_default()
+    }
+
+    /// Get a market's funding and fee accumulators, so accountants can check the funding
+    /// and borrowing integrals against positions without replaying every event.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// MarketAccrualState with the funding indices at the last checkpoint, the current
+    /// cumulative borrow index and the fees charged since creation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the market does not exist
+    pub fn get_market_accrual_state(env: Env, market_id: u32) -> MarketAccrualState {
+        let market = get_market(&env, market_id);
+        let fees = Self::get_market_fees(env.clone(), market_id);
+
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+
+        MarketAccrualState {
+            market_id,
+            last_funding_update: market.last_funding_update,
+            funding_rate: market.funding_rate,
+            cumulative_funding_long: market.cumulative_funding_long,
+            cumulative_funding_short: market.cumulative_funding_short,
+            cumulative_borrow_index: config_client.cumulative_borrow_index(),
+            total_fees: fees.borrowing + fees.early_close + fees.liquidation,
+            fees,
+        }
+    }
+
+    /// Get the human-readable symbol of a market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// The market symbol set at creation (e.g., "XLMPERP")
+    pub fn get_market_symbol(env: Env, market_id: u32) -> Symbol {
+        get_market(&env, market_id).symbol
+    }
+
+    /// Get every market with its full state, for bootstrapping an indexer without
+    /// replaying events.
+    ///
+    /// # Returns
+    ///
+    /// All created markets in creation order
+    pub fn get_markets(env: Env) -> Vec<Market> {
+        let mut markets = Vec::new(&env);
+        for market_id in get_market_ids(&env).iter() {
+            markets.push_back(get_market(&env, market_id));
+        }
+        markets
+    }
+
+    /// Get the number of markets, pause records and fee records in storage, to monitor
+    /// ledger rent exposure.
+    ///
+    /// # Returns
+    ///
+    /// The StorageUsage counters. Records written before the counters were introduced
+    /// are not included.
+    pub fn get_storage_usage(env: Env) -> StorageUsage {
+        StorageUsage {
+            markets: get_storage_count(&env, &DataKey::MarketCount),
+            paused_markets: get_storage_count(&env, &DataKey::PausedMarketCount),
+            fee_records: get_storage_count(&env, &DataKey::FeeRecordCount),
+        }
+    }
+
     /// Get the current open interest for a market.
     ///
     /// # Arguments
@@ -471,20 +1291,228 @@ impl MarketManager {
         (market.long_open_interest, market.short_open_interest)
     }
 
-    /// Pause a market to prevent new positions from being opened.
+    /// Get how much of a market's OI cap one side is using.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `is_long` - True for the long side, false for the short side
+    ///
+    /// # Returns
+    ///
+    /// The side's open interest as bps of `max_open_interest` (0 when the cap is 0)
+    pub fn get_oi_utilization(env: Env, market_id: u32, is_long: bool) -> u32 {
+        let market = get_market(&env, market_id);
+        let current_oi = if is_long {
+            market.long_open_interest
+        } else {
+            market.short_open_interest
+        };
+        (current_oi * 10000)
+            .checked_div(market.max_open_interest)
+            .unwrap_or(0) as u32
+    }
+
+    /// Get the open interest summed over both sides of every market.
+    ///
+    /// # Returns
+    ///
+    /// Total long plus short open interest across all markets
+    pub fn get_total_open_interest(env: Env) -> u128 {
+        let mut total = 0u128;
+        for market_id in get_market_ids(&env).iter() {
+            let market = get_market(&env, market_id);
+            total += market.long_open_interest + market.short_open_interest;
+        }
+        total
+    }
+
+    /// Get the size-weighted average entry price of each side's open interest.
+    ///
+    /// Updated on every open, increase, decrease, close and liquidation, so an off-chain
+    /// hedger can derive the pool's net delta (the pool holds the opposite of each side)
+    /// without pricing every position.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (avg_long_entry_price, avg_short_entry_price), 0 for a side with no OI
+    pub fn get_avg_entry_prices(env: Env, market_id: u32) -> (i128, i128) {
+        let market = get_market(&env, market_id);
+        (
+            average_entry_price(market.long_open_interest, market.long_entry_value),
+            average_entry_price(market.short_open_interest, market.short_entry_value),
+        )
+    }
+
+    /// Get live risk metrics for a market, valued at the current oracle price.
+    ///
+    /// Each side is treated as one position at its size-weighted average entry price,
+    /// so `pool_pnl` is the negated unrealized price PnL of all open positions (funding
+    /// and borrowing fees excluded).
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// MarketRisk snapshot with net exposure, average entry prices, pool PnL and utilization
+    pub fn get_market_risk(env: Env, market_id: u32) -> MarketRisk {
+        let market = get_market(&env, market_id);
+
+        let config_manager = get_config_manager(&env);
+        let config_client = config_manager::Client::new(&env, &config_manager);
+        let oracle_client =
+            oracle_integrator::Client::new(&env, &config_client.oracle_integrator());
+        let mark_price = oracle_client.get_price(&market_id);
+
+        let avg_long_entry_price =
+            average_entry_price(market.long_open_interest, market.long_entry_value);
+        let avg_short_entry_price =
+            average_entry_price(market.short_open_interest, market.short_entry_value);
+
+        let traders_pnl = side_pnl(
+            market.long_open_interest,
+            avg_long_entry_price,
+            mark_price,
+            true,
+        ) + side_pnl(
+            market.short_open_interest,
+            avg_short_entry_price,
+            mark_price,
+            false,
+        );
+
+        let busier_side = market.long_open_interest.max(market.short_open_interest);
+        let utilization_bps = (busier_side * 10000)
+            .checked_div(market.max_open_interest)
+            .unwrap_or(0) as u32;
+
+        MarketRisk {
+            market_id,
+            long_open_interest: market.long_open_interest,
+            short_open_interest: market.short_open_interest,
+            net_exposure: market.long_open_interest as i128 - market.short_open_interest as i128,
+            avg_long_entry_price,
+            avg_short_entry_price,
+            mark_price,
+            pool_pnl: -traders_pnl,
+            utilization_bps,
+        }
+    }
+
+    /// Estimate the pool's aggregate payout or gain if every position in a market settled
+    /// at a hypothetical price, so LPs can size tail risk before depositing.
+    ///
+    /// Each side is treated as one position at its size-weighted average entry price, as
+    /// in `get_market_risk()`. Funding and fees are excluded, and trader losses are not
+    /// capped at their collateral, so a loss beyond a position's collateral overstates
+    /// the pool's gain.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `hypothetical_price` - The settlement price (1e7 scaled)
+    ///
+    /// # Returns
+    ///
+    /// PoolSettlementPreview with each side's PnL and the pool's net result
+    ///
+    /// # Panics
+    ///
+    /// Panics if the market does not exist or the price is not positive
+    pub fn preview_pool_settlement(
+        env: Env,
+        market_id: u32,
+        hypothetical_price: i128,
+    ) -> PoolSettlementPreview {
+        if hypothetical_price <= 0 {
+            panic_with_error!(&env, MarketError::InvalidPrice);
+        }
+        let market = get_market(&env, market_id);
+
+        let long_pnl = side_pnl(
+            market.long_open_interest,
+            average_entry_price(market.long_open_interest, market.long_entry_value),
+            hypothetical_price,
+            true,
+        );
+        let short_pnl = side_pnl(
+            market.short_open_interest,
+            average_entry_price(market.short_open_interest, market.short_entry_value),
+            hypothetical_price,
+            false,
+        );
+
+        PoolSettlementPreview {
+            market_id,
+            price: hypothetical_price,
+            long_pnl,
+            short_pnl,
+            pool_pnl: -(long_pnl + short_pnl),
+        }
+    }
+
+    /// Pause a market to prevent new positions from being opened, recording an admin
+    /// pause with no resume estimate.
     ///
     /// # Arguments
     ///
     /// * `admin` - Address of the admin
     /// * `market_id` - The market identifier
     pub fn pause_market(env: Env, admin: Address, market_id: u32) {
+        Self::pause_market_with_reason(env, admin, market_id, PauseReason::Admin, 0);
+    }
+
+    /// Pause a market, recording why and when it is expected to resume for
+    /// `get_market_status()`. Pausing an already paused market updates the reason and
+    /// estimate but keeps the original pause time.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - Address of the admin
+    /// * `market_id` - The market identifier
+    /// * `reason` - Why trading is stopped
+    /// * `expected_resume_at` - Estimated resume timestamp (0 = unknown)
+    pub fn pause_market_with_reason(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        reason: PauseReason,
+        expected_resume_at: u64,
+    ) {
         require_admin(&env, &admin);
 
         let mut market = get_market(&env, market_id);
+        if !market.is_paused {
+            accrue_funding(&env, &mut market);
+            market.paused_at = env.ledger().timestamp();
+        }
         market.is_paused = true;
-        set_market(&env, &market);
+        set_market_checked(&env, &market, 0, 0);
 
-        env.events().publish((symbol_short!("paused"),), market_id);
+        if !env.storage().instance().has(&DataKey::PauseInfo(market_id)) {
+            adjust_storage_count(&env, &DataKey::PausedMarketCount, true);
+        }
+        let pause = PauseInfo {
+            reason,
+            paused_by: admin,
+            paused_at: market.paused_at,
+            expected_resume_at,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::PauseInfo(market_id), &pause);
+
+        MarketPausedEvent {
+            market_id,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
     }
 
     /// Unpause a market to allow new positions.
@@ -493,15 +1521,86 @@ impl MarketManager {
     ///
     /// * `admin` - Address of the admin
     /// * `market_id` - The market identifier
+    ///
+    /// # Panics
+    ///
+    /// If the market's asset has fewer healthy oracle sources than
+    /// `get_min_oracle_sources()` requires
     pub fn unpause_market(env: Env, admin: Address, market_id: u32) {
         require_admin(&env, &admin);
+        require_oracle_sources(&env, market_id);
 
         let mut market = get_market(&env, market_id);
+        // Funding resumes from now; the paused period is not charged
+        accrue_funding(&env, &mut market);
         market.is_paused = false;
-        set_market(&env, &market);
+        market.paused_at = 0;
+        set_market_checked(&env, &market, 0, 0);
+        if env.storage().instance().has(&DataKey::PauseInfo(market_id)) {
+            env.storage()
+                .instance()
+                .remove(&DataKey::PauseInfo(market_id));
+            adjust_storage_count(&env, &DataKey::PausedMarketCount, false);
+        }
 
-        env.events()
-            .publish((symbol_short!("unpaused"),), market_id);
+        MarketUnpausedEvent {
+            market_id,
+            seq: next_event_seq(&env),
+        }
+        .publish(&env);
+    }
+
+    /// Get a market's trading status, including why and by whom it was paused.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// MarketStatus with the pause details and when force settlement opens
+    ///
+    /// # Panics
+    ///
+    /// Panics if the market does not exist
+    pub fn get_market_status(env: Env, market_id: u32) -> MarketStatus {
+        let market = get_market(&env, market_id);
+        if !market.is_paused {
+            return MarketStatus {
+                market_id,
+                is_paused: false,
+                reason: PauseReason::NotPaused,
+                paused_by: None,
+                paused_at: 0,
+                expected_resume_at: 0,
+                settlement_enabled_at: 0,
+            };
+        }
+
+        // Every pause path records PauseInfo
+        let pause: PauseInfo = env
+            .storage()
+            .instance()
+            .get(&DataKey::PauseInfo(market_id))
+            .unwrap();
+
+        let config_manager = get_config_manager(&env);
+        let config_client = config_manager::Client::new(&env, &config_manager);
+        let max_pause_duration = config_client.max_pause_duration();
+
+        MarketStatus {
+            market_id,
+            is_paused: true,
+            reason: pause.reason,
+            paused_by: Some(pause.paused_by),
+            paused_at: pause.paused_at,
+            expected_resume_at: pause.expected_resume_at,
+            settlement_enabled_at: if max_pause_duration == 0 {
+                0
+            } else {
+                market.paused_at + max_pause_duration
+            },
+        }
     }
 
     /// Check if a market is currently paused.
@@ -518,7 +1617,51 @@ impl MarketManager {
         market.is_paused
     }
 
-    /// Check if a new position can be opened based on OI limits.
+    /// Check if the circuit breaker is tripped, i.e. any market is paused with
+    /// `PauseReason::CircuitBreaker`. New positions in every market are then held to
+    /// ConfigManager's circuit breaker leverage cap.
+    ///
+    /// # Returns
+    ///
+    /// True if any market is paused by the circuit breaker
+    pub fn is_circuit_breaker_tripped(env: Env) -> bool {
+        get_market_ids(&env).iter().any(|market_id| {
+            env.storage()
+                .instance()
+                .get::<_, PauseInfo>(&DataKey::PauseInfo(market_id))
+                .is_some_and(|pause| pause.reason == PauseReason::CircuitBreaker)
+        })
+    }
+
+    /// Check if a paused market has exceeded the max pause duration, allowing
+    /// its positions to be force-settled at the last valid TWAP.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// True if the market has been paused longer than the configured maximum
+    pub fn is_settlement_enabled(env: Env, market_id: u32) -> bool {
+        let market = get_market(&env, market_id);
+        if !market.is_paused {
+            return false;
+        }
+
+        let config_manager = get_config_manager(&env);
+        let config_client = config_manager::Client::new(&env, &config_manager);
+        let max_pause_duration = config_client.max_pause_duration();
+
+        // A zero duration disables force settlement entirely
+        if max_pause_duration == 0 {
+            return false;
+        }
+
+        env.ledger().timestamp() - market.paused_at >= max_pause_duration
+    }
+
+    /// Check if a new position can be opened based on pause state, side flags and OI limits.
     ///
     /// # Arguments
     ///
@@ -544,6 +1687,16 @@ impl MarketManager {
             return false;
         }
 
+        // Check the side is open for new positions
+        let side_allowed = if is_long {
+            market.allow_longs
+        } else {
+            market.allow_shorts
+        };
+        if !side_allowed {
+            return false;
+        }
+
         // Check if adding this size would exceed max OI
         let current_oi = if is_long {
             market.long_open_interest
@@ -558,6 +1711,219 @@ impl MarketManager {
 
         true
     }
+
+    /// Get how much more open interest one side of a market can take right now: the
+    /// headroom under the max OI cap, lowered by the hourly OI growth allowance.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `is_long` - True for the long side, false for the short side
+    ///
+    /// # Returns
+    ///
+    /// Additional OI that `can_open_position` and `update_open_interest` would accept
+    /// (0 if the market doesn't exist, is paused or the side is disabled)
+    pub fn get_remaining_oi_capacity(env: Env, market_id: u32, is_long: bool) -> u128 {
+        let market = match env
+            .storage()
+            .instance()
+            .get::<DataKey, Market>(&DataKey::Market(market_id))
+        {
+            Some(m) => m,
+            None => return 0,
+        };
+
+        let side_allowed = if is_long {
+            market.allow_longs
+        } else {
+            market.allow_shorts
+        };
+        if market.is_paused || !side_allowed {
+            return 0;
+        }
+
+        let current_oi = if is_long {
+            market.long_open_interest
+        } else {
+            market.short_open_interest
+        };
+
+        market
+            .max_open_interest
+            .saturating_sub(current_oi)
+            .min(oi_growth_remaining(&env, &market))
+    }
+
+    /// Get the protocol-wide cap on new positions' leverage: ConfigManager's leverage
+    /// step-down and, while the circuit breaker is tripped, its circuit breaker cap.
+    ///
+    /// # Returns
+    ///
+    /// The lower of the active caps (0 = no cap)
+    pub fn get_leverage_step_down(env: Env) -> u32 {
+        leverage_step_down(&env)
+    }
+
+    /// Get the max leverage a new position in a market may use right now: the market's
+    /// limit, lowered by any protocol-wide leverage step-down and, while the circuit
+    /// breaker is tripped, its leverage cap. Open positions keep their leverage.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// The effective max leverage for new positions
+    pub fn get_max_leverage(env: Env, market_id: u32) -> u32 {
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let (_, max_leverage) = config_client.market_leverage_limits(&market_id);
+
+        match leverage_step_down(&env) {
+            0 => max_leverage as u32,
+            cap => cap.min(max_leverage as u32),
+        }
+    }
+
+    /// Get the max leverage a new position on one side of a market may use right now:
+    /// `get_max_leverage()` further lowered by the market's leverage decay as that
+    /// side's open interest nears the OI cap.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `is_long` - True for longs, false for shorts
+    ///
+    /// # Returns
+    ///
+    /// The effective max leverage for new positions on that side
+    pub fn get_effective_max_leverage(env: Env, market_id: u32, is_long: bool) -> u32 {
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let (_, max_leverage) = config_client.market_leverage_limits(&market_id);
+        let max_leverage = match config_client.market_leverage_decay(&market_id) {
+            Some(decay) => perp::decayed_max_leverage(
+                max_leverage,
+                decay.floor_leverage,
+                Self::get_oi_utilization(env.clone(), market_id, is_long) as i128,
+                decay.start_utilization_bps as i128,
+                decay.end_utilization_bps as i128,
+            ) as u32,
+            None => max_leverage as u32,
+        };
+
+        match leverage_step_down(&env) {
+            0 => max_leverage,
+            cap => cap.min(max_leverage),
+        }
+    }
+
+    /// Get the timestamp a market's next funding checkpoint is due.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// The last funding checkpoint plus the funding interval; from then on, keepers
+    /// may call `update_funding_rate()`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the market does not exist
+    pub fn next_funding_time(env: Env, market_id: u32) -> u64 {
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        get_market(&env, market_id).last_funding_update + config_client.funding_interval()
+    }
+
+    /// Estimate the price impact of opening a position, so traders can size orders
+    /// within an acceptable price without trial-and-error reverts.
+    ///
+    /// Positions currently fill at the oracle price with no skew-based impact, so the
+    /// estimate is zero impact at the oracle price; the signature is stable for when an
+    /// impact model is added.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    /// * `size` - Contemplated position size
+    /// * `is_long` - Side of the contemplated trade
+    ///
+    /// # Returns
+    ///
+    /// PriceImpact with the impact in bps and the estimated execution price
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size is zero or there is no valid oracle price
+    pub fn get_price_impact(env: Env, market_id: u32, size: u128, is_long: bool) -> PriceImpact {
+        if size == 0 {
+            panic_with_error!(&env, MarketError::InvalidSize);
+        }
+        // Without skew-based impact both sides fill at the same price
+        let _ = is_long;
+
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let oracle_client =
+            oracle_integrator::Client::new(&env, &config_client.oracle_integrator());
+        PriceImpact {
+            impact_bps: 0,
+            execution_price: oracle_client.get_price(&market_id),
+        }
+    }
+
+    /// Get the data a trading UI header shows for a market in one call: mark and index
+    /// prices, funding, open interest, leverage limit and fees.
+    ///
+    /// Never panics on an oracle outage; the prices are reported as invalid instead so
+    /// the header can still render.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - The market identifier
+    ///
+    /// # Returns
+    ///
+    /// MarketOverview for the market
+    ///
+    /// # Panics
+    ///
+    /// Panics if the market does not exist
+    pub fn get_market_overview(env: Env, market_id: u32) -> MarketOverview {
+        let market = get_market(&env, market_id);
+        let config_client = config_manager::Client::new(&env, &get_config_manager(&env));
+        let oracle_client =
+            oracle_integrator::Client::new(&env, &config_client.oracle_integrator());
+
+        let (price_valid, mark_price) = match oracle_client.try_get_price(&market_id) {
+            Ok(Ok(price)) => (true, price),
+            _ => (false, 0),
+        };
+        let (index_twap, index_updated_at) = match oracle_client.try_get_twap(&market_id) {
+            Ok(Ok(twap)) => twap,
+            _ => (0, 0),
+        };
+
+        MarketOverview {
+            market_id,
+            symbol: market.symbol.clone(),
+            is_paused: market.is_paused,
+            price_valid,
+            mark_price,
+            index_twap,
+            index_updated_at,
+            funding_rate: funding_rate_for(&market),
+            next_funding_time: market.last_funding_update + config_client.funding_interval(),
+            long_open_interest: market.long_open_interest,
+            short_open_interest: market.short_open_interest,
+            max_leverage: Self::get_effective_max_leverage(env.clone(), market_id, true).min(
+                Self::get_effective_max_leverage(env.clone(), market_id, false),
+            ),
+            maker_fee_bps: config_client.maker_fee_bps(),
+            taker_fee_bps: config_client.taker_fee_bps(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,77 +1,124 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Env};
+use soroban_sdk::{
+    symbol_short, testutils::Address as _, testutils::Events as _, testutils::Ledger as _, token,
+    Env, IntoVal, Map, Val,
+};
+
+/// Register a ConfigManager, which every event draws its sequence number from
+fn create_config_manager(env: &Env, admin: &Address) -> Address {
+    env.register(
+        config_manager::WASM,
+        (admin, config_manager::NetworkProfile::Testnet),
+    )
+}
 
 #[test]
-fn test_initialize() {
+fn test_constructor_sets_admin() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
-
-    let contract_id = env.register(MarketManager, ());
+    let config_manager = create_config_manager(&env, &admin);
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
 
-    client.initialize(&config_manager, &admin);
-
-    // Verify initialization worked - should not panic
+    // Only the admin given at deployment manages the contract
+    let other = Address::generate(&env);
+    assert!(client.try_set_position_manager(&other, &other).is_err());
+    client.set_position_manager(&admin, &other);
+    assert_eq!(client.get_markets().len(), 0);
 }
 
 #[test]
-#[should_panic(expected = "already initialized")]
-fn test_initialize_twice_fails() {
+fn test_create_market_success() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    let (long_oi, short_oi) = client.get_open_interest(&0u32);
+    assert_eq!(long_oi, 0);
+    assert_eq!(short_oi, 0);
+
+    let funding_rate = client.get_funding_rate(&0u32);
+    assert_eq!(funding_rate, 0);
 
-    client.initialize(&config_manager, &admin);
-    client.initialize(&config_manager, &admin); // Should panic
+    assert_eq!(client.get_market_symbol(&0u32), symbol_short!("XLMPERP"));
 }
 
 #[test]
-fn test_create_market_success() {
+fn test_get_markets() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
-
-    let (long_oi, short_oi) = client.get_open_interest(&0u32);
-    assert_eq!(long_oi, 0);
-    assert_eq!(short_oi, 0);
-
-    let funding_rate = client.get_funding_rate(&0u32);
-    assert_eq!(funding_rate, 0);
+    assert_eq!(client.get_markets().len(), 0);
+
+    // Market IDs need not be contiguous
+    client.create_market(
+        &admin,
+        &5u32,
+        &symbol_short!("BTCPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    let markets = client.get_markets();
+    assert_eq!(markets.len(), 2);
+    assert_eq!(markets.get(0).unwrap().market_id, 5);
+    assert_eq!(markets.get(0).unwrap().symbol, symbol_short!("BTCPERP"));
+    assert_eq!(markets.get(1).unwrap().market_id, 0);
 }
 
 #[test]
-#[should_panic(expected = "market already exists")]
+#[should_panic(expected = "Error(Contract, #505)")]
 fn test_create_duplicate_market_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128); // Duplicate
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    ); // Duplicate
 }
 
 #[test]
@@ -80,13 +127,11 @@ fn test_set_position_manager() {
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
     let position_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
     client.set_position_manager(&admin, &position_manager);
 
     // If this doesn't panic, it succeeded
@@ -98,22 +143,36 @@ fn test_update_open_interest_increase() {
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
     let position_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
     client.set_position_manager(&admin, &position_manager);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
 
     // Increase long OI
-    client.update_open_interest(&position_manager, &0u32, &true, &1_000_000_000i128);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &100_000_000i128,
+    );
 
     let (long_oi, short_oi) = client.get_open_interest(&0u32);
     assert_eq!(long_oi, 1_000_000_000);
     assert_eq!(short_oi, 0);
+
+    // 0.1% of the OI cap is in use on the long side
+    assert_eq!(client.get_oi_utilization(&0u32, &true), 10);
+    assert_eq!(client.get_oi_utilization(&0u32, &false), 0);
 }
 
 #[test]
@@ -122,19 +181,35 @@ fn test_update_open_interest_decrease() {
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
     let position_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
     client.set_position_manager(&admin, &position_manager);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
 
     // Increase then decrease
-    client.update_open_interest(&position_manager, &0u32, &true, &1_000_000_000i128);
-    client.update_open_interest(&position_manager, &0u32, &true, &-500_000_000i128);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &100_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &-500_000_000i128,
+        &100_000_000i128,
+    );
 
     let (long_oi, short_oi) = client.get_open_interest(&0u32);
     assert_eq!(long_oi, 500_000_000);
@@ -142,43 +217,499 @@ fn test_update_open_interest_decrease() {
 }
 
 #[test]
-#[should_panic(expected = "exceeds max open interest")]
+#[should_panic(expected = "Error(Contract, #507)")]
 fn test_update_open_interest_exceeds_cap() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
     let position_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
     client.set_position_manager(&admin, &position_manager);
-    client.create_market(&admin, &0u32, &1_000_000_000u128, &10000i128); // Max OI = 1B
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000u128,
+        &10000i128,
+    ); // Max OI = 1B
 
     // Try to add 1.1B (exceeds cap)
-    client.update_open_interest(&position_manager, &0u32, &true, &1_100_000_000i128);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_100_000_000i128,
+        &100_000_000i128,
+    );
 }
 
 #[test]
-fn test_pause_unpause_market() {
+fn test_record_fee_accrues_per_market() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.create_market(
+        &admin,
+        &1u32,
+        &symbol_short!("BTCPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    client.record_fee(&position_manager, &0u32, &FeeKind::Borrowing, &300u128);
+    client.record_fee(&position_manager, &0u32, &FeeKind::Borrowing, &200u128);
+    client.record_fee(&position_manager, &0u32, &FeeKind::Liquidation, &1_000u128);
+    client.record_fee(&position_manager, &1u32, &FeeKind::EarlyClose, &50u128);
+
+    assert_eq!(
+        client.get_market_fees(&0u32),
+        MarketFees {
+            borrowing: 500,
+            early_close: 0,
+            liquidation: 1_000,
+        }
+    );
+    assert_eq!(
+        client.get_market_fees(&1u32),
+        MarketFees {
+            borrowing: 0,
+            early_close: 50,
+            liquidation: 0,
+        }
+    );
+    assert_eq!(client.get_market_fees(&2u32), MarketFees::default());
+}
+
+#[test]
+fn test_storage_usage_counts_records() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(
+        MarketManager,
+        (&create_config_manager(&env, &admin), &admin),
+    );
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    for (market_id, symbol) in [
+        (0u32, symbol_short!("XLMPERP")),
+        (1, symbol_short!("BTCPERP")),
+    ] {
+        client.create_market(
+            &admin,
+            &market_id,
+            &symbol,
+            &1_000_000_000_000u128,
+            &10000i128,
+        );
+    }
+
+    client.record_fee(&position_manager, &0u32, &FeeKind::Borrowing, &300u128);
+    client.record_fee(&position_manager, &0u32, &FeeKind::EarlyClose, &50u128);
+    client.pause_market(&admin, &1u32);
+    client.pause_market(&admin, &1u32);
+    assert_eq!(
+        client.get_storage_usage(),
+        StorageUsage {
+            markets: 2,
+            paused_markets: 1,
+            fee_records: 1,
+        }
+    );
+
+    client.unpause_market(&admin, &1u32);
+    client.unpause_market(&admin, &1u32);
+    assert_eq!(client.get_storage_usage().paused_markets, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #503)")]
+fn test_record_fee_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    client.record_fee(&admin, &0u32, &FeeKind::Borrowing, &300u128);
+}
+
+#[test]
+fn test_avg_entry_prices_track_open_and_close() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &10_000_000_000u128,
+        &10000i128,
+    );
+    assert_eq!(client.get_avg_entry_prices(&0u32), (0, 0));
+
+    // Longs of 100 tokens at $1.00 and 300 tokens at $1.20
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &100_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &3_000_000_000i128,
+        &120_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &500_000_000i128,
+        &90_000_000i128,
+    );
+    assert_eq!(
+        client.get_avg_entry_prices(&0u32),
+        (115_000_000, 90_000_000)
+    );
+
+    // Closing the $1.00 long leaves only the $1.20 entries
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &-1_000_000_000i128,
+        &100_000_000i128,
+    );
+    assert_eq!(
+        client.get_avg_entry_prices(&0u32),
+        (120_000_000, 90_000_000)
+    );
+
+    // A fully closed side resets
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &-500_000_000i128,
+        &90_000_000i128,
+    );
+    assert_eq!(client.get_avg_entry_prices(&0u32), (120_000_000, 0));
+}
+
+#[test]
+fn test_get_market_risk() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(&env, &config_manager);
 
-    client.initialize(&config_manager, &admin);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
+    let oracle = env.register(oracle_integrator::WASM, (&config_manager,));
+    let oracle_client = oracle_integrator::Client::new(&env, &oracle);
+    oracle_client.set_fixed_price_mode(&admin, &true);
+    config_client.set_oracle_integrator(&admin, &oracle);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &10_000_000_000u128,
+        &10000i128,
+    );
+
+    // Longs of 100 tokens at $1.00 and $1.20, one 50 token short at $1.00
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &100_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &120_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &500_000_000i128,
+        &100_000_000i128,
+    );
+
+    // Mark at $1.10: longs are flat, the short is down 5 tokens
+    let mut prices = Map::new(&env);
+    prices.set(0u32, 110_000_000i128);
+    oracle_client.set_test_mode(&admin, &true, &prices);
+
+    let risk = client.get_market_risk(&0u32);
+    assert_eq!(risk.net_exposure, 1_500_000_000);
+    assert_eq!(risk.avg_long_entry_price, 110_000_000);
+    assert_eq!(risk.avg_short_entry_price, 100_000_000);
+    assert_eq!(risk.mark_price, 110_000_000);
+    assert_eq!(risk.pool_pnl, 50_000_000);
+    assert_eq!(risk.utilization_bps, 2000);
+
+    // Closing the $1.20 long leaves the $1.00 long up 10 tokens
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &-1_000_000_000i128,
+        &120_000_000i128,
+    );
+
+    let risk = client.get_market_risk(&0u32);
+    assert_eq!(risk.net_exposure, 500_000_000);
+    assert_eq!(risk.avg_long_entry_price, 100_000_000);
+    assert_eq!(risk.pool_pnl, -50_000_000);
+    assert_eq!(risk.utilization_bps, 1000);
+}
+
+#[test]
+fn test_preview_pool_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &10_000_000_000u128,
+        &10000i128,
+    );
+
+    // 200 tokens long and 50 tokens short, all entered at $1.00
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &2_000_000_000i128,
+        &100_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &500_000_000i128,
+        &100_000_000i128,
+    );
+
+    // A 50% rally costs the pool 100 tokens to longs, less 25 tokens from shorts
+    let preview = client.preview_pool_settlement(&0u32, &150_000_000i128);
+    assert_eq!(preview.long_pnl, 1_000_000_000);
+    assert_eq!(preview.short_pnl, -250_000_000);
+    assert_eq!(preview.pool_pnl, -750_000_000);
+
+    // A 50% crash is the mirror image
+    let preview = client.preview_pool_settlement(&0u32, &50_000_000i128);
+    assert_eq!(preview.pool_pnl, 750_000_000);
+
+    assert!(client.try_preview_pool_settlement(&0u32, &0i128).is_err());
+}
+
+/// Market with a 1,000 token pool behind it and OI growth capped at 10% of TVL per hour
+fn setup_oi_growth_limited_market(env: &Env) -> (MarketManagerClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let position_manager = Address::generate(env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(env, &config_manager);
+
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_admin = token::StellarAssetClient::new(env, &token_contract.address());
+
+    let pool = env.register(
+        liquidity_pool::WASM,
+        (&config_manager, &token_contract.address()),
+    );
+    let pool_client = liquidity_pool::Client::new(env, &pool);
+    token_admin.mint(&admin, &10_000_000_000);
+    pool_client.deposit(&admin, &10_000_000_000);
+    config_client.set_liquidity_pool(&admin, &pool);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.set_oi_growth_limit(&admin, &0u32, &1000u32);
+
+    (client, position_manager)
+}
+
+#[test]
+fn test_oi_growth_limit_replenishes_over_window() {
+    let env = Env::default();
+    let (client, position_manager) = setup_oi_growth_limited_market(&env);
+
+    // Allowance is 100 tokens per hour across both sides
+    assert_eq!(client.get_oi_growth_remaining(&0u32), 1_000_000_000);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &600_000_000i128,
+        &100_000_000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &400_000_000i128,
+        &100_000_000i128,
+    );
+    assert_eq!(client.get_oi_growth_remaining(&0u32), 0);
+
+    // Decreases don't free up allowance
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &-600_000_000i128,
+        &100_000_000i128,
+    );
+    assert_eq!(client.get_oi_growth_remaining(&0u32), 0);
+
+    // Half an hour later half the allowance is back
+    env.ledger().with_mut(|li| li.timestamp += 1800);
+    assert_eq!(client.get_oi_growth_remaining(&0u32), 500_000_000);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &500_000_000i128,
+        &100_000_000i128,
+    );
+    assert_eq!(client.get_oi_growth_remaining(&0u32), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #509)")]
+fn test_oi_growth_limit_exceeded() {
+    let env = Env::default();
+    let (client, position_manager) = setup_oi_growth_limited_market(&env);
+
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &600_000_000i128,
+        &100_000_000i128,
+    );
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &600_000_000i128,
+        &100_000_000i128,
+    );
+}
+
+#[test]
+fn test_pause_unpause_market() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
 
     assert!(!client.is_market_paused(&0u32));
 
     client.pause_market(&admin, &0u32);
+    // Indexers see the same ("paused",) topic and [market_id, seq] data as ever
+    let events = env.events().all();
+    let topics: soroban_sdk::Vec<Val> = (symbol_short!("paused"),).into_val(&env);
+    let seq = config_manager::Client::new(&env, &config_manager).get_event_seq();
+    let data: Val = (0u32, seq).into_val(&env);
+    assert!(events.contains((contract_id.clone(), topics, data)));
     assert!(client.is_market_paused(&0u32));
 
     client.unpause_market(&admin, &0u32);
@@ -186,18 +717,162 @@ fn test_pause_unpause_market() {
 }
 
 #[test]
-fn test_can_open_position_when_paused() {
+fn test_settlement_enabled_after_max_pause_duration() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(&env, &config_manager);
+    config_client.set_max_pause_duration(&admin, &3600u64);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    // Active markets are never settleable
+    assert!(!client.is_settlement_enabled(&0u32));
+
+    client.pause_market(&admin, &0u32);
+    assert!(!client.is_settlement_enabled(&0u32));
+
+    // Deadline passes while still paused
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert!(client.is_settlement_enabled(&0u32));
+
+    // Unpausing closes the settlement window
+    client.unpause_market(&admin, &0u32);
+    assert!(!client.is_settlement_enabled(&0u32));
+}
+
+#[test]
+fn test_market_status_reports_pause_reason() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(&env, &config_manager);
+    config_client.set_max_pause_duration(&admin, &3600u64);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    let status = client.get_market_status(&0u32);
+    assert!(!status.is_paused);
+    assert_eq!(status.reason, PauseReason::NotPaused);
+
+    let paused_at = env.ledger().timestamp();
+    client.pause_market_with_reason(
+        &admin,
+        &0u32,
+        &PauseReason::OracleFailure,
+        &(paused_at + 600),
+    );
+    assert_eq!(
+        client.get_market_status(&0u32),
+        MarketStatus {
+            market_id: 0,
+            is_paused: true,
+            reason: PauseReason::OracleFailure,
+            paused_by: Some(admin.clone()),
+            paused_at,
+            expected_resume_at: paused_at + 600,
+            settlement_enabled_at: paused_at + 3600,
+        }
+    );
+
+    // Escalating the reason keeps the original pause time
+    env.ledger().with_mut(|li| li.timestamp += 300);
+    client.pause_market(&admin, &0u32);
+    let status = client.get_market_status(&0u32);
+    assert_eq!(status.reason, PauseReason::Admin);
+    assert_eq!(status.paused_at, paused_at);
+    assert_eq!(status.expected_resume_at, 0);
+
+    client.unpause_market(&admin, &0u32);
+    let status = client.get_market_status(&0u32);
+    assert_eq!(status.reason, PauseReason::NotPaused);
+    assert_eq!(status.paused_by, None);
+}
+
+#[test]
+fn test_circuit_breaker_tripped_by_breaker_pauses() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    for (market_id, symbol) in [
+        (0u32, symbol_short!("XLMPERP")),
+        (1u32, symbol_short!("BTCPERP")),
+    ] {
+        client.create_market(
+            &admin,
+            &market_id,
+            &symbol,
+            &1_000_000_000_000u128,
+            &10000i128,
+        );
+    }
+    assert!(!client.is_circuit_breaker_tripped());
+
+    // Only circuit breaker pauses trip it
+    client.pause_market(&admin, &0u32);
+    assert!(!client.is_circuit_breaker_tripped());
 
-    client.initialize(&config_manager, &admin);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
+    client.pause_market_with_reason(&admin, &1u32, &PauseReason::CircuitBreaker, &0u64);
+    assert!(client.is_circuit_breaker_tripped());
+
+    client.unpause_market(&admin, &1u32);
+    assert!(!client.is_circuit_breaker_tripped());
+}
+
+#[test]
+fn test_can_open_position_when_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
 
     assert!(client.can_open_position(&0u32, &true, &1_000_000u128));
 
@@ -211,31 +886,128 @@ fn test_can_open_position_exceeds_oi() {
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
-
-    client.initialize(&config_manager, &admin);
-    client.create_market(&admin, &0u32, &1_000_000_000u128, &10000i128); // Max OI = 1B
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000u128,
+        &10000i128,
+    ); // Max OI = 1B
 
     assert!(!client.can_open_position(&0u32, &true, &1_100_000_000u128)); // Exceeds cap
     assert!(client.can_open_position(&0u32, &true, &900_000_000u128)); // Within cap
 }
 
 #[test]
-fn test_get_cumulative_funding() {
+fn test_one_sided_market() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &1_000i128,
+        &100_000_000i128,
+    );
+
+    // Shorts disabled: only longs can open
+    client.set_market_sides(&admin, &0u32, &true, &false);
+    assert!(client.can_open_position(&0u32, &true, &1_000u128));
+    assert!(!client.can_open_position(&0u32, &false, &1_000u128));
+
+    // Existing shorts can still close
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &-1_000i128,
+        &100_000_000i128,
+    );
+    assert_eq!(client.get_open_interest(&0u32), (0, 0));
+
+    client.set_market_sides(&admin, &0u32, &true, &true);
+    assert!(client.can_open_position(&0u32, &false, &1_000u128));
+}
+
+#[test]
+fn test_remaining_oi_capacity() {
     let env = Env::default();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
-    let config_manager = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
 
-    let contract_id = env.register(MarketManager, ());
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
     let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000u128,
+        &10000i128,
+    ); // Max OI = 1B per side
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &400_000_000i128,
+        &100_000_000i128,
+    );
+
+    assert_eq!(client.get_remaining_oi_capacity(&0u32, &true), 600_000_000);
+    assert_eq!(
+        client.get_remaining_oi_capacity(&0u32, &false),
+        1_000_000_000
+    );
+    assert!(client.can_open_position(&0u32, &true, &600_000_000u128));
+    assert!(!client.can_open_position(&0u32, &true, &600_000_001u128));
+
+    // Disabled sides, paused and unknown markets have no capacity
+    client.set_market_sides(&admin, &0u32, &true, &false);
+    assert_eq!(client.get_remaining_oi_capacity(&0u32, &false), 0);
+    client.pause_market(&admin, &0u32);
+    assert_eq!(client.get_remaining_oi_capacity(&0u32, &true), 0);
+    assert_eq!(client.get_remaining_oi_capacity(&7u32, &true), 0);
+}
 
-    client.initialize(&config_manager, &admin);
-    client.create_market(&admin, &0u32, &1_000_000_000_000u128, &10000i128);
+#[test]
+fn test_get_cumulative_funding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
 
     // Initially should be 0
     let cumulative_long = client.get_cumulative_funding(&0u32, &true);
@@ -245,6 +1017,478 @@ fn test_get_cumulative_funding() {
     assert_eq!(cumulative_short, 0);
 }
 
-// Note: Comprehensive funding rate testing requires setting up ConfigManager mock
-// which is complex in unit tests. The funding rate logic is tested through
-// the formula implementation and will be verified in integration tests.
+#[test]
+fn test_preview_funding_after() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &100_000_000i128,
+    );
+
+    // Longs only: full imbalance pays the base rate
+    assert_eq!(client.get_funding_rate(&0u32), 100);
+    // A matching short balances the market
+    assert_eq!(
+        client.preview_funding_after(&0u32, &false, &1_000_000_000i128),
+        0
+    );
+    // A 3x short flips the imbalance to -50%: 100 * 0.5^2 = 25 bps, paid by shorts
+    assert_eq!(
+        client.preview_funding_after(&0u32, &false, &3_000_000_000i128),
+        -25
+    );
+    // Closing half the longs leaves the market fully long
+    assert_eq!(
+        client.preview_funding_after(&0u32, &true, &-500_000_000i128),
+        100
+    );
+
+    // Previews don't change the market
+    assert_eq!(client.get_open_interest(&0u32), (1_000_000_000, 0));
+    assert_eq!(client.get_funding_rate(&0u32), 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #508)")]
+fn test_preview_funding_after_below_zero_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    client.preview_funding_after(&0u32, &true, &-1i128);
+}
+
+#[test]
+fn test_funding_accrues_for_time_open() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    // A fully one-sided market pays the base rate of 100 bps per hour
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000i128,
+        &100_000_000i128,
+    );
+    assert_eq!(client.get_funding_rate(&0u32), 100);
+
+    // Funding accrues without a keeper checkpoint
+    env.ledger().with_mut(|li| li.timestamp += 30);
+    assert_eq!(client.get_cumulative_funding(&0u32, &true), 3_000);
+
+    // Closing before the next checkpoint still settles the 30 seconds held
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &-1_000i128,
+        &100_000_000i128,
+    );
+    env.ledger().with_mut(|li| li.timestamp += 30);
+    assert_eq!(client.get_cumulative_funding(&0u32, &true), 3_000);
+
+    // A checkpoint after the close charges nothing for the empty period
+    client.update_funding_rate(&admin, &0u32);
+    assert_eq!(client.get_cumulative_funding(&0u32, &true), 3_000);
+    assert_eq!(client.get_cumulative_funding(&0u32, &false), 0);
+
+    // Paused periods are not charged
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &1_000i128,
+        &100_000_000i128,
+    );
+    client.pause_market(&admin, &0u32);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    client.unpause_market(&admin, &0u32);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    assert_eq!(client.get_cumulative_funding(&0u32, &false), 1_000);
+}
+
+#[test]
+fn test_min_oracle_sources_gates_market_going_live() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(&env, &config_manager);
+
+    // A test-mode oracle reports one healthy source per market
+    let oracle = env.register(oracle_integrator::WASM, (&config_manager,));
+    let oracle_client = oracle_integrator::Client::new(&env, &oracle);
+    oracle_client.set_test_mode(&admin, &true, &Map::new(&env));
+    config_client.set_oracle_integrator(&admin, &oracle);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    assert_eq!(client.get_min_oracle_sources(), 0);
+
+    client.set_min_oracle_sources(&admin, &2);
+    assert!(client
+        .try_create_market(
+            &admin,
+            &0u32,
+            &symbol_short!("XLMPERP"),
+            &1_000_000_000_000u128,
+            &10000i128,
+        )
+        .is_err());
+
+    client.set_min_oracle_sources(&admin, &1);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    // A paused market stays paused until enough sources are healthy again
+    client.pause_market(&admin, &0u32);
+    client.set_min_oracle_sources(&admin, &2);
+    assert!(client.try_unpause_market(&admin, &0u32).is_err());
+    assert!(client.is_market_paused(&0u32));
+
+    client.set_min_oracle_sources(&admin, &1);
+    client.unpause_market(&admin, &0u32);
+    assert!(!client.is_market_paused(&0u32));
+}
+
+#[test]
+fn test_funding_update_cost_independent_of_position_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+
+    let open = |is_long: bool| {
+        client.update_open_interest(
+            &position_manager,
+            &0u32,
+            &is_long,
+            &1_000i128,
+            &100_000_000i128,
+        );
+    };
+
+    // One open position
+    open(true);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    client.update_funding_rate(&admin, &0u32);
+    let single = env.cost_estimate().resources();
+
+    // 10,000 open positions, two longs for every short
+    for i in 1..10_000u32 {
+        open(i % 3 != 0);
+    }
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    client.update_funding_rate(&admin, &0u32);
+    let many = env.cost_estimate().resources();
+
+    assert_eq!(many.disk_read_entries, single.disk_read_entries);
+    assert_eq!(many.memory_read_entries, single.memory_read_entries);
+    assert_eq!(many.write_entries, single.write_entries);
+    assert!(client.get_cumulative_funding(&0u32, &true) > 0);
+}
+
+#[test]
+fn test_warp_funding_accrues_intervals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(&env, &config_manager);
+
+    let oracle = env.register(oracle_integrator::WASM, (&config_manager,));
+    let oracle_client = oracle_integrator::Client::new(&env, &oracle);
+    config_client.set_oracle_integrator(&admin, &oracle);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000i128,
+        &100_000_000i128,
+    );
+
+    // Only available while the oracle runs in test mode
+    assert!(client.try_warp_funding(&admin, &0u32, &10u32).is_err());
+    oracle_client.set_test_mode(&admin, &true, &Map::new(&env));
+
+    // 1,000 one-minute intervals at 100 bps per hour, plus 30 real seconds
+    env.ledger().with_mut(|li| li.timestamp += 30);
+    assert_eq!(client.warp_funding(&admin, &0u32, &1_000u32), 60_000);
+    assert_eq!(client.get_cumulative_funding(&0u32, &true), 6_003_000);
+    assert_eq!(client.get_cumulative_funding(&0u32, &false), 0);
+
+    // The ledger clock is untouched
+    let state = client.get_market_accrual_state(&0u32);
+    assert_eq!(state.last_funding_update, env.ledger().timestamp());
+}
+
+#[test]
+fn test_get_market_accrual_state() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let position_manager = Address::generate(&env);
+
+    let config_manager = env.register(
+        config_manager::WASM,
+        (&admin, config_manager::NetworkProfile::Testnet),
+    );
+    let config_client = config_manager::Client::new(&env, &config_manager);
+    config_client.set_borrow_rate_per_second(&admin, &5);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    let created_at = env.ledger().timestamp();
+
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000i128,
+        &100_000_000i128,
+    );
+    client.record_fee(&position_manager, &0u32, &FeeKind::Borrowing, &300u128);
+    client.record_fee(&position_manager, &0u32, &FeeKind::Liquidation, &700u128);
+
+    // Reports the last checkpoint, not funding accrued since
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let state = client.get_market_accrual_state(&0u32);
+    assert_eq!(state.last_funding_update, created_at);
+    assert_eq!(state.cumulative_funding_long, 0);
+    assert_eq!(state.cumulative_borrow_index, 300);
+
+    client.update_funding_rate(&admin, &0u32);
+    let state = client.get_market_accrual_state(&0u32);
+    assert_eq!(
+        state,
+        MarketAccrualState {
+            market_id: 0,
+            last_funding_update: created_at + 60,
+            funding_rate: 100,
+            cumulative_funding_long: 6_000,
+            cumulative_funding_short: 0,
+            cumulative_borrow_index: 300,
+            fees: MarketFees {
+                borrowing: 300,
+                early_close: 0,
+                liquidation: 700,
+            },
+            total_fees: 1_000,
+        }
+    );
+}
+
+#[test]
+fn test_invariant_checks_pass_on_normal_flow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let config_manager = create_config_manager(&env, &admin);
+    let position_manager = Address::generate(&env);
+
+    let contract_id = env.register(MarketManager, (&config_manager, &admin));
+    let client = MarketManagerClient::new(&env, &contract_id);
+    client.set_position_manager(&admin, &position_manager);
+    client.create_market(
+        &admin,
+        &0u32,
+        &symbol_short!("XLMPERP"),
+        &1_000_000_000_000u128,
+        &10000i128,
+    );
+    assert!(!client.invariant_checks_enabled());
+    client.set_invariant_checks(&admin, &true);
+    assert!(client.invariant_checks_enabled());
+
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &1_000_000_000i128,
+        &100_000_000i128,
+    );
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    client.update_funding_rate(&admin, &0u32);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &false,
+        &400_000_000i128,
+        &100_000_000i128,
+    );
+    client.pause_market(&admin, &0u32);
+    client.unpause_market(&admin, &0u32);
+    client.update_open_interest(
+        &position_manager,
+        &0u32,
+        &true,
+        &-1_000_000_000i128,
+        &100_000_000i128,
+    );
+
+    assert_eq!(client.get_open_interest(&0u32), (0, 400_000_000));
+    assert_eq!(client.get_cumulative_funding(&0u32, &true), 6_000);
+}
+
+#[test]
+#[should_panic(expected = "invariant violated: OI delta does not match size delta")]
+fn test_invariant_oi_delta_mismatch() {
+    let env = Env::default();
+    let before = Market {
+        market_id: 0,
+        symbol: symbol_short!("XLMPERP"),
+        max_open_interest: 1_000_000,
+        long_open_interest: 1_000,
+        short_open_interest: 0,
+        long_entry_value: 0,
+        short_entry_value: 0,
+        funding_rate: 0,
+        last_funding_update: env.ledger().timestamp(),
+        cumulative_funding_long: 0,
+        cumulative_funding_short: 0,
+        is_paused: false,
+        paused_at: 0,
+        base_funding_rate: 100,
+        max_funding_rate: 10000,
+        max_oi_growth_bps: 0,
+        oi_growth_used: 0,
+        oi_growth_updated_at: 0,
+        allow_longs: true,
+        allow_shorts: true,
+    };
+    let mut after = before.clone();
+    after.long_open_interest = 1_500;
+
+    // Only 400 was added
+    check_market_invariants(&before, &after, 400, 0);
+}
+
+#[test]
+#[should_panic(expected = "invariant violated: funding index decreased")]
+fn test_invariant_funding_index_decreased() {
+    let env = Env::default();
+    let before = Market {
+        market_id: 0,
+        symbol: symbol_short!("XLMPERP"),
+        max_open_interest: 1_000_000,
+        long_open_interest: 0,
+        short_open_interest: 0,
+        long_entry_value: 0,
+        short_entry_value: 0,
+        funding_rate: 0,
+        last_funding_update: env.ledger().timestamp(),
+        cumulative_funding_long: 5_000,
+        cumulative_funding_short: 0,
+        is_paused: false,
+        paused_at: 0,
+        base_funding_rate: 100,
+        max_funding_rate: 10000,
+        max_oi_growth_bps: 0,
+        oi_growth_used: 0,
+        oi_growth_updated_at: 0,
+        allow_longs: true,
+        allow_shorts: true,
+    };
+    let mut after = before.clone();
+    after.cumulative_funding_long = 4_000;
+
+    check_market_invariants(&before, &after, 0, 0);
+}